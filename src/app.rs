@@ -0,0 +1,2464 @@
+use std::{
+    collections::HashSet,
+    io::{stdout, Stdout},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::TryRecvError,
+    },
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor::{MoveTo, Show},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyEventState, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use tui::{backend::CrosstermBackend, layout::Rect, widgets::ListState, Terminal};
+
+use crate::{
+    args::Args,
+    browser::{Browser, Preview},
+    cache,
+    caps::Caps,
+    cargo,
+    deleter::{Cleanup, Deleter, Finished, Job, Removal},
+    disk::{self, Filesystem},
+    duplicates,
+    editors::{self, OpenProjects},
+    error::{self, Error},
+    excluded, guard,
+    history::{self, History},
+    i18n::{t, tf},
+    input::{Input, Message},
+    journal::Interrupted,
+    keys, remote,
+    report::{self, Report, ReportFormat},
+    scan::{self, Entry, Found, Kind},
+    size,
+    state::{Batch, Confirm, Duplicates, Events, Messages, Mode, Profiles, Sort, State, Undo},
+    trace::exit,
+    trashcan,
+    tree::{self, Tree},
+    ui::{draw, root_of, TOMBSTONE},
+    watch,
+};
+
+/// The interface on the terminal for `args`, until quit. Targets the
+/// `interrupted` bulk delete didn't get to are marked when found, deletes are
+/// journaled in `journal`.
+pub fn run(
+    args: &Args,
+    caps: Caps,
+    journal: Option<PathBuf>,
+    interrupted: Option<Interrupted>,
+) -> Result<(), Error> {
+    // Review later flags and notes, kept with scan history
+    let saved = args
+        .history
+        .then(|| history::path().map(|path| History::load(&path)))
+        .map(Option::unwrap_or_default);
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+
+    // Create state
+    let mut state = State {
+        mode: Mode::List,
+        roots: args.paths.clone(),
+        scan_options: args.scan_options(home.as_deref()),
+        results: vec![],
+        scan: None,
+        started: Instant::now(),
+        time: 0.0,
+        cancelled: false,
+        warnings: vec![],
+        salvage: args.salvage,
+        permanent: args.permanent,
+        cleanup: args.cleanup(),
+        cargo_clean_args: args.cargo_clean_args.clone(),
+        checker: args
+            .verify
+            .then(|| cargo::Checker::spawn(args.verify_command.clone())),
+        confirm_single: args.confirm_single,
+        preview: args.preview,
+        preview_of: None,
+        filter: String::new(),
+        older_than: args.older_than,
+        active_within: args.active_within,
+        min_size: args.min_size,
+        hide_small: args.min_size.is_some(),
+        sort: args.sort,
+        sort_reversed: false,
+        group_by_filesystem: args.group_filesystems,
+        show_names: args.names,
+        pinned: HashSet::new(),
+        resume: interrupted
+            .iter()
+            .flat_map(|i| i.in_flight.iter().chain(&i.remaining).cloned())
+            .collect(),
+        deleter: Deleter::spawn(args.paths.clone(), args.cargo_clean_args.clone(), journal),
+        batch: None,
+        filesystems: vec![],
+        keys: args.keys.clone(),
+        tombstones: args.tombstones,
+        size_colors: args.size_colors,
+        theme: args.theme,
+        excluded: excluded::path().map_or(vec![], |path| excluded::load(&path)),
+        tree: None,
+        open_projects: args.open_projects,
+        editor: editors::editor(&args.editor),
+        // Scans limited by age can't be compared, or shown for a full one
+        cache: (args.cache && args.older_than.is_none())
+            .then(|| history::key(&args.paths, &args.types)),
+        cached: HashSet::new(),
+        history: (args.history && args.older_than.is_none())
+            .then(|| history::key(&args.paths, &args.types)),
+        later: saved.as_ref().map(History::later),
+        notes: saved.as_ref().map(History::notes),
+        changes: None,
+        trend: vec![],
+        report: args.report.clone().map(|dest| (args.report_format, dest)),
+        deleted: vec![],
+        trashed: vec![],
+        undo: vec![],
+        show_details: false,
+        details: None,
+        messages: Messages::default(),
+        free_at_start: disk::free_space(&args.paths[0]),
+        max_total: args.max_total,
+        freed: 0,
+        caps,
+        failures: vec![],
+    };
+
+    if let Some(warning) = trashcan::deletes_instead(&state.roots).filter(|_| !state.permanent) {
+        state.messages.error(t(warning).to_string());
+    }
+    if let Some(i) = &interrupted {
+        state.messages.error(tf(
+            "Last bulk delete didn't finish: {} done, {} interrupted, {} not started, those are marked when found",
+            &[&i.done.len(), &i.in_flight.len(), &i.remaining.len()],
+        ));
+    }
+
+    // Scan in the background, results show up as they are found
+    state.scan = Some(spawn_scan(&state));
+
+    // Projects that are open in an editor get flagged
+    let open = if state.open_projects == OpenProjects::Off {
+        vec![]
+    } else {
+        editors::open_folders()
+    };
+
+    // Create stateful widget state
+    let mut events = Events::new(vec![]);
+    show_cached(&mut state, &mut events, &open);
+
+    // Give the terminal back however the interface ends: returning with an
+    // error drops the guard, a panic runs the hook before printing
+    let _guard = TerminalGuard(state.caps);
+    let caps = state.caps;
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Background threads panicking don't end the interface
+        if std::thread::current().name() == Some("main") {
+            reset_terminal(caps);
+        }
+        hook(info);
+    }));
+
+    // setup terminal, leaving out what it can't do
+    let backend = CrosstermBackend::new(stdout());
+    let mut terminal = Terminal::new(backend)?;
+    enter(&mut terminal, state.caps)?;
+    // Keys are read in raw mode only, so once the terminal is set up
+    let input = Input::spawn(Duration::from_millis(100));
+
+    // Draw initial screen
+    terminal.draw(|f| draw(f, &mut state, &mut events))?;
+
+    // Events come from the input thread, with a tick every 100 millis none
+    // does. Draw again after an event, and on a tick when something changed,
+    // like the spinner while scanning.
+    loop {
+        let mut redraw = receive(&mut state, &mut events, &open);
+        // Keep fading tombstones until they are gone
+        redraw |= !events.tombstones.is_empty();
+        events.bury(TOMBSTONE);
+        redraw |= state.messages.tick();
+        redraw |= checked(&mut state);
+        redraw |= compared(&mut state);
+        redraw |= deleted(&mut state, &mut events, false);
+
+        // Quit if not results
+        if state.scan.is_none() && state.results.is_empty() && state.deleted.is_empty() {
+            restore(&mut terminal, state.caps)?;
+            println!("{}", t("No target folders found!"));
+            exit(0);
+        }
+
+        if let Message::Event(event) = input.next() {
+            // Only the terminal fails here, it can't be trusted anymore
+            let handled = handle_event(&event, &mut terminal, &input, &mut state, &mut events);
+            if let Err(e) = handled {
+                let _ = restore(&mut terminal, state.caps);
+                println!("Error: {e}");
+                exit(2);
+            }
+            redraw = true;
+        }
+        if redraw {
+            terminal.draw(|f| draw(f, &mut state, &mut events))?;
+        }
+    }
+}
+
+/// `rclin watch`: how fast targets grow, shown until quit
+pub fn watch(args: &Args, caps: Caps) -> Result<(), Error> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let interval = Duration::from_secs(args.watch_interval.max(1) as u64);
+    let _guard = TerminalGuard(caps);
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+    enter(&mut terminal, caps)?;
+    let watched = watch::watch(
+        &mut terminal,
+        args.paths.clone(),
+        args.scan_options(home.as_deref()),
+        interval,
+        args.theme,
+        caps,
+    );
+    restore(&mut terminal, caps)?;
+    watched
+}
+
+// Tell how the checks of --verify went. Returns true if any finished.
+fn checked(state: &mut State) -> bool {
+    let Some(checker) = &mut state.checker else {
+        return false;
+    };
+    let results: Vec<_> = checker.results.try_iter().collect();
+    let finished = results.len();
+    checker.pending -= finished;
+
+    for (path, result) in results {
+        match result {
+            Ok(()) => state
+                .messages
+                .push(tf("{} still builds", &[&path.display()])),
+            Err(source) => fail(state, Error::Check { path, source }),
+        }
+    }
+    finished > 0
+}
+
+// Take in the comparison of dependencies when it's done. Returns true if it
+// is.
+fn compared(state: &mut State) -> bool {
+    let Mode::Duplicates(duplicates) = &mut state.mode else {
+        return false;
+    };
+    let Some(receiver) = &duplicates.receiver else {
+        return false;
+    };
+    match receiver.try_recv() {
+        Ok(report) => {
+            duplicates
+                .state
+                .select((!report.duplicates.is_empty()).then_some(0));
+            duplicates.report = Some(report);
+        }
+        Err(TryRecvError::Empty) => return false,
+        Err(TryRecvError::Disconnected) => (),
+    }
+    duplicates.receiver = None;
+    true
+}
+
+// Compare the dependency artifacts of the Rust targets found in the
+// background, showing what they have in common once done
+fn compare_dependencies(state: &mut State) {
+    let targets: Vec<PathBuf> = state
+        .results
+        .iter()
+        .filter(|e| e.kind == Kind::Rust)
+        .map(|e| PathBuf::from(&e.path))
+        .collect();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let targets: Vec<&Path> = targets.iter().map(PathBuf::as_path).collect();
+        let _ = sender.send(duplicates::find(&targets));
+    });
+    state.mode = Mode::Duplicates(Duplicates {
+        receiver: Some(receiver),
+        report: None,
+        state: ListState::default(),
+    });
+}
+
+// Pin the selected entry to the top of the list, keeping it out of bulk
+// actions, or unpin it
+fn toggle_pin(state: &mut State, events: &mut Events) {
+    let Some(path) = events.selected().map(|e| e.path.clone()) else {
+        return;
+    };
+    if state.pinned.remove(&path) {
+        state.messages.push(tf("Unpinned {}", &[&path.display()]));
+    } else {
+        events.marked.remove(&path);
+        state.messages.push(tf("Pinned {}", &[&path.display()]));
+        state.pinned.insert(path);
+    }
+    order(state, events);
+}
+
+// Flag the selected entry for review in a later session, or unflag it, and
+// save that right away
+fn toggle_later(state: &mut State, events: &Events) {
+    let Some(entry) = events.selected() else {
+        return;
+    };
+    let (Some(later), Some(path)) = (&mut state.later, history::path()) else {
+        let error = t("Review later flags are kept in scan history, which is off");
+        return state.messages.error(error.to_string());
+    };
+    let flag = !later.remove(&entry.id);
+    if flag {
+        later.insert(entry.id.clone());
+    }
+
+    let mut history = History::load(&path);
+    history.set_later(entry, flag);
+    if let Err(e) = history.save(&path) {
+        return fail(state, tf("Saving scan history failed: {}", &[&e]));
+    }
+    state.messages.push(if flag {
+        tf("Flagged {} for review later", &[&entry.path.display()])
+    } else {
+        tf(
+            "{} no longer flagged for review later",
+            &[&entry.path.display()],
+        )
+    });
+}
+
+// Exclude the project of the selected entry from scans, in this run and the
+// next ones, and drop its targets from the list. Folders in it are excluded
+// too.
+fn exclude_project(state: &mut State, events: &mut Events) {
+    let Some(entry) = events.selected() else {
+        return;
+    };
+    let project = PathBuf::from(&entry.project);
+    let Some(path) = excluded::path() else {
+        let error = t("Excluded projects are kept next to scan history, which needs a home folder");
+        return state.messages.error(error.to_string());
+    };
+    if state.excluded.contains(&project) {
+        return;
+    }
+    state.excluded.push(project.clone());
+    if let Err(e) = excluded::save(&path, &state.excluded) {
+        state.excluded.pop();
+        return fail(state, tf("Saving excluded projects failed: {}", &[&e]));
+    }
+    state.scan_options.excludes.push(project.clone());
+
+    let excluded = &state.excluded;
+    state.results.retain(|e| !is_excluded(excluded, &e.project));
+    events.items.retain(|e| !is_excluded(excluded, &e.project));
+    events.marked.retain(|path| !path.starts_with(&project));
+    match events.state.selected() {
+        Some(_) if events.items.is_empty() => events.state.select(None),
+        Some(selected) => events.select(selected),
+        None => (),
+    }
+    state.messages.push(tf(
+        "Excluded {} from scans, review excluded projects with X",
+        &[&project.display()],
+    ));
+}
+
+// Whether `project` is in one excluded with x
+fn is_excluded(excluded: &[PathBuf], project: &Path) -> bool {
+    excluded.iter().any(|p| project.starts_with(p))
+}
+
+// Start typing the note of the selected entry, from the note it has
+fn start_note(state: &mut State, events: &Events) {
+    let Some(entry) = events.selected() else {
+        return;
+    };
+    let Some(notes) = &state.notes else {
+        let error = t("Notes are kept in scan history, which is off");
+        return state.messages.error(error.to_string());
+    };
+    state.mode = Mode::Note(notes.get(&entry.id).cloned().unwrap_or_default());
+}
+
+// Attach the typed note to the selected entry and save it right away
+fn save_note(state: &mut State, events: &Events, text: &str) {
+    let text = text.trim();
+    let (Some(entry), Some(notes), Some(path)) =
+        (events.selected(), &mut state.notes, history::path())
+    else {
+        return;
+    };
+    if text.is_empty() {
+        notes.remove(&entry.id);
+    } else {
+        notes.insert(entry.id.clone(), text.to_string());
+    }
+
+    let mut history = History::load(&path);
+    history.set_note(entry, text);
+    if let Err(e) = history.save(&path) {
+        return fail(state, tf("Saving scan history failed: {}", &[&e]));
+    }
+    state.messages.push(if text.is_empty() {
+        tf("Removed note from {}", &[&entry.path.display()])
+    } else {
+        tf("Saved note on {}", &[&entry.path.display()])
+    });
+}
+
+// Show the project of the selected entry in the file manager
+fn open_folder(state: &mut State, events: &Events) {
+    let Some(project) = events.selected().map(|e| e.project.clone()) else {
+        return;
+    };
+    if let Err(e) = editors::open_folder(&project) {
+        fail(
+            state,
+            tf("Opening {} failed: {}", &[&project.display(), &e]),
+        );
+    }
+}
+
+// Open the project of the selected entry in the editor, handing the terminal
+// over until it quits
+fn edit_project(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    input: &Input,
+    state: &mut State,
+    events: &Events,
+) -> error::Result<()> {
+    let Some(project) = events.selected().map(|e| e.project.clone()) else {
+        return Ok(());
+    };
+    // The editor reads the keys until then
+    input.pause();
+    restore(terminal, state.caps)?;
+    let result = editors::edit(&state.editor, &project);
+    enter(terminal, state.caps)?;
+    input.resume();
+
+    match result {
+        Ok(status) if status.success() => (),
+        Ok(status) => fail(
+            state,
+            tf("Editor {} quit with {}", &[&state.editor, &status]),
+        ),
+        Err(e) => fail(state, tf("Editor {} failed: {}", &[&state.editor, &e])),
+    }
+    Ok(())
+}
+
+// Open the browser on the selected entry
+fn look_inside(state: &mut State, events: &Events) {
+    if let Some(entry) = events.selected() {
+        state.mode = Mode::Browse(Browser::new(&entry.path));
+    }
+}
+
+// Clicks on the key help press the key. In the list a click selects a row, a
+// double click looks inside it and the wheel moves the selection.
+fn handle_mouse_event(
+    mouse: &MouseEvent,
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    input: &Input,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    let inside = |area: &Rect| {
+        (area.x..area.right()).contains(&mouse.column)
+            && (area.y..area.bottom()).contains(&mouse.row)
+    };
+    if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+        let hint = events.hints.iter().find(|(area, _)| inside(area));
+        if let Some(key) = hint.and_then(|(_, key)| keys::event(key)) {
+            return handle_event(&key, terminal, input, state, events);
+        }
+    }
+    if !matches!(state.mode, Mode::List) {
+        return Ok(());
+    }
+
+    match mouse.kind {
+        // Rows start below the border
+        MouseEventKind::Down(MouseButton::Left)
+            if inside(&events.list_area) && mouse.row > events.list_area.y =>
+        {
+            let row = events.offset + usize::from(mouse.row - events.list_area.y - 1);
+            if events.click(row) {
+                look_inside(state, events);
+            }
+        }
+        MouseEventKind::ScrollUp => events.previous(),
+        MouseEventKind::ScrollDown => events.next(),
+        _ => (),
+    }
+    Ok(())
+}
+
+// Take in what the background scan has found. Returns true if the list needs
+// redrawing.
+fn receive(state: &mut State, events: &mut Events, open: &[PathBuf]) -> bool {
+    let Some((receiver, ..)) = &state.scan else {
+        return false;
+    };
+
+    let done = loop {
+        match receiver.try_recv() {
+            // Used recently
+            Ok(Found::Entry(entry)) if state.older_than.is_some_and(|a| !entry.is_older(a)) => (),
+            // Excluded since the scan started
+            Ok(Found::Entry(entry)) if is_excluded(&state.excluded, &entry.project) => (),
+            Ok(Found::Entry(mut entry)) => {
+                entry.open = editors::is_open(&entry.project, open);
+                // Space free here says nothing of the --ssh host
+                if remote::get().is_none() {
+                    add_filesystem(&mut state.filesystems, &entry);
+                }
+                if state.resume.remove(&entry.path) {
+                    events.marked.insert(entry.path.clone());
+                }
+                let cached = state.cached.remove(&entry.path);
+                if cached {
+                    state.results.retain(|e| e.path != entry.path);
+                }
+                state.results.push((*entry).clone());
+                // Found again, in place of what was cached
+                let row = cached
+                    .then(|| events.items.iter().position(|e| e.path == entry.path))
+                    .flatten();
+                match row {
+                    Some(row) => events.items[row] = *entry,
+                    None if listed(state, &entry) => events.items.push(*entry),
+                    None => (),
+                }
+                if events.state.selected().is_none() {
+                    events.next();
+                }
+            }
+            Ok(Found::Unreadable(path, kind)) => {
+                let warning = tf("Cannot scan {}: {}", &[&path.display(), &kind]);
+                state.warnings.push(warning);
+            }
+            Err(TryRecvError::Empty) => break false,
+            Err(TryRecvError::Disconnected) => break true,
+        }
+    };
+    order(state, events);
+    if !done {
+        return true;
+    }
+
+    // Scan is done
+    let Some((_, handle, progress)) = state.scan.take() else {
+        return true;
+    };
+    state.time = state.started.elapsed().as_secs_f32();
+    state.cancelled = progress.is_cancelled();
+    match handle.join() {
+        // Only part of it was scanned, cached targets may still be there and
+        // the total can't be compared
+        Ok(Ok(())) if state.cancelled => state.messages.push(tf(
+            "Scan cancelled after {} folders, {} targets found",
+            &[&progress.dirs(), &state.results.len()],
+        )),
+        Ok(Ok(())) => {
+            // Cached targets that weren't found again are gone
+            let gone = std::mem::take(&mut state.cached);
+            state.results.retain(|e| !gone.contains(&e.path));
+            events.items.retain(|e| !gone.contains(&e.path));
+            if let Err(e) = save_cache(state) {
+                fail(state, tf("Saving scan cache failed: {}", &[&e]));
+            }
+            remember(state);
+            mark_over_budget(state, events);
+        }
+        Ok(Err(e)) => fail(state, tf("Scanning failed: {}", &[&e])),
+        Err(_) => fail(state, t("Scanning failed").to_string()),
+    }
+    true
+}
+
+// Mark the least recently built targets that need to go for all of them to
+// fit in the budget
+fn mark_over_budget(state: &mut State, events: &mut Events) {
+    let Some(budget) = state.max_total else {
+        return;
+    };
+    let total: u64 = state.results.iter().map(|e| e.size).sum();
+    if total <= budget {
+        state.messages.push(tf(
+            "Targets take {}, within the budget of {}",
+            &[&size::format_size(total), &size::format_size(budget)],
+        ));
+        return;
+    }
+
+    let chosen = scan::over_budget(&state.results, budget, |e| {
+        guard::refuse(e, &state.roots).is_none()
+            && !(e.active || e.open && state.open_projects == OpenProjects::Skip)
+            && !state.pinned.contains(&e.path)
+    });
+    let size = chosen.iter().map(|e| e.size).sum();
+    for entry in &chosen {
+        events.marked.insert(entry.path.clone());
+    }
+    state.messages.push(tf(
+        "Targets take {}, over the budget of {}. Marked the {} least recently built ({})",
+        &[
+            &size::format_size(total),
+            &size::format_size(budget),
+            &chosen.len(),
+            &size::format_size(size),
+        ],
+    ));
+}
+
+// Show the targets of the last scan of the same folders while they are
+// scanned again
+fn show_cached(state: &mut State, events: &mut Events, open: &[PathBuf]) {
+    let (Some(key), Some(path)) = (&state.cache, cache::path()) else {
+        return;
+    };
+    for mut entry in cache::load(&path, key) {
+        if is_excluded(&state.excluded, &entry.project) {
+            continue;
+        }
+        entry.open = editors::is_open(&entry.project, open);
+        add_filesystem(&mut state.filesystems, &entry);
+        state.cached.insert(entry.path.clone());
+        if listed(state, &entry) {
+            events.items.push(entry.clone());
+        }
+        state.results.push(entry);
+    }
+    order(state, events);
+    events.next();
+}
+
+// Cache the list for showing it right away next time
+fn save_cache(state: &State) -> std::io::Result<()> {
+    match (&state.cache, cache::path()) {
+        (Some(key), Some(path)) => cache::save(&path, key, &state.results),
+        _ => Ok(()),
+    }
+}
+
+// Compare the finished scan with the last one of the same folders and
+// remember it for the next time
+fn remember(state: &mut State) {
+    let (Some(key), Some(path)) = (&state.history, history::path()) else {
+        return;
+    };
+    let mut history = History::load(&path);
+    state.changes = history.changes(key, &state.results);
+    history.record(key, &state.results);
+    state.trend = history.totals(key);
+    if let Err(e) = history.save(&path) {
+        fail(state, tf("Saving scan history failed: {}", &[&e]));
+    }
+}
+
+#[tracing::instrument(skip_all)]
+fn trash_selected(state: &mut State, events: &mut Events) {
+    // Marked entries take precedence over the selection
+    if !events.marked.is_empty() {
+        let paths = bulk_paths(state, |e| events.marked.contains(&e.path));
+        return ask_delete(state, paths);
+    }
+
+    if let Some(path) = events.selected().map(|e| e.path.clone()) {
+        if state.confirm_single || state.preview {
+            return ask_delete(state, vec![path]);
+        }
+        delete_paths(state, vec![path], state.permanent, state.cleanup.clone());
+    }
+}
+
+fn trash_all(state: &mut State) {
+    let paths = bulk_paths(state, |_| true);
+    ask_delete(state, paths);
+}
+
+// Ask before deleting `paths`, offering to skip the trash if trashing looks
+// like it takes long
+fn ask_delete(state: &mut State, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    let confirm = if state.permanent {
+        Confirm::PermanentDelete(paths)
+    } else if trashcan::estimate(files(state, &paths)) > trashcan::SLOW {
+        Confirm::SlowDelete(paths)
+    } else {
+        Confirm::Trash(paths)
+    };
+    state.preview_of = state.preview.then(|| Preview::of(&paths_of(&confirm)));
+    state.mode = Mode::Confirm(confirm);
+}
+
+/// Folders a dialog asks about deleting
+pub fn paths_of(confirm: &Confirm) -> Vec<PathBuf> {
+    match confirm {
+        Confirm::Trash(paths)
+        | Confirm::SlowDelete(paths)
+        | Confirm::PermanentDelete(paths)
+        | Confirm::CargoClean(paths)
+        | Confirm::LightClean(paths) => paths.clone(),
+        Confirm::EmptyTrash => vec![],
+    }
+}
+
+// Selected or marked paths for permanent delete
+fn ask_permanent_delete(state: &mut State, events: &Events) {
+    let paths = if events.marked.is_empty() {
+        events
+            .selected()
+            .map(|e| vec![e.path.clone()])
+            .unwrap_or_default()
+    } else {
+        bulk_paths(state, |e| events.marked.contains(&e.path))
+    };
+
+    if !paths.is_empty() {
+        state.preview_of = state.preview.then(|| Preview::of(&paths));
+        state.mode = Mode::Confirm(Confirm::PermanentDelete(paths));
+    }
+}
+
+// Take a cleaned up entry out of the list, or measure it again if cargo
+// clean left some of it, like `--release` does
+fn removed(state: &mut State, events: &mut Events, path: &Path, removal: Removal) {
+    let Some(old) = state.results.iter_mut().find(|e| e.path == path) else {
+        return;
+    };
+    if let Some(checker) = state.checker.as_mut().filter(|_| old.kind == Kind::Rust) {
+        checker.check(&old.project);
+    }
+    if removal != Removal::Cleaned || remote::get().is_some() || !path.exists() {
+        // Trashed folders take space until the trash is emptied
+        let trashed = matches!(removal, Removal::Trashed | Removal::Quarantined);
+        if !trashed {
+            state.freed += old.size;
+        }
+        // The trash of the --ssh host can't be undone or emptied from here
+        return forget(state, events, path, trashed && remote::get().is_none());
+    }
+
+    let mut entry = Entry::new(old.path.clone(), old.project.clone(), old.kind, &|dir| {
+        old.is_kept(dir)
+    });
+    entry.open = old.open;
+    entry.active = old.active;
+    state.freed += old.size.saturating_sub(entry.size);
+    *old = entry.clone();
+    if let Some(item) = events.items.iter_mut().find(|e| e.path == path) {
+        *item = entry;
+    }
+}
+
+/// Number of files in entries of `paths`
+pub fn files(state: &State, paths: &[PathBuf]) -> u64 {
+    state
+        .results
+        .iter()
+        .filter(|e| paths.contains(&e.path))
+        .map(|e| e.files)
+        .sum()
+}
+
+// Drop a deleted entry from the lists and remember it for the report
+fn forget(state: &mut State, events: &mut Events, path: &Path, trashed: bool) {
+    if let Some(idx) = state.results.iter().position(|e| e.path == path) {
+        let entry = state.results.remove(idx);
+        if trashed {
+            state.trashed.push(entry.clone());
+            state.undo.push(Undo {
+                path: path.to_path_buf(),
+                result: idx,
+                row: events.items.iter().position(|e| e.path == path),
+            });
+        }
+        state.deleted.push(entry);
+    }
+    if state.tombstones {
+        events.tombstones.insert(path.to_path_buf(), Instant::now());
+    } else {
+        events.items.retain(|e| e.path != path);
+    }
+    events.marked.remove(path);
+    events.failed.remove(path);
+}
+
+// Paths of entries for a bulk delete, leaving out open projects if asked to
+// and active ones. Only listed entries are included, so a filter scopes bulk
+// actions.
+fn bulk_paths(state: &State, filter: impl Fn(&Entry) -> bool) -> Vec<PathBuf> {
+    state
+        .results
+        .iter()
+        .filter(|e| listed(state, e) && filter(e))
+        .filter(|e| !(e.open && state.open_projects == OpenProjects::Skip))
+        .filter(|e| !e.active)
+        .filter(|e| !state.pinned.contains(&e.path))
+        .filter(|e| !state.cached.contains(&e.path))
+        .map(|e| e.path.clone())
+        .collect()
+}
+
+// Trash or permanently delete folders in the background, after the ones
+// already going. `deleted` tells how it goes.
+#[tracing::instrument(skip_all, fields(count = paths.len(), permanent))]
+fn delete_paths(state: &mut State, paths: Vec<PathBuf>, permanent: bool, cleanup: Cleanup) {
+    // What's inside may have changed since, like excluded folders
+    let waiting = paths.iter().filter(|p| state.cached.contains(*p)).count();
+    if waiting > 0 {
+        state.messages.error(tf(
+            "{} targets are from the last run, they can be deleted once the scan finds them again",
+            &[&waiting],
+        ));
+    }
+    let jobs: Vec<Job> = paths
+        .iter()
+        .filter(|path| !state.deleter.is_queued(path) && !state.cached.contains(*path))
+        .filter_map(|path| state.results.iter().find(|e| &e.path == path))
+        .map(|entry| Job {
+            entry: entry.clone(),
+            permanent,
+            cleanup: cleanup.clone(),
+            salvage: state.salvage,
+        })
+        .collect();
+    if jobs.is_empty() {
+        return;
+    }
+
+    let batch = state.batch.get_or_insert_with(|| Batch {
+        total: 0,
+        finished: 0,
+        permanent,
+        cleaning: false,
+        free_before: disk::free_space(&state.roots[0]),
+        last: None,
+    });
+    batch.total += jobs.len();
+    // Progress tells about the latest ones
+    batch.permanent = permanent;
+    batch.cleaning = cleanup != Cleanup::Remove;
+    state.deleter.delete(jobs);
+}
+
+// Scan the roots in the background, or the folder on the host with --ssh
+fn spawn_scan(state: &State) -> scan::Background {
+    match remote::get() {
+        Some(remote) => remote::spawn(remote.clone(), &state.scan_options.kinds),
+        None => scan::spawn(state.roots.clone(), state.scan_options.clone()),
+    }
+}
+
+// Take in deletes finished in the background, showing progress and how much
+// the free space has grown so far. With `wait`, waits for one to finish.
+// Returns true while the list needs drawing again.
+fn deleted(state: &mut State, events: &mut Events, wait: bool) -> bool {
+    let finished = state.deleter.finished(wait);
+    let Some(mut batch) = state.batch.take() else {
+        return false;
+    };
+
+    let any = !finished.is_empty();
+    for Finished {
+        path,
+        installed,
+        result,
+    } in finished
+    {
+        if !installed.is_empty() {
+            state
+                .messages
+                .push(tf("Installed {}", &[&installed.join(", ")]));
+        }
+        match result {
+            Ok(removal) => {
+                removed(state, events, &path, removal);
+                batch.last = Some((path, removal));
+            }
+            Err(e) => {
+                events.failed.insert(path, e.reason());
+                fail(state, e);
+            }
+        }
+        batch.finished += 1;
+    }
+
+    let freed = freed_since(batch.free_before, &state.roots[0]);
+    if state.deleter.pending() > 0 {
+        if any && batch.total > 1 {
+            let progress = if batch.cleaning {
+                "Cleaned {}/{}"
+            } else if batch.permanent {
+                "Deleted {}/{}"
+            } else {
+                "Trashed {}/{}"
+            };
+            let mut message = tf(progress, &[&batch.finished, &batch.total]);
+            if let Some(freed) = freed {
+                message.push_str(&tf(", freed {}", &[&size::format_size(freed)]));
+            }
+            state.messages.progress(message);
+        }
+        state.batch = Some(batch);
+        // Keeps the spinner going
+        return true;
+    }
+
+    let total = batch.total;
+    let done = if total == 1 {
+        // Still there until the trash is emptied if it's on the same
+        // filesystem
+        batch.last.map(|(path, removal)| {
+            let mut done = tf(removal.done(), &[&path.display()]);
+            if let Some(freed) = freed.filter(|&f| f > 0) {
+                done.push_str(&tf(", freed {}", &[&size::format_size(freed)]));
+            }
+            done
+        })
+    } else if !batch.permanent && !batch.cleaning && freed == Some(0) {
+        // Trashing on the same filesystem only moves files around
+        Some(tf(
+            "Trashed {} folders but free space did not grow, press E to empty them from trash",
+            &[&total],
+        ))
+    } else {
+        let done = if batch.cleaning {
+            "Cleaned {} folders"
+        } else if batch.permanent {
+            "Deleted {} folders"
+        } else {
+            "Trashed {} folders"
+        };
+        let mut done = tf(done, &[&total]);
+        if let Some(freed) = freed {
+            done.push_str(&tf(", freed {}", &[&size::format_size(freed)]));
+        }
+        Some(done)
+    };
+    if let Some(done) = done {
+        state.messages.finish(done);
+    }
+
+    for fs in &mut state.filesystems {
+        fs.free = disk::free_space(&fs.mount);
+    }
+
+    // Tombstones keep the rows in place until they go away by themselves
+    if !state.tombstones {
+        show_filtered(state, events);
+    }
+    true
+}
+
+// Order results and the list by `by`, or the other way around if they are
+// in that order already
+fn sort_by(state: &mut State, events: &mut Events, by: Sort) {
+    state.sort_reversed = state.sort == by && !state.sort_reversed;
+    state.sort = by;
+    by.apply(&mut state.results, state.sort_reversed);
+    order(state, events);
+}
+
+// Sort the list, grouping it by filesystem if asked to and by root when
+// several were scanned
+fn order(state: &State, events: &mut Events) {
+    events.sort(state.sort, state.sort_reversed);
+    events.group_workspaces();
+    if state.group_by_filesystem {
+        events.group(|e| {
+            let filesystems = &state.filesystems;
+            let rank = filesystems
+                .iter()
+                .position(|fs| Some(fs.device) == e.device);
+            rank.unwrap_or(filesystems.len())
+        });
+    }
+    if state.roots.len() > 1 {
+        events.group(|e| root_of(&state.roots, e).unwrap_or(state.roots.len()));
+    }
+    events.group(|e| usize::from(!state.pinned.contains(&e.path)));
+}
+
+// Find out about the filesystem of `entry` unless it's known already
+fn add_filesystem(filesystems: &mut Vec<Filesystem>, entry: &Entry) {
+    if filesystems.iter().any(|fs| Some(fs.device) == entry.device) {
+        return;
+    }
+    if let Some(fs) = Filesystem::of(&entry.path) {
+        filesystems.push(fs);
+        filesystems.sort_by(|a, b| a.free_fraction().total_cmp(&b.free_fraction()));
+    }
+}
+
+// List the results matching the filter, selecting the first one
+fn show_filtered(state: &State, events: &mut Events) {
+    events.clear();
+    events.items = state
+        .results
+        .iter()
+        .filter(|e| listed(state, e))
+        .cloned()
+        .collect();
+    order(state, events);
+    events.next();
+}
+
+/// Whether `entry` is shown: it matches the filter and isn't hidden for being
+/// small
+pub fn listed(state: &State, entry: &Entry) -> bool {
+    entry.matches(&state.filter)
+        && !(state.hide_small && state.min_size.is_some_and(|min| entry.size < min))
+}
+
+// Hide targets under --min-size, or show them again
+fn toggle_small(state: &mut State, events: &mut Events) {
+    let Some(min) = state.min_size else {
+        let error = t("Give a size with --min-size to hide small targets");
+        return state.messages.error(error.to_string());
+    };
+    state.hide_small = !state.hide_small;
+    let size = size::format_size(min);
+    state.messages.push(if state.hide_small {
+        tf("Hiding targets under {}", &[&size])
+    } else {
+        tf("Showing targets under {} too", &[&size])
+    });
+    show_filtered(state, events);
+}
+
+// Put the latest trashed entry back from trash and into the list where it was
+fn undo(state: &mut State, events: &mut Events) {
+    let Some(undo) = state.undo.pop() else {
+        return;
+    };
+    let Some(idx) = state.trashed.iter().rposition(|e| e.path == undo.path) else {
+        return;
+    };
+    let entry = state.trashed.remove(idx);
+
+    if let Err(e) = trashcan::restore(&entry) {
+        fail(
+            state,
+            tf("Restoring {} failed: {}", &[&entry.path.display(), &e]),
+        );
+        state.trashed.insert(idx, entry);
+        state.undo.push(undo);
+        return;
+    }
+
+    if let Some(idx) = state.deleted.iter().rposition(|e| e.path == entry.path) {
+        state.deleted.remove(idx);
+    }
+    state
+        .results
+        .insert(undo.result.min(state.results.len()), entry.clone());
+    // Still in the list if its tombstone hasn't gone away yet
+    if events.tombstones.remove(&entry.path).is_none() && listed(state, &entry) {
+        let row = undo.row.unwrap_or(events.items.len());
+        events
+            .items
+            .insert(row.min(events.items.len()), entry.clone());
+    }
+    let row = events.items.iter().position(|e| e.path == entry.path);
+    events.state.select(row);
+    state
+        .messages
+        .push(tf("Restored {}", &[&entry.path.display()]));
+}
+
+// Scan the same folders again in the background, starting the list over
+// but keeping the filter, the sort order and pins
+fn rescan(state: &mut State, events: &mut Events) {
+    if state.scan.is_some() {
+        return state.messages.push(t("Already scanning").to_string());
+    }
+    // They'd come back before they're gone
+    if state.deleter.pending() > 0 {
+        return state
+            .messages
+            .error(t("Deletes are still going, scan again once they finish").to_string());
+    }
+
+    state.results.clear();
+    state.cached.clear();
+    state.filesystems.clear();
+    state.warnings.clear();
+    state.changes = None;
+    state.details = None;
+    *events = Events::new(vec![]);
+    state.started = Instant::now();
+    state.cancelled = false;
+    state.scan = Some(spawn_scan(state));
+}
+
+// Growth of free space on the filesystem of `path` compared to `before`
+fn freed_since(before: Option<u64>, path: &Path) -> Option<u64> {
+    Some(disk::free_space(path)?.saturating_sub(before?))
+}
+
+// Let cargo clean the marked Rust targets, or the selected one. A light
+// clean only removes the parts that are rebuilt quickly.
+fn clean_selected(state: &mut State, events: &mut Events, cleanup: Cleanup) {
+    if !events.marked.is_empty() {
+        let paths = bulk_paths(state, |e| {
+            events.marked.contains(&e.path) && e.kind == Kind::Rust
+        });
+        if !paths.is_empty() {
+            state.preview_of = None;
+            state.mode = Mode::Confirm(match cleanup {
+                Cleanup::Light => Confirm::LightClean(paths),
+                _ => Confirm::CargoClean(paths),
+            });
+        }
+        return;
+    }
+
+    let Some(entry) = events.selected() else {
+        return;
+    };
+    if entry.kind != Kind::Rust {
+        let error = match cleanup {
+            Cleanup::Light => t("Only Rust targets can be cleaned lightly"),
+            _ => t("Only Rust targets can be cleaned with cargo"),
+        };
+        state.messages.error(error.to_string());
+        return;
+    }
+    let path = entry.path.clone();
+    delete_paths(state, vec![path], false, cleanup);
+}
+
+// Let the profile folders of the selected Rust target be picked to clean,
+// those of --profiles or all but release to begin with
+fn pick_profiles(state: &mut State, events: &Events) {
+    let Some(entry) = events.selected() else {
+        return;
+    };
+    if entry.kind != Kind::Rust {
+        state
+            .messages
+            .error(t("Only Rust targets have profiles to clean").to_string());
+        return;
+    }
+    let picked = match &state.cleanup {
+        Cleanup::Profiles(profiles) => profiles.clone(),
+        _ => vec![],
+    };
+    let folders: Vec<(String, u64, bool)> = cargo::profiles(&entry.path)
+        .into_iter()
+        .map(|(name, size)| {
+            let clean = if picked.is_empty() {
+                name != "release"
+            } else {
+                picked.contains(&name)
+            };
+            (name, size, clean)
+        })
+        .collect();
+    if folders.is_empty() {
+        state.messages.error(tf(
+            "{} has no profile folders to clean",
+            &[&entry.path.display()],
+        ));
+        return;
+    }
+    let mut list = ListState::default();
+    list.select(Some(0));
+    state.mode = Mode::Profiles(Profiles {
+        path: entry.path.clone(),
+        folders,
+        state: list,
+    });
+}
+
+/// Arguments given to cargo clean, followed by a space, for dialogs
+pub fn cargo_args(state: &State) -> String {
+    state
+        .cargo_clean_args
+        .iter()
+        .map(|arg| format!("{arg} "))
+        .collect()
+}
+
+fn salvage_selected(state: &mut State, events: &Events) {
+    if let Some(path) = events.selected().map(|e| e.path.clone()) {
+        salvage(state, &path);
+    }
+}
+
+// Copy release binaries to cargo bin folder. Returns false on failure.
+fn salvage(state: &mut State, path: &Path) -> bool {
+    match cargo::salvage(path) {
+        Ok(names) if names.is_empty() => true,
+        Ok(names) => {
+            state
+                .messages
+                .push(tf("Installed {}", &[&names.join(", ")]));
+            true
+        }
+        Err(e) => {
+            fail(
+                state,
+                Error::Salvage {
+                    path: path.to_path_buf(),
+                    source: e,
+                },
+            );
+            false
+        }
+    }
+}
+
+// Show an error and keep it for the session summary
+fn fail(state: &mut State, error: impl ToString) {
+    let error = error.to_string();
+    state.messages.error(error.clone());
+    state.failures.push(error);
+}
+
+fn handle_event(
+    event: &Event,
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    input: &Input,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    if let Event::Mouse(mouse) = event {
+        return handle_mouse_event(mouse, terminal, input, state, events);
+    }
+    // Fit the new size right away instead of on the next key, the loop draws
+    // after every event
+    if let Event::Resize(..) = event {
+        return Ok(terminal.autoresize()?);
+    }
+    match state.mode {
+        Mode::Browse(_) => handle_browser_event(event, terminal, state, events),
+        Mode::MarkOlder(_) => handle_mark_older_event(event, state, events),
+        Mode::Search(_) => handle_search_event(event, state, events),
+        Mode::Note(_) => handle_note_event(event, state, events),
+        Mode::Excluded(_) => handle_excluded_event(event, state),
+        Mode::Tree => handle_tree_event(event, state),
+        Mode::Profiles(_) => handle_profiles_event(event, state),
+        Mode::Duplicates(_) => handle_duplicates_event(event, state),
+        Mode::Confirm(Confirm::EmptyTrash) => handle_empty_trash_event(event, state),
+        Mode::Confirm(_) => handle_confirm_delete_event(event, state, events),
+        Mode::Trend | Mode::Help => handle_back_event(event, state),
+        Mode::List => handle_list_event(event, terminal, input, state, events),
+    }
+}
+
+// Any key closes the trend and the help
+fn handle_back_event(event: &Event, state: &mut State) -> error::Result<()> {
+    if let Event::Key(KeyEvent {
+        kind: KeyEventKind::Press,
+        ..
+    }) = event
+    {
+        state.mode = Mode::List;
+    }
+    Ok(())
+}
+
+fn handle_list_event(
+    event: &Event,
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    input: &Input,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    // Handlers match default keys
+    let Some(event) = state.keys.remap(event) else {
+        return Ok(());
+    };
+    if remote::get().is_some() && is_local(&event) {
+        state
+            .messages
+            .error(t("Not possible with targets over SSH").to_string());
+        return Ok(());
+    }
+    match &event {
+        // Select previous
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            events.previous();
+        }
+
+        // Select next
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            events.next();
+        }
+
+        // Select a screenful up
+        Event::Key(KeyEvent {
+            code: KeyCode::PageUp,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => events.page_up(),
+
+        // Select a screenful down
+        Event::Key(KeyEvent {
+            code: KeyCode::PageDown,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => events.page_down(),
+
+        // Select first
+        Event::Key(KeyEvent {
+            code: KeyCode::Home,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => events.select(0),
+
+        // Select last
+        Event::Key(KeyEvent {
+            code: KeyCode::End,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => events.select(usize::MAX),
+
+        // Trash one
+        Event::Key(KeyEvent {
+            code: KeyCode::Delete,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            trash_selected(state, events);
+            // Stay on the entry a dialog asks about
+            if !matches!(state.mode, Mode::Confirm(_)) {
+                events.next();
+            }
+        }
+
+        // Trash all
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('a'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            trash_all(state);
+        }
+
+        // Look inside selected
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            look_inside(state, events);
+        }
+
+        // Mark or unmark selected and move on
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(' '),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            events.toggle_mark();
+            events.next();
+        }
+
+        // Mark or unmark everything
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('v'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            events.mark_all();
+        }
+
+        // Sort by size, path or age, pressing again turns the order around
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c @ ('s' | 'p' | 'm')),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let by = match c {
+                's' => Sort::Size,
+                'p' => Sort::Path,
+                _ => Sort::Age,
+            };
+            sort_by(state, events, by);
+        }
+
+        // Mark a row on screen by its number
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c @ '1'..='9'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            events.quick_select(*c as usize - '0' as usize);
+        }
+
+        // Type a filter
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('/'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::Search(state.filter.clone());
+        }
+
+        // Hide targets under --min-size, or show them again
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('h'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => toggle_small(state, events),
+
+        // List targets on the fullest filesystem first
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('g'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.group_by_filesystem = !state.group_by_filesystem;
+            if state.group_by_filesystem {
+                order(state, events);
+            } else {
+                show_filtered(state, events);
+            }
+        }
+
+        // Pin or unpin selected
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('P'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => toggle_pin(state, events),
+
+        // Flag selected for review later, or unflag it
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('r'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => toggle_later(state, events),
+
+        // Add or edit the note of selected
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('N'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => start_note(state, events),
+
+        // Exclude the project of selected from scans for good
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('x'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => exclude_project(state, events),
+
+        // Group results under the folders they share
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('T'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.tree = Some(Tree::default());
+            state.mode = Mode::Tree;
+        }
+
+        // Show the excluded projects
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('X'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let mut list = ListState::default();
+            list.select((!state.excluded.is_empty()).then_some(0));
+            state.mode = Mode::Excluded(list);
+        }
+
+        // Switch between full paths and project names
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('n'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => state.show_names = !state.show_names,
+
+        // Show how the total size has changed between scans
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('t'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::Trend;
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('R'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => rescan(state, events),
+
+        // Show the project in the file manager
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('o'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => open_folder(state, events),
+
+        // Open the project in the editor
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('e'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => edit_project(terminal, input, state, events)?,
+
+        // Show every key and the current settings
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('?'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => state.mode = Mode::Help,
+
+        // Ask age and mark everything older
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('O'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::MarkOlder(String::new());
+        }
+
+        // Toggle details pane
+        Event::Key(KeyEvent {
+            code: KeyCode::Tab,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.show_details = !state.show_details;
+        }
+
+        // Delete selected or marked without trash
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('D'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            ask_permanent_delete(state, events);
+        }
+
+        // Empty trashed targets from trash
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('E'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) if !state.trashed.is_empty() => {
+            state.mode = Mode::Confirm(Confirm::EmptyTrash);
+        }
+
+        // Let cargo clean the project
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('c'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => clean_selected(state, events, Cleanup::Cargo),
+
+        // Remove what is rebuilt quickly, keeping binaries
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('l'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => clean_selected(state, events, Cleanup::Light),
+
+        // Pick the profiles of the selected Rust target to clean
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('C'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => pick_profiles(state, events),
+
+        // Tell what dependencies built in several Rust targets take
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('A'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => compare_dependencies(state),
+
+        // Put the latest trashed entry back
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('u'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => undo(state, events),
+
+        // Copy release binaries to cargo bin folder
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('i'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            salvage_selected(state, events);
+        }
+
+        // Stop the scan, keeping what it found, Esc again quits
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) if state
+            .scan
+            .as_ref()
+            .is_some_and(|(_, _, progress)| !progress.is_cancelled()) =>
+        {
+            if let Some((_, _, progress)) = &state.scan {
+                progress.cancel();
+            }
+            state
+                .messages
+                .push(t("Cancelling the scan, Esc again quits").to_string());
+        }
+
+        // Exit
+        Event::Key(
+            KeyEvent {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                state: KeyEventState::NONE,
+            }
+            | KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+                state: KeyEventState::NONE,
+            },
+        ) => {
+            quit(terminal, state, events)?;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_browser_event(
+    event: &Event,
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    let Mode::Browse(browser) = &mut state.mode else {
+        return Ok(());
+    };
+
+    match event {
+        // Select previous
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            browser.previous();
+        }
+
+        // Select next
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            browser.next();
+        }
+
+        // Open folder
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            browser.enter();
+        }
+
+        // Go up, back to list from the target root
+        Event::Key(KeyEvent {
+            code: KeyCode::Backspace | KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let at_root = !browser.back();
+            if at_root {
+                state.mode = Mode::List;
+            }
+        }
+
+        // Exit
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('c'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            quit(terminal, state, events)?;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_mark_older_event(
+    event: &Event,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    let Mode::MarkOlder(input) = &mut state.mode else {
+        return Ok(());
+    };
+
+    match event {
+        // Type days
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) if c.is_ascii_digit() => {
+            input.push(*c);
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Backspace,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            input.pop();
+        }
+
+        // Mark
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            if let Ok(days) = input.parse::<u64>() {
+                let count = events.mark_older(Duration::from_secs(days * 24 * 60 * 60));
+                state
+                    .messages
+                    .push(tf("Marked {} entries older than {} days", &[&count, &days]));
+            }
+            state.mode = Mode::List;
+        }
+
+        // Cancel
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_note_event(event: &Event, state: &mut State, events: &mut Events) -> error::Result<()> {
+    let Mode::Note(input) = &mut state.mode else {
+        return Ok(());
+    };
+
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            input.push(*c);
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Backspace,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            input.pop();
+        }
+
+        // Save, an empty note removes it
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            if let Mode::Note(text) = std::mem::replace(&mut state.mode, Mode::List) {
+                save_note(state, events, &text);
+            }
+        }
+
+        // Cancel
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_excluded_event(event: &Event, state: &mut State) -> error::Result<()> {
+    let Mode::Excluded(list) = &mut state.mode else {
+        return Ok(());
+    };
+    let last = state.excluded.len().checked_sub(1);
+
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            list.select(list.selected().map(|i| i.saturating_sub(1)));
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            list.select(list.selected().zip(last).map(|(i, last)| (i + 1).min(last)));
+        }
+
+        // Scan the selected project again
+        Event::Key(KeyEvent {
+            code: KeyCode::Delete,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let (Some(i), Some(path)) = (list.selected(), excluded::path()) else {
+                return Ok(());
+            };
+            let project = state.excluded.remove(i);
+            let last = state.excluded.len().checked_sub(1);
+            list.select(last.map(|last| i.min(last)));
+            if let Err(e) = excluded::save(&path, &state.excluded) {
+                state.excluded.insert(i, project);
+                fail(state, tf("Saving excluded projects failed: {}", &[&e]));
+                return Ok(());
+            }
+            state.scan_options.excludes.retain(|p| *p != project);
+            state.messages.push(tf(
+                "{} is scanned again, rescan with R to list it",
+                &[&project.display()],
+            ));
+        }
+
+        // Back to the list
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        })
+        | Event::Key(KeyEvent {
+            code: KeyCode::Char('X'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+// Whether `event` is for an action that needs the targets on this machine,
+// like looking inside
+fn is_local(event: &Event) -> bool {
+    let Event::Key(KeyEvent {
+        code,
+        kind: KeyEventKind::Press,
+        ..
+    }) = event
+    else {
+        return false;
+    };
+    matches!(
+        code,
+        KeyCode::Enter | KeyCode::Tab | KeyCode::Char('o' | 'e' | 'i' | 'x' | 'X' | 'C' | 'A')
+    )
+}
+
+fn handle_duplicates_event(event: &Event, state: &mut State) -> error::Result<()> {
+    let Mode::Duplicates(duplicates) = &mut state.mode else {
+        return Ok(());
+    };
+    let last = duplicates
+        .report
+        .as_ref()
+        .and_then(|report| report.duplicates.len().checked_sub(1));
+    let list = &mut duplicates.state;
+
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            list.select(list.selected().map(|i| i.saturating_sub(1)));
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            list.select(list.selected().zip(last).map(|(i, last)| (i + 1).min(last)));
+        }
+
+        // Back to the list, what a comparison still going finds is dropped
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        })
+        | Event::Key(KeyEvent {
+            code: KeyCode::Char('A'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_profiles_event(event: &Event, state: &mut State) -> error::Result<()> {
+    let Mode::Profiles(profiles) = &mut state.mode else {
+        return Ok(());
+    };
+    let last = profiles.folders.len().saturating_sub(1);
+    let selected = profiles.state.selected().unwrap_or_default();
+
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            profiles.state.select(Some(selected.saturating_sub(1)));
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            profiles.state.select(Some((selected + 1).min(last)));
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(' '),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            if let Some((_, _, clean)) = profiles.folders.get_mut(selected) {
+                *clean = !*clean;
+            }
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let picked: Vec<String> = profiles
+                .folders
+                .iter()
+                .filter(|(_, _, clean)| *clean)
+                .map(|(name, ..)| name.clone())
+                .collect();
+            if picked.is_empty() {
+                state
+                    .messages
+                    .error(t("Nothing picked to clean, Space picks a profile").to_string());
+                return Ok(());
+            }
+            let path = profiles.path.clone();
+            state.mode = Mode::List;
+            delete_paths(state, vec![path], false, Cleanup::Profiles(picked));
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_tree_event(event: &Event, state: &mut State) -> error::Result<()> {
+    let Some(mut tree) = state.tree.take() else {
+        state.mode = Mode::List;
+        return Ok(());
+    };
+    let entries: Vec<&Entry> = state.results.iter().filter(|e| listed(state, e)).collect();
+    let nodes = tree::nodes(&state.roots, &entries);
+    let rows = tree.rows(&nodes);
+    let selected = tree.state.selected().unwrap_or(0);
+
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => tree.select(selected.saturating_sub(1), rows.len()),
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => tree.select(selected + 1, rows.len()),
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Home,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => tree.select(0, rows.len()),
+
+        Event::Key(KeyEvent {
+            code: KeyCode::End,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => tree.select(rows.len(), rows.len()),
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter | KeyCode::Left | KeyCode::Right,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => tree.toggle(&rows),
+
+        // Everything in the selected folder, asking first
+        Event::Key(KeyEvent {
+            code: KeyCode::Delete,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            if let Some(node) = rows.get(selected) {
+                let paths = bulk_paths(state, |e| node.targets.contains(&e.path));
+                state.tree = Some(tree);
+                ask_delete(state, paths);
+                return Ok(());
+            }
+        }
+
+        // Back to the list
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        })
+        | Event::Key(KeyEvent {
+            code: KeyCode::Char('T'),
+            modifiers: KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+            return Ok(());
+        }
+
+        _ => (),
+    }
+    state.tree = Some(tree);
+    Ok(())
+}
+
+fn handle_search_event(event: &Event, state: &mut State, events: &mut Events) -> error::Result<()> {
+    match event {
+        // Filter as you type
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.filter.push(*c);
+            show_filtered(state, events);
+        }
+
+        Event::Key(KeyEvent {
+            code: KeyCode::Backspace,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.filter.pop();
+            show_filtered(state, events);
+        }
+
+        // Keep filter
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            state.mode = Mode::List;
+        }
+
+        // Go back to the filter there was before typing
+        Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            if let Mode::Search(previous) = std::mem::replace(&mut state.mode, Mode::List) {
+                state.filter = previous;
+            }
+            show_filtered(state, events);
+        }
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_empty_trash_event(event: &Event, state: &mut State) -> error::Result<()> {
+    match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('y'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }) => {
+            let total = state.trashed.iter().map(|e| e.size).sum();
+            let size = size::format_size(total);
+            match trashcan::empty(&state.trashed) {
+                Ok(count) => {
+                    state.freed += total;
+                    state.trashed.clear();
+                    state.undo.clear();
+                    state
+                        .messages
+                        .push(tf("Emptied {} folders ({}) from trash", &[&count, &size]));
+                }
+                Err(e) => fail(state, tf("Emptying trash failed: {}", &[&e])),
+            }
+            state.mode = Mode::List;
+        }
+
+        // Anything else cancels
+        Event::Key(_) => state.mode = Mode::List,
+
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_confirm_delete_event(
+    event: &Event,
+    state: &mut State,
+    events: &mut Events,
+) -> error::Result<()> {
+    let Event::Key(key) = event else {
+        return Ok(());
+    };
+    // Yes means permanently when that's what was asked
+    let cleanup = state.cleanup.clone();
+    // Back to the tree when it was asked from there
+    let back = if state.tree.is_some() {
+        Mode::Tree
+    } else {
+        Mode::List
+    };
+    let (paths, permanent, cleanup, slow) = match std::mem::replace(&mut state.mode, back) {
+        Mode::Confirm(Confirm::Trash(paths)) => (paths, false, cleanup, false),
+        Mode::Confirm(Confirm::SlowDelete(paths)) => (paths, false, cleanup, true),
+        Mode::Confirm(Confirm::PermanentDelete(paths)) => (paths, true, cleanup, false),
+        Mode::Confirm(Confirm::CargoClean(paths)) => (paths, false, Cleanup::Cargo, false),
+        Mode::Confirm(Confirm::LightClean(paths)) => (paths, false, Cleanup::Light, false),
+        mode => {
+            state.mode = mode;
+            return Ok(());
+        }
+    };
+
+    match key {
+        KeyEvent {
+            code: KeyCode::Char('y'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        } => delete_paths(state, paths, permanent, cleanup),
+
+        // Skipping the trash avoids copying and having to empty it later
+        KeyEvent {
+            code: KeyCode::Char('p'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        } if slow => delete_paths(state, paths, true, cleanup),
+
+        // Anything else cancels
+        _ => return Ok(()),
+    }
+    events.next();
+    Ok(())
+}
+
+fn quit(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    state: &mut State,
+    events: &mut Events,
+) -> Result<(), Error> {
+    // Exiting would cut deletes in the background short
+    while state.deleter.pending() > 0 {
+        state.messages.progress(tf(
+            "Waiting for {} deletes to finish before quitting",
+            &[&state.deleter.pending()],
+        ));
+        terminal.draw(|f| draw(f, state, events))?;
+        deleted(state, events, true);
+    }
+    // Deleted targets shouldn't show up next time. A scan cut short found
+    // only some, keeping the cache as it was is better.
+    if state.scan.is_none() && !state.deleted.is_empty() {
+        let _ = save_cache(state);
+    }
+    restore(terminal, state.caps)?;
+
+    // A report on stdout tells the same and more
+    if state.report.as_ref().map(|(_, dest)| dest.as_str()) != Some("-") {
+        print_summary(state);
+    }
+
+    if let Some((format, dest)) = &state.report {
+        let report = Report {
+            groups: report::group_by_root(&state.roots, state.results.clone()),
+            deleted: state.deleted.clone(),
+        };
+        write_report(&report, *format, dest);
+    }
+
+    // Quit
+    exit(0);
+}
+
+// Set while the interface has the terminal in raw mode
+static INTERFACE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the interface has the terminal, nothing else should write to it then
+pub fn has_terminal() -> bool {
+    INTERFACE.load(Ordering::SeqCst)
+}
+
+/// Gives the terminal back when dropped, unless that's done already
+struct TerminalGuard(Caps);
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        reset_terminal(self.0);
+    }
+}
+
+// Leave raw mode and the alternate screen if the interface still has them,
+// for when there is no `Terminal` at hand
+fn reset_terminal(caps: Caps) {
+    if !INTERFACE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let _ = disable_raw_mode();
+    let mut stdout = stdout();
+    if caps.mouse {
+        let _ = execute!(stdout, DisableMouseCapture);
+    }
+    if caps.alternate_screen {
+        let _ = execute!(stdout, LeaveAlternateScreen);
+    }
+    let _ = execute!(stdout, Show);
+}
+
+// Take the terminal over for the interface
+fn enter(terminal: &mut Terminal<CrosstermBackend<Stdout>>, caps: Caps) -> Result<(), Error> {
+    INTERFACE.store(true, Ordering::SeqCst);
+    enable_raw_mode()?;
+    if caps.alternate_screen {
+        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    }
+    if caps.mouse {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    Ok(terminal.clear()?)
+}
+
+// Leave the interface and give the terminal back
+fn restore(terminal: &mut Terminal<CrosstermBackend<Stdout>>, caps: Caps) -> Result<(), Error> {
+    INTERFACE.store(false, Ordering::SeqCst);
+    disable_raw_mode()?;
+    if caps.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    if caps.alternate_screen {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    } else {
+        // Drawn over the normal screen, don't leave it behind
+        terminal.clear()?;
+        execute!(terminal.backend_mut(), MoveTo(0, 0))?;
+    }
+    Ok(terminal.show_cursor()?)
+}
+
+// What happened during the session, printed after leaving the interface
+fn print_summary(state: &State) {
+    let sum = |entries: &[Entry]| size::format_size(entries.iter().map(|e| e.size).sum());
+
+    let found = state.results.len() + state.deleted.len();
+    println!("{}", tf("Found {} target folders", &[&found]));
+    println!(
+        "{}",
+        tf(
+            "Deleted {} ({})",
+            &[&state.deleted.len(), &sum(&state.deleted)]
+        )
+    );
+    if !state.trashed.is_empty() {
+        println!(
+            "{}",
+            tf(
+                "Still in trash {} ({})",
+                &[&state.trashed.len(), &sum(&state.trashed)]
+            )
+        );
+    }
+    if let Some(freed) = freed_since(state.free_at_start, &state.roots[0]) {
+        println!("{}", tf("Freed {}", &[&size::format_size(freed)]));
+    }
+    if !state.failures.is_empty() {
+        println!("{}", tf("Failed {}:", &[&state.failures.len()]));
+        for failure in &state.failures {
+            println!("  {failure}");
+        }
+    }
+    if !state.warnings.is_empty() {
+        println!("{}", tf("Unreadable {}:", &[&state.warnings.len()]));
+        for warning in &state.warnings {
+            println!("  {warning}");
+        }
+    }
+}
+
+fn write_report(report: &Report, format: ReportFormat, dest: &str) {
+    if let Err(e) = report.write(format, dest) {
+        println!("Writing report to {dest} failed: {e}");
+        exit(2);
+    }
+}
@@ -0,0 +1,923 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    auto::Rules,
+    deleter::Cleanup,
+    disk,
+    editors::OpenProjects,
+    excluded,
+    i18n::{self, Lang},
+    keys::Keys,
+    output::Output,
+    quiet::QuietHours,
+    remote::Remote,
+    report::ReportFormat,
+    scan::{self, Kind, Traversal},
+    size::{self, Metric},
+    state::Sort,
+    theme::Theme,
+};
+
+/// Type of an option's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Bool,
+    Number,
+    String,
+    // Comma separated on the command line, array in the config file
+    List,
+}
+
+impl Type {
+    pub fn name(self) -> &'static str {
+        match self {
+            Type::Bool => "boolean",
+            Type::Number => "integer",
+            Type::String => "string",
+            Type::List => "list of strings",
+        }
+    }
+}
+
+/// Option that can be given on the command line as `--name` and in the
+/// config file as `name = ...`
+pub struct Opt {
+    pub name: &'static str,
+    pub ty: Type,
+    pub help: &'static str,
+    // Default value as written in the config file
+    pub default: &'static str,
+}
+
+pub const OPTIONS: &[Opt] = &[
+    Opt {
+        name: "roots",
+        ty: Type::List,
+        help: "folders to scan when none are given, the current folder if empty",
+        default: "[]",
+    },
+    Opt {
+        name: "max-depth",
+        ty: Type::Number,
+        help: "how many folders deep to scan, 0 for no limit",
+        default: "0",
+    },
+    Opt {
+        name: "project-depth",
+        ty: Type::Number,
+        help: "how many folders deep to look for projects, 0 for no limit. Folders inside projects are scanned to --max-depth",
+        default: "0",
+    },
+    Opt {
+        name: "traversal",
+        ty: Type::String,
+        help: "bfs or dfs",
+        default: r#""dfs""#,
+    },
+    Opt {
+        name: "types",
+        ty: Type::List,
+        help: "project types to look for: rust, node, python, cmake, gradle and cachedir",
+        default: r#"["rust"]"#,
+    },
+    Opt {
+        name: "threads",
+        ty: Type::Number,
+        help: "folders scanned in parallel, 0 for one per CPU",
+        default: "0",
+    },
+    Opt {
+        name: "default-excludes",
+        ty: Type::Bool,
+        help: "skip package manager and toolchain folders like ~/.cargo/registry",
+        default: "true",
+    },
+    Opt {
+        name: "exclude",
+        ty: Type::List,
+        help: "globs of folders not to scan, e.g. vendor,*.bak,/mnt/**",
+        default: "[]",
+    },
+    Opt {
+        name: "gitignore",
+        ty: Type::Bool,
+        help: "skip folders ignored by .gitignore and .ignore files",
+        default: "true",
+    },
+    Opt {
+        name: "follow-symlinks",
+        ty: Type::Bool,
+        help: "scan symlinked folders too",
+        default: "false",
+    },
+    Opt {
+        name: "same-filesystem",
+        ty: Type::Bool,
+        help: "don't scan folders on other filesystems than the folder they are in",
+        default: "false",
+    },
+    Opt {
+        name: "skip-unbuilt",
+        ty: Type::Bool,
+        help: "leave out Rust projects without Cargo.lock and targets that hold nothing",
+        default: "false",
+    },
+    Opt {
+        name: "apparent-size",
+        ty: Type::Bool,
+        help: "size targets by the lengths of their files instead of the disk space they take",
+        default: "false",
+    },
+    Opt {
+        name: "older-than",
+        ty: Type::String,
+        help: "only list targets unused for this long, e.g. 30d, 2w or 12h",
+        default: r#""""#,
+    },
+    Opt {
+        name: "min-size",
+        ty: Type::String,
+        help: "leave out targets smaller than this, e.g. 100MB, they aren't worth rebuilding",
+        default: r#""""#,
+    },
+    Opt {
+        name: "active-within",
+        ty: Type::String,
+        help: "flag projects with uncommitted changes or commits this recent, e.g. 14d, and leave them out of bulk deletes",
+        default: r#""""#,
+    },
+    Opt {
+        name: "salvage",
+        ty: Type::Bool,
+        help: "copy release binaries to cargo bin folder before trashing",
+        default: "false",
+    },
+    Opt {
+        name: "permanent",
+        ty: Type::Bool,
+        help: "delete folders for good instead of sending them to trash",
+        default: "false",
+    },
+    Opt {
+        name: "quarantine",
+        ty: Type::String,
+        help: "move trashed folders into this folder instead of the trash, for broken trash",
+        default: r#""""#,
+    },
+    Opt {
+        name: "cargo-clean",
+        ty: Type::Bool,
+        help: "run cargo clean in Rust projects instead of deleting their targets",
+        default: "false",
+    },
+    Opt {
+        name: "cargo-clean-args",
+        ty: Type::String,
+        help: "arguments for cargo clean, e.g. '--release' or '-p foo'",
+        default: r#""""#,
+    },
+    Opt {
+        name: "light-clean",
+        ty: Type::Bool,
+        help: "only remove incremental caches, docs and old artifacts of Rust targets, keeping binaries",
+        default: "false",
+    },
+    Opt {
+        name: "profiles",
+        ty: Type::List,
+        help: "only remove these folders of Rust targets, e.g. debug,doc, keeping the rest like release",
+        default: "[]",
+    },
+    Opt {
+        name: "verify",
+        ty: Type::Bool,
+        help: "check that projects still build after deleting or cleaning their Rust targets",
+        default: "false",
+    },
+    Opt {
+        name: "verify-command",
+        ty: Type::String,
+        help: "command --verify runs in the project",
+        default: r#""cargo check""#,
+    },
+    Opt {
+        name: "preview",
+        ty: Type::Bool,
+        help: "show the biggest entries and files of folders before deleting them",
+        default: "false",
+    },
+    Opt {
+        name: "confirm-single",
+        ty: Type::Bool,
+        help: "ask before deleting a single entry too, bulk deletes always ask",
+        default: "false",
+    },
+    Opt {
+        name: "tombstones",
+        ty: Type::Bool,
+        help: "show deleted entries struck through for a moment before removing them",
+        default: "true",
+    },
+    Opt {
+        name: "sort",
+        ty: Type::String,
+        help: "found, size, path or age, biggest and oldest first",
+        default: r#""found""#,
+    },
+    Opt {
+        name: "group-filesystems",
+        ty: Type::Bool,
+        help: "list targets on the fullest filesystem first, with totals per filesystem",
+        default: "false",
+    },
+    Opt {
+        name: "names",
+        ty: Type::Bool,
+        help: "list targets by project name and a shortened path instead of the full path",
+        default: "false",
+    },
+    Opt {
+        name: "keys",
+        ty: Type::List,
+        help: "keys for list actions, e.g. trash-all=x,quit=q",
+        default: "[]",
+    },
+    Opt {
+        name: "size-colors",
+        ty: Type::List,
+        help: "sizes from which sizes are yellow and red, e.g. 100MB,1GB",
+        default: r#"["100MB", "1GB"]"#,
+    },
+    Opt {
+        name: "theme",
+        ty: Type::List,
+        help: "styles of the interface, e.g. selected=black:cyan,marked=blue+bold",
+        default: "[]",
+    },
+    Opt {
+        name: "editor",
+        ty: Type::String,
+        help: "editor `e` opens projects in, $VISUAL or $EDITOR when empty",
+        default: r#""""#,
+    },
+    Opt {
+        name: "open-projects",
+        ty: Type::String,
+        help: "off, warn or skip projects open in an editor",
+        default: r#""off""#,
+    },
+    Opt {
+        name: "ssh",
+        ty: Type::String,
+        help: "experimental: scan and clean user@host:/path over SSH with the rclin installed there",
+        default: r#""""#,
+    },
+    Opt {
+        name: "ssh-rclin",
+        ty: Type::String,
+        help: "command running rclin on the --ssh host, e.g. ~/.cargo/bin/rclin",
+        default: r#""rclin""#,
+    },
+    Opt {
+        name: "all-users",
+        ty: Type::Bool,
+        help: "scan every user's home and print a report",
+        default: "false",
+    },
+    Opt {
+        name: "list",
+        ty: Type::Bool,
+        help: "print found targets and sizes instead of opening the interface",
+        default: "false",
+    },
+    Opt {
+        name: "output",
+        ty: Type::String,
+        help: "print found targets as json or csv instead of opening the interface",
+        default: r#""""#,
+    },
+    Opt {
+        name: "clean",
+        ty: Type::Bool,
+        help: "trash found targets without opening the interface",
+        default: "false",
+    },
+    Opt {
+        name: "yes",
+        ty: Type::Bool,
+        help: "don't ask before --clean deletes",
+        default: "false",
+    },
+    Opt {
+        name: "dry-run",
+        ty: Type::Bool,
+        help: "make --clean print what it would delete instead of deleting",
+        default: "false",
+    },
+    Opt {
+        name: "max-total",
+        ty: Type::String,
+        help: "budget like 100GB for all targets, the least recently built ones over it are marked or, with --clean, deleted",
+        default: r#""""#,
+    },
+    Opt {
+        name: "auto-older-than",
+        ty: Type::String,
+        help: "rclin auto deletes targets unused for this long, e.g. 60d",
+        default: r#""""#,
+    },
+    Opt {
+        name: "auto-max-total",
+        ty: Type::String,
+        help: "rclin auto deletes the least recently built targets while all of them take more than this, e.g. 50GB",
+        default: r#""""#,
+    },
+    Opt {
+        name: "auto-keep",
+        ty: Type::Number,
+        help: "rclin auto keeps this many of the most recently built targets whatever the other rules say",
+        default: "0",
+    },
+    Opt {
+        name: "watch-interval",
+        ty: Type::Number,
+        help: "seconds between the scans of rclin watch",
+        default: "10",
+    },
+    Opt {
+        name: "ci",
+        ty: Type::Bool,
+        help:
+            "for containers and CI jobs: --clean --yes --permanent --no-history with English output",
+        default: "false",
+    },
+    Opt {
+        name: "emergency",
+        ty: Type::Bool,
+        help: "for a full disk: offer the biggest targets one by one for deleting permanently, without the interface",
+        default: "false",
+    },
+    Opt {
+        name: "quiet-hours",
+        ty: Type::String,
+        help: "time like 09:00-17:00 when --clean --yes leaves things for a later run",
+        default: r#""""#,
+    },
+    Opt {
+        name: "history",
+        ty: Type::Bool,
+        help: "remember scans to show what changed since the last one",
+        default: "true",
+    },
+    Opt {
+        name: "cache",
+        ty: Type::Bool,
+        help: "show the targets of the last scan right away while scanning again",
+        default: "true",
+    },
+    Opt {
+        name: "report",
+        ty: Type::String,
+        help: "write a report to file on exit, - for stdout",
+        default: r#""""#,
+    },
+    Opt {
+        name: "report-format",
+        ty: Type::String,
+        help: "text or html",
+        default: r#""text""#,
+    },
+    Opt {
+        name: "ascii",
+        ty: Type::Bool,
+        help: "draw borders with plain ASCII instead of box drawing characters",
+        default: "false",
+    },
+    Opt {
+        name: "color",
+        ty: Type::Bool,
+        help: "use colors if the terminal has them",
+        default: "true",
+    },
+    Opt {
+        name: "language",
+        ty: Type::String,
+        help: "auto, en or fi, auto follows the locale",
+        default: r#""auto""#,
+    },
+    Opt {
+        name: "verbose",
+        ty: Type::Number,
+        help: "log to stderr, 1 for found targets, warnings and deletes, 2 for every folder skipped too. -v and -vv for short",
+        default: "0",
+    },
+    Opt {
+        name: "log-file",
+        ty: Type::String,
+        help: "append every delete and clean to file, with times",
+        default: r#""""#,
+    },
+    Opt {
+        name: "trace-output",
+        ty: Type::String,
+        help: "write a Chrome trace of scanning, sizing and deleting to file",
+        default: r#""""#,
+    },
+];
+
+pub struct Args {
+    // Folders to scan
+    pub paths: Vec<PathBuf>,
+    // Folder levels to scan below the roots, 0 for no limit
+    pub max_depth: usize,
+    // How deep to look for projects, their insides are scanned to max_depth
+    pub project_depth: usize,
+    pub traversal: Traversal,
+    // Project types to look for
+    pub types: Vec<Kind>,
+    // Scanner threads, 0 picks one per CPU
+    pub threads: usize,
+    pub default_excludes: bool,
+    // Globs of folders not to scan
+    pub exclude: Vec<String>,
+    pub gitignore: bool,
+    // Leave out projects that were never built
+    pub skip_unbuilt: bool,
+    // Whether sizes are disk usage or apparent sizes
+    pub metric: Metric,
+    pub follow_symlinks: bool,
+    // Stay on the filesystem of each root
+    pub same_filesystem: bool,
+    // Only targets nothing was modified in for this long
+    pub older_than: Option<Duration>,
+    // Only targets at least this big
+    pub min_size: Option<u64>,
+    // Projects with uncommitted changes or commits this recent are active
+    pub active_within: Option<Duration>,
+    pub salvage: bool,
+    // Remove instead of trashing, for servers and containers without trash
+    pub permanent: bool,
+    // Folder trashed folders are moved into instead of the trash
+    pub quarantine: Option<PathBuf>,
+    // Let cargo clean Rust targets
+    pub cargo_clean: bool,
+    pub cargo_clean_args: Vec<String>,
+    // Clean Rust targets partly instead of deleting them
+    pub light_clean: bool,
+    // Clean only these profile folders of Rust targets, like debug and doc
+    pub profiles: Vec<String>,
+    // Build projects after their Rust targets are gone, with this command
+    pub verify: bool,
+    pub verify_command: Vec<String>,
+    // Ask before single deletes, not only bulk deletes
+    pub confirm_single: bool,
+    // Show what's inside folders before deleting them
+    pub preview: bool,
+    // Leave deleted entries in the list for a moment
+    pub tombstones: bool,
+    pub sort: Sort,
+    pub group_filesystems: bool,
+    // List targets by project name
+    pub names: bool,
+    // Keys bound to list actions
+    pub keys: Keys,
+    pub size_colors: (u64, u64),
+    // Styles of the interface
+    pub theme: Theme,
+    pub open_projects: OpenProjects,
+    // Editor command, empty for $VISUAL or $EDITOR
+    pub editor: String,
+    // Folder on another machine to work on instead, with the rclin command
+    // there
+    pub ssh: Option<Remote>,
+    pub ssh_rclin: String,
+    // Scan every user's home and print a report
+    pub all_users: bool,
+    // Print targets, or trash them, without the interface
+    pub list: bool,
+    // Print targets in a machine readable format
+    pub output: Option<Output>,
+    pub clean: bool,
+    // Don't ask before cleaning
+    pub yes: bool,
+    // Only tell what --clean would do
+    pub dry_run: bool,
+    // Cleaning in a container or CI job, turns on what that needs
+    pub ci: bool,
+    // Disk is full, delete the biggest targets right away
+    pub emergency: bool,
+    // Budget for the size of all targets, least recently built ones over it
+    // get marked or cleaned
+    pub max_total: Option<u64>,
+    // Rules of `rclin auto`
+    pub auto: Rules,
+    // Seconds between the scans of `rclin watch`
+    pub watch_interval: usize,
+    // When unattended cleaning doesn't run
+    pub quiet_hours: Option<QuietHours>,
+    // Remember scans to compare with the next one
+    pub history: bool,
+    // Show the last scan of the same folders until scanned again
+    pub cache: bool,
+    // Report file, `-` for stdout
+    pub report: Option<String>,
+    pub report_format: ReportFormat,
+    // Plain ASCII borders even if the terminal looks capable
+    pub ascii: bool,
+    // Colors if the terminal has them, or only modifiers like reversed
+    pub color: bool,
+    pub language: Lang,
+    // Events on stderr, 0 for none
+    pub verbose: usize,
+    // Audit trail of deletes
+    pub log_file: Option<String>,
+    // Chrome trace file for profiling
+    pub trace_output: Option<String>,
+}
+
+impl Default for Args {
+    fn default() -> Args {
+        Args {
+            paths: vec![current_dir()],
+            max_depth: 0,
+            project_depth: 0,
+            traversal: Traversal::DepthFirst,
+            types: vec![Kind::Rust],
+            threads: 0,
+            default_excludes: true,
+            exclude: vec![],
+            gitignore: true,
+            skip_unbuilt: false,
+            metric: Metric::Disk,
+            follow_symlinks: false,
+            same_filesystem: false,
+            older_than: None,
+            active_within: None,
+            min_size: None,
+            salvage: false,
+            permanent: false,
+            quarantine: None,
+            cargo_clean: false,
+            cargo_clean_args: vec![],
+            light_clean: false,
+            profiles: vec![],
+            verify: false,
+            verify_command: vec!["cargo".to_string(), "check".to_string()],
+            confirm_single: false,
+            preview: false,
+            tombstones: true,
+            sort: Sort::Found,
+            group_filesystems: false,
+            names: false,
+            keys: Keys::default(),
+            size_colors: (100 << 20, 1 << 30),
+            theme: Theme::default(),
+            open_projects: OpenProjects::Off,
+            editor: String::new(),
+            ssh: None,
+            ssh_rclin: "rclin".to_string(),
+            all_users: false,
+            list: false,
+            output: None,
+            clean: false,
+            yes: false,
+            dry_run: false,
+            ci: false,
+            emergency: false,
+            max_total: None,
+            auto: Rules::default(),
+            watch_interval: 10,
+            quiet_hours: None,
+            history: true,
+            cache: true,
+            report: None,
+            report_format: ReportFormat::Text,
+            ascii: false,
+            color: true,
+            language: i18n::from_locale(),
+            verbose: 0,
+            log_file: None,
+            trace_output: None,
+        }
+    }
+}
+
+impl Args {
+    // Apply process arguments on top of current values. Paths given here
+    // replace the configured roots.
+    pub fn parse(&mut self, args: impl IntoIterator<Item = String>) -> Result<(), String> {
+        let mut args = args.into_iter();
+        let mut paths = vec![];
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bfs" => self.traversal = Traversal::BreadthFirst,
+                "--dfs" => self.traversal = Traversal::DepthFirst,
+                "-v" => self.verbose += 1,
+                "-vv" => self.verbose += 2,
+                // Turn off boolean options
+                _ if arg.starts_with("--no-")
+                    && find(&arg[5..]).is_some_and(|o| o.ty == Type::Bool) =>
+                {
+                    self.set(&arg[5..], "false")?;
+                }
+                _ if arg.starts_with("--") => {
+                    let name = &arg[2..];
+                    let opt = find(name).ok_or_else(|| unknown(&arg, name))?;
+                    if opt.ty == Type::Bool {
+                        self.set(name, "true")?;
+                    } else {
+                        let value = args.next().ok_or(format!("{arg} needs a value"))?;
+                        self.set(name, &value)?;
+                    }
+                }
+                _ => paths.push(disk::plain(Path::new(&arg))),
+            }
+        }
+
+        if !paths.is_empty() {
+            self.paths = paths;
+        }
+        Ok(())
+    }
+
+    /// Set option `name` from its string form
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match canonical(name) {
+            "roots" => {
+                self.paths = parse_list(value)
+                    .iter()
+                    .map(|path| disk::plain(Path::new(path)))
+                    .collect();
+                if self.paths.is_empty() {
+                    self.paths.push(current_dir());
+                }
+            }
+            "max-depth" => self.max_depth = parse_number(value)?,
+            "project-depth" => self.project_depth = parse_number(value)?,
+            "traversal" => self.traversal = value.parse()?,
+            "types" => self.types = parse_types(value)?,
+            "threads" => self.threads = parse_number(value)?,
+            "default-excludes" => self.default_excludes = parse_bool(value)?,
+            "exclude" => self.exclude = parse_list(value),
+            "gitignore" => self.gitignore = parse_bool(value)?,
+            "skip-unbuilt" => self.skip_unbuilt = parse_bool(value)?,
+            "apparent-size" if parse_bool(value)? => self.metric = Metric::Apparent,
+            "apparent-size" => self.metric = Metric::Disk,
+            "follow-symlinks" => self.follow_symlinks = parse_bool(value)?,
+            "same-filesystem" => self.same_filesystem = parse_bool(value)?,
+            "older-than" => self.older_than = parse_age(value)?,
+            "active-within" => self.active_within = parse_age(value)?,
+            "min-size" if value.is_empty() => self.min_size = None,
+            "min-size" => self.min_size = Some(size::parse_size(value)?),
+            "salvage" => self.salvage = parse_bool(value)?,
+            "permanent" => self.permanent = parse_bool(value)?,
+            "quarantine" if value.is_empty() => self.quarantine = None,
+            "quarantine" => self.quarantine = Some(parse_folder(value)),
+            "cargo-clean" => self.cargo_clean = parse_bool(value)?,
+            "light-clean" => self.light_clean = parse_bool(value)?,
+            "profiles" => self.profiles = parse_list(value),
+            "cargo-clean-args" => {
+                self.cargo_clean_args = value.split_whitespace().map(String::from).collect()
+            }
+            "verify" => self.verify = parse_bool(value)?,
+            "verify-command" => {
+                self.verify_command = value.split_whitespace().map(String::from).collect()
+            }
+            "confirm-single" => self.confirm_single = parse_bool(value)?,
+            "preview" => self.preview = parse_bool(value)?,
+            "tombstones" => self.tombstones = parse_bool(value)?,
+            "sort" => self.sort = value.parse()?,
+            "group-filesystems" => self.group_filesystems = parse_bool(value)?,
+            "names" => self.names = parse_bool(value)?,
+            "keys" => self.keys = value.parse()?,
+            "size-colors" => self.size_colors = parse_size_colors(value)?,
+            "theme" => self.theme = value.parse()?,
+            "open-projects" => self.open_projects = value.parse()?,
+            "editor" => self.editor = value.to_string(),
+            "ssh" if value.is_empty() => self.ssh = None,
+            "ssh" => self.ssh = Some(value.parse()?),
+            "ssh-rclin" => self.ssh_rclin = value.to_string(),
+            "all-users" => self.all_users = parse_bool(value)?,
+            "list" => self.list = parse_bool(value)?,
+            "output" if value.is_empty() => self.output = None,
+            "output" => self.output = Some(value.parse()?),
+            "clean" => self.clean = parse_bool(value)?,
+            "yes" => self.yes = parse_bool(value)?,
+            "dry-run" => self.dry_run = parse_bool(value)?,
+            "emergency" => self.emergency = parse_bool(value)?,
+            "ci" => {
+                self.ci = parse_bool(value)?;
+                // Options after it can still change these
+                if self.ci {
+                    self.clean = true;
+                    self.yes = true;
+                    self.permanent = true;
+                    self.history = false;
+                    self.language = Lang::En;
+                }
+            }
+            "max-total" if value.is_empty() => self.max_total = None,
+            "max-total" => self.max_total = Some(size::parse_size(value)?),
+            "auto-older-than" => self.auto.older_than = parse_age(value)?,
+            "auto-max-total" if value.is_empty() => self.auto.max_total = None,
+            "auto-max-total" => self.auto.max_total = Some(size::parse_size(value)?),
+            "auto-keep" => self.auto.keep = parse_number(value)?,
+            "watch-interval" => self.watch_interval = parse_number(value)?,
+            "quiet-hours" if value.is_empty() => self.quiet_hours = None,
+            "quiet-hours" => self.quiet_hours = Some(value.parse()?),
+            "history" => self.history = parse_bool(value)?,
+            "cache" => self.cache = parse_bool(value)?,
+            "report" if value.is_empty() => self.report = None,
+            "report" => self.report = Some(value.to_string()),
+            "report-format" => self.report_format = value.parse()?,
+            "ascii" => self.ascii = parse_bool(value)?,
+            "color" => self.color = parse_bool(value)?,
+            "language" => self.language = value.parse()?,
+            "verbose" => self.verbose = parse_number(value)?,
+            "log-file" if value.is_empty() => self.log_file = None,
+            "log-file" => self.log_file = Some(value.to_string()),
+            "trace-output" if value.is_empty() => self.trace_output = None,
+            "trace-output" => self.trace_output = Some(value.to_string()),
+            _ => return Err(format!("unknown option '{name}'")),
+        }
+        Ok(())
+    }
+
+    /// How Rust targets are cleaned up, removed unless a clean is asked for
+    pub fn cleanup(&self) -> Cleanup {
+        if self.cargo_clean {
+            Cleanup::Cargo
+        } else if self.light_clean {
+            Cleanup::Light
+        } else if !self.profiles.is_empty() {
+            Cleanup::Profiles(self.profiles.clone())
+        } else {
+            Cleanup::Remove
+        }
+    }
+
+    /// Scanner options for scanning the home folder `home`
+    pub fn scan_options(&self, home: Option<&Path>) -> scan::Options {
+        let mut excludes = if self.default_excludes {
+            scan::default_excludes(home)
+        } else {
+            vec![]
+        };
+        // Projects excluded from the list for good
+        excludes.extend(excluded::path().map_or(vec![], |path| excluded::load(&path)));
+
+        let threads = match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+
+        scan::Options {
+            traversal: self.traversal,
+            threads,
+            max_depth: self.max_depth,
+            project_depth: self.project_depth,
+            excludes,
+            globs: self.exclude.clone(),
+            gitignore: self.gitignore,
+            skip_unbuilt: self.skip_unbuilt,
+            follow_symlinks: self.follow_symlinks,
+            same_filesystem: self.same_filesystem,
+            active_within: self.active_within,
+            kinds: self.types.clone(),
+        }
+    }
+}
+
+fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+// Folder from the current folder, or the home folder with `~/` like in a
+// config file, where the shell doesn't expand it
+fn parse_folder(s: &str) -> PathBuf {
+    match (s.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => current_dir().join(s),
+    }
+}
+
+pub fn find(name: &str) -> Option<&'static Opt> {
+    let name = canonical(name);
+    OPTIONS.iter().find(|o| o.name == name)
+}
+
+/// Earlier names of options, still taken for the new ones so command lines
+/// and config files written for them keep working
+pub const ALIASES: &[(&str, &str)] = &[("max-total-size", "max-total")];
+
+// Name of the option `name` is an earlier name of, or `name` itself
+fn canonical(name: &str) -> &str {
+    ALIASES
+        .iter()
+        .find(|(old, _)| *old == name)
+        .map_or(name, |(_, new)| new)
+}
+
+/// Closest option name to a misspelled one
+pub fn suggest(name: &str) -> Option<&'static str> {
+    OPTIONS
+        .iter()
+        .map(|o| (distance(name, o.name), o.name))
+        .filter(|(d, _)| *d <= 3)
+        .min()
+        .map(|(_, name)| name)
+}
+
+fn unknown(arg: &str, name: &str) -> String {
+    match suggest(name) {
+        Some(s) => format!("unknown option '{arg}', did you mean '--{s}'?"),
+        None => format!("unknown option '{arg}'"),
+    }
+}
+
+/// Levenshtein distance
+pub fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(prev + cost);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+    match s {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("expected true or false, found '{s}'")),
+    }
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("expected a number, found '{s}'"))
+}
+
+/// Age like `30d`, `2w` or `12h`, plain numbers are days. Empty or zero is no
+/// limit.
+pub fn parse_age(s: &str) -> Result<Option<Duration>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "d"),
+    };
+    let hours = match unit.trim() {
+        "h" => 1,
+        "d" => 24,
+        "w" => 24 * 7,
+        _ => return Err(format!("expected an age like 30d, 2w or 12h, found '{s}'")),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("expected an age like 30d, 2w or 12h, found '{s}'"))?;
+    Ok((number > 0).then(|| Duration::from_secs(number * hours * 60 * 60)))
+}
+
+// Comma separated values, empty ones left out
+fn parse_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Parse comma separated project types, e.g. `rust,node`
+fn parse_types(s: &str) -> Result<Vec<Kind>, String> {
+    let types = parse_list(s)
+        .iter()
+        .map(|t| t.parse())
+        .collect::<Result<Vec<Kind>, String>>()?;
+    if types.is_empty() {
+        return Err("expected at least one project type".to_string());
+    }
+    Ok(types)
+}
+
+// Parse yellow and red thresholds, e.g. `100MB,1GB`
+fn parse_size_colors(s: &str) -> Result<(u64, u64), String> {
+    let (yellow, red) = s
+        .split_once(',')
+        .ok_or("expected two sizes, e.g. 100MB,1GB")?;
+    Ok((size::parse_size(yellow)?, size::parse_size(red)?))
+}
@@ -0,0 +1,140 @@
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    history,
+    scan::{self, Entry},
+};
+
+/// File `rclin auto` logs what it did in, next to the history
+pub fn log_path() -> Option<PathBuf> {
+    Some(history::path()?.with_file_name("auto.log"))
+}
+
+/// Append `lines` to the log at `path`
+pub fn log(path: &Path, lines: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(lines.as_bytes())
+}
+
+/// When unattended cleaning deletes targets
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    // Targets unused for this long go
+    pub older_than: Option<Duration>,
+    // The least recently built go while all of them take more
+    pub max_total: Option<u64>,
+    // This many of the most recently built are kept whatever the rest says
+    pub keep: usize,
+}
+
+/// Which rule picked a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Old,
+    OverBudget,
+}
+
+impl Rules {
+    /// Whether there's a rule at all, keeping alone deletes nothing
+    pub fn is_empty(&self) -> bool {
+        self.older_than.is_none() && self.max_total.is_none()
+    }
+
+    /// Targets of `entries` the rules delete and why, old ones first, then
+    /// the ones over the budget once the old ones are gone, least recently
+    /// built first. Only entries `deletable` says can be deleted are picked.
+    pub fn pick<'a>(
+        &self,
+        entries: &'a [Entry],
+        deletable: impl Fn(&Entry) -> bool,
+    ) -> Vec<(&'a Entry, Reason)> {
+        let mut recent: Vec<&Entry> = entries.iter().collect();
+        recent.sort_by_key(|e| Reverse(e.modified));
+        let kept: HashSet<&Path> = recent
+            .iter()
+            .take(self.keep)
+            .map(|e| e.path.as_path())
+            .collect();
+        let deletable = |e: &Entry| !kept.contains(e.path.as_path()) && deletable(e);
+        let old = |e: &Entry| self.older_than.is_some_and(|age| e.is_older(age)) && deletable(e);
+
+        let mut picked: Vec<(&Entry, Reason)> = entries
+            .iter()
+            .filter(|e| old(e))
+            .map(|e| (e, Reason::Old))
+            .collect();
+        if let Some(budget) = self.max_total {
+            // The old ones are gone by then, only the rest has to fit
+            let gone: u64 = picked.iter().map(|(e, _)| e.size).sum();
+            let over = scan::over_budget(entries, budget.saturating_add(gone), |e| {
+                deletable(e) && !old(e)
+            });
+            picked.extend(over.into_iter().map(|e| (e, Reason::OverBudget)));
+        }
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::{scan::Kind, size::Stats};
+
+    fn entry(path: &str, size: u64, days: u64) -> Entry {
+        let stats = Stats {
+            size,
+            modified: Some(SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60)),
+            files: 1,
+            shared: 0,
+            skipped: vec![],
+        };
+        Entry::with_stats(path.into(), path.into(), Kind::Node, stats)
+    }
+
+    #[test]
+    fn picks_old_then_over_budget_keeping_recent() {
+        let entries = [
+            entry("/p/a", 10, 100),
+            entry("/p/b", 30, 40),
+            entry("/p/c", 20, 30),
+            entry("/p/d", 40, 1),
+            entry("/p/e", 50, 200),
+        ];
+        let rules = Rules {
+            older_than: Some(Duration::from_secs(60 * 24 * 60 * 60)),
+            max_total: Some(60),
+            keep: 2,
+        };
+        let picked: Vec<(&Path, Reason)> = rules
+            .pick(&entries, |e| e.path != Path::new("/p/e"))
+            .into_iter()
+            .map(|(e, reason)| (e.path.as_path(), reason))
+            .collect();
+        // e can't be deleted and c and d are the most recent, so only b goes
+        // for the budget and the rest stays over it
+        assert_eq!(
+            picked,
+            [
+                (Path::new("/p/a"), Reason::Old),
+                (Path::new("/p/b"), Reason::OverBudget)
+            ]
+        );
+
+        assert!(Rules::default().is_empty());
+        assert!(Rules::default().pick(&entries, |_| true).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+
+use tui::widgets::ListState;
+
+use crate::size;
+
+/// File or folder inside the browsed target
+pub struct Item {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// Read-only view into the contents of a target folder
+pub struct Browser {
+    // Target folder the browser was opened at, can't go above it
+    pub root: PathBuf,
+    pub dir: PathBuf,
+    pub items: Vec<Item>,
+    pub state: ListState,
+}
+
+impl Browser {
+    pub fn new(root: &Path) -> Browser {
+        let mut browser = Browser {
+            root: root.to_path_buf(),
+            dir: root.to_path_buf(),
+            items: vec![],
+            state: ListState::default(),
+        };
+        browser.load();
+        browser
+    }
+
+    // Read current dir, biggest entries first
+    fn load(&mut self) {
+        self.items = std::fs::read_dir(&self.dir)
+            .map(|dir| {
+                dir.flatten()
+                    .filter_map(|entry| {
+                        let meta = entry.metadata().ok()?;
+                        let size = if meta.is_dir() {
+                            size::dir_size(&entry.path())
+                        } else {
+                            size::usage(&meta)
+                        };
+                        Some(Item {
+                            name: entry.file_name().to_string_lossy().to_string(),
+                            size,
+                            is_dir: meta.is_dir(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.items
+            .sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+
+        let selected = if self.items.is_empty() { None } else { Some(0) };
+        self.state = ListState::default();
+        self.state.select(selected);
+    }
+
+    pub fn size(&self) -> u64 {
+        self.items.iter().map(|i| i.size).sum()
+    }
+
+    // Descend into selected folder
+    pub fn enter(&mut self) {
+        if let Some(item) = self.state.selected().and_then(|i| self.items.get(i)) {
+            if item.is_dir {
+                self.dir = self.dir.join(&item.name);
+                self.load();
+            }
+        }
+    }
+
+    // Go to parent folder. Returns false when already at the root.
+    pub fn back(&mut self) -> bool {
+        if self.dir == self.root {
+            return false;
+        }
+        self.dir.pop();
+        self.load();
+        true
+    }
+
+    pub fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.items.len() => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(0) | None => self.items.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(i));
+    }
+}
+
+// Biggest files a preview lists
+const BIGGEST: usize = 5;
+
+/// What deleting some folders removes, for a last look before confirming
+pub struct Preview {
+    // Entries of the folder when there is one, biggest first
+    pub items: Vec<Item>,
+    // Biggest files and their size, biggest first. Paths are inside the
+    // folder when there is one.
+    pub biggest: Vec<(PathBuf, u64)>,
+}
+
+impl Preview {
+    pub fn of(paths: &[PathBuf]) -> Preview {
+        let mut biggest = vec![];
+        let mut items = vec![];
+        for root in paths {
+            let Ok(dir) = std::fs::read_dir(root) else {
+                continue;
+            };
+            for entry in dir.flatten() {
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                let size = if meta.is_dir() {
+                    size::usage(&meta) + walk(&entry.path(), &mut biggest)
+                } else {
+                    keep(&mut biggest, entry.path(), size::usage(&meta));
+                    size::usage(&meta)
+                };
+                items.push(Item {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    size,
+                    is_dir: meta.is_dir(),
+                });
+            }
+        }
+
+        if paths.len() == 1 {
+            let root = &paths[0];
+            for (path, _) in &mut biggest {
+                *path = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            }
+            items.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+        } else {
+            items.clear();
+        }
+        Preview { items, biggest }
+    }
+}
+
+// Disk usage of files in `dir`, keeping the biggest of them. Symlinks are not
+// followed.
+fn walk(dir: &Path, biggest: &mut Vec<(PathBuf, u64)>) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut size = 0;
+    for entry in entries.flatten() {
+        if let Ok(meta) = entry.metadata() {
+            if meta.is_dir() {
+                size += size::usage(&meta) + walk(&entry.path(), biggest);
+            } else if meta.is_file() {
+                size += size::usage(&meta);
+                keep(biggest, entry.path(), size::usage(&meta));
+            }
+        }
+    }
+    size
+}
+
+fn keep(biggest: &mut Vec<(PathBuf, u64)>, path: PathBuf, size: u64) {
+    if biggest.len() == BIGGEST && biggest.last().is_some_and(|(_, s)| *s >= size) {
+        return;
+    }
+    biggest.push((path, size));
+    biggest.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    biggest.truncate(BIGGEST);
+}
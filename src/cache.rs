@@ -0,0 +1,165 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    scan::{Entry, Kind},
+    size::Stats,
+};
+
+// Scans of this many sets of folders are kept, the least recent are forgotten
+const SCANS: usize = 20;
+
+/// File the last scan of each set of folders is cached in
+pub fn path() -> Option<PathBuf> {
+    let dir = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?).join("rclin")
+    } else if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+        PathBuf::from(dir).join("rclin")
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join(".cache")
+            .join("rclin")
+    };
+
+    Some(dir.join("scan"))
+}
+
+// Lines are `scan <key>` followed by the targets of that scan as
+// `<type> <size> <files> <shared> <seconds since epoch or -> <project> <path>`,
+// separated by tabs
+fn read(path: &Path) -> Vec<(String, Vec<String>)> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let mut scans: Vec<(String, Vec<String>)> = vec![];
+    for line in content.lines() {
+        match line.split_once('\t') {
+            Some(("scan", key)) => scans.push((key.to_string(), vec![])),
+            _ => {
+                if let Some((_, targets)) = scans.last_mut() {
+                    targets.push(line.to_string());
+                }
+            }
+        }
+    }
+    scans
+}
+
+/// Targets the last scan with `key` found, as they were then. Lines that
+/// don't parse are left out.
+pub fn load(path: &Path, key: &str) -> Vec<Entry> {
+    let Some((_, targets)) = read(path).into_iter().find(|(k, _)| k == key) else {
+        return vec![];
+    };
+    targets.iter().filter_map(|line| parse(line)).collect()
+}
+
+fn parse(line: &str) -> Option<Entry> {
+    let mut fields = line.splitn(7, '\t');
+    let kind: Kind = fields.next()?.to_lowercase().parse().ok()?;
+    let size = fields.next()?.parse().ok()?;
+    let files = fields.next()?.parse().ok()?;
+    let shared = fields.next()?.parse().ok()?;
+    let modified = match fields.next()? {
+        "-" => None,
+        secs => Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs.parse().ok()?)),
+    };
+    let project = PathBuf::from(fields.next()?);
+    let path = PathBuf::from(fields.next()?);
+    let stats = Stats {
+        size,
+        modified,
+        files,
+        shared,
+        skipped: vec![],
+    };
+    Some(Entry::with_stats(path, project, kind, stats))
+}
+
+/// Cache `entries` as the last scan with `key`, keeping other scans
+pub fn save(path: &Path, key: &str, entries: &[Entry]) -> std::io::Result<()> {
+    let mut content = format!("scan\t{key}\n");
+    for entry in entries {
+        // Written as text, the scan finds the rest anyway. Tabs and newlines
+        // would split fields and lines like in history.
+        let (Some(project), Some(target)) = (entry.project.to_str(), entry.path.to_str()) else {
+            continue;
+        };
+        if project.contains(['\t', '\n']) || target.contains(['\t', '\n']) {
+            continue;
+        }
+        let modified = entry
+            .modified
+            .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map_or("-".to_string(), |d| d.as_secs().to_string());
+        content.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{modified}\t{}\t{}\n",
+            entry.kind.name(),
+            entry.size,
+            entry.files,
+            entry.shared,
+            project,
+            target
+        ));
+    }
+    // Latest first
+    for (other, targets) in read(path)
+        .into_iter()
+        .filter(|(k, _)| k != key)
+        .take(SCANS - 1)
+    {
+        content.push_str(&format!("scan\t{other}\n"));
+        for target in targets {
+            content.push_str(&target);
+            content.push('\n');
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Written next to it and renamed over it, so it's never half written
+    let new = path.with_extension("new");
+    std::fs::File::create(&new)?.write_all(content.as_bytes())?;
+    std::fs::rename(new, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+
+    fn entry(path: &str, size: u64) -> Entry {
+        let stats = Stats {
+            size,
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            files: 3,
+            shared: 1,
+            skipped: vec![],
+        };
+        let project = Path::new(path).parent().unwrap().to_path_buf();
+        Entry::with_stats(path.into(), project, Kind::Node, stats)
+    }
+
+    #[test]
+    fn loads_what_was_saved_by_key() {
+        let fx = Fixture::new("cache");
+        let path = fx.path("scan");
+        save(&path, "a", &[entry("/p/a/node_modules", 10)]).unwrap();
+        let odd = [entry("/p/b/node_modules", 20), entry("/p/t\tab/x", 30)];
+        save(&path, "b", &odd).unwrap();
+
+        let a = load(&path, "a");
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].path, Path::new("/p/a/node_modules"));
+        assert_eq!(a[0].project, Path::new("/p/a"));
+        assert_eq!((a[0].size, a[0].files, a[0].shared), (10, 3, 1));
+        assert_eq!(a[0].modified, entry("/p/a/node_modules", 10).modified);
+        // The path with a tab is left out instead of splitting its line
+        let b = load(&path, "b");
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[0].path, Path::new("/p/b/node_modules"));
+        assert!(load(&path, "c").is_empty());
+    }
+}
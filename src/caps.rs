@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+
+use tui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+/// What the terminal can do, so the interface can degrade on minimal
+/// consoles and serial terminals instead of turning into garbage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caps {
+    // Draw on the alternate screen, otherwise over the normal one
+    pub alternate_screen: bool,
+    pub colors: bool,
+    // Box drawing characters render properly
+    pub unicode: bool,
+    pub mouse: bool,
+}
+
+impl Caps {
+    const FULL: Caps = Caps {
+        alternate_screen: true,
+        colors: true,
+        unicode: true,
+        mouse: true,
+    };
+
+    /// Guess from `TERM`, its terminfo entry, `NO_COLOR` and the locale
+    pub fn detect() -> Caps {
+        let term = std::env::var("TERM").unwrap_or_default();
+
+        // Windows consoles don't set TERM and handle everything
+        if cfg!(windows) && term.is_empty() {
+            return Caps::FULL;
+        }
+        if term.is_empty() || term == "dumb" {
+            return Caps {
+                alternate_screen: false,
+                colors: false,
+                unicode: false,
+                mouse: false,
+            };
+        }
+
+        // Guess from the name when there's no terminfo entry
+        let serial = term.starts_with("vt") || term == "ansi";
+        let mono = term.ends_with("-m") || term.ends_with("-mono");
+        let (colors, alternate_screen) = match terminfo(&term) {
+            Some(info) => (info.max_colors >= 8, info.alternate_screen),
+            None => (!mono && term != "vt100", !serial),
+        };
+
+        Caps {
+            alternate_screen,
+            colors: colors && std::env::var_os("NO_COLOR").is_none(),
+            unicode: !serial && utf8_locale(),
+            // The Linux console has no mouse reporting without gpm
+            mouse: alternate_screen && !serial && term != "linux",
+        }
+    }
+}
+
+// Only a locale with another charset, like `fi_FI.ISO-8859-15`, rules out
+// UTF-8. Unset, C and POSIX are common in containers on terminals that are
+// fine with it.
+fn utf8_locale() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+
+    let Some(locale) = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+    else {
+        return true;
+    };
+
+    let locale = locale.to_ascii_lowercase();
+    matches!(locale.as_str(), "c" | "posix") || locale.contains("utf-8") || locale.contains("utf8")
+}
+
+// The few terminfo capabilities we care about
+struct Terminfo {
+    max_colors: i32,
+    alternate_screen: bool,
+}
+
+// Read the compiled terminfo entry of `term`, see term(5)
+fn terminfo(term: &str) -> Option<Terminfo> {
+    let first = term.chars().next()?;
+    let data = terminfo_dirs().into_iter().find_map(|dir| {
+        // Entries are in folders named by the first letter, or its hex code on macOS
+        std::fs::read(dir.join(first.to_string()).join(term))
+            .or_else(|_| std::fs::read(dir.join(format!("{:x}", first as u32)).join(term)))
+            .ok()
+    })?;
+
+    let short = |i: usize| -> Option<i32> {
+        let bytes = data.get(i..i + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]) as i32)
+    };
+
+    // Magic tells whether numbers are 16 or 32 bits
+    let number_size = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names = short(2)? as usize;
+    let bools = short(4)? as usize;
+    let numbers = short(6)? as usize;
+
+    let mut offset = 12 + names + bools;
+    // Numbers start on an even byte
+    offset += offset % 2;
+
+    // max_colors is number 13
+    let max_colors = if numbers > 13 {
+        let at = offset + 13 * number_size;
+        let bytes = data.get(at..at + number_size)?;
+        match number_size {
+            2 => i16::from_le_bytes([bytes[0], bytes[1]]) as i32,
+            _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    } else {
+        -1
+    };
+    offset += numbers * number_size;
+
+    // enter_ca_mode, switching to the alternate screen, is string 28
+    let strings = short(8)? as usize;
+    let alternate_screen = strings > 28 && short(offset + 28 * 2)? >= 0;
+
+    Some(Terminfo {
+        max_colors,
+        alternate_screen,
+    })
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = std::env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    for dir in [
+        "/etc/terminfo",
+        "/lib/terminfo",
+        "/usr/share/terminfo",
+        "/usr/lib/terminfo",
+    ] {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs
+}
+
+/// Drawn last over the whole screen, takes out what the terminal can't show
+pub struct Degrade(pub Caps);
+
+impl Widget for Degrade {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                if !self.0.unicode {
+                    if let Some(ascii) = ascii(&cell.symbol) {
+                        cell.set_symbol(ascii);
+                    }
+                }
+                if !self.0.colors {
+                    cell.set_fg(Color::Reset).set_bg(Color::Reset);
+                }
+            }
+        }
+    }
+}
+
+// Plain ASCII for box drawing characters
+fn ascii(symbol: &str) -> Option<&'static str> {
+    match symbol {
+        "─" | "═" | "━" => Some("-"),
+        "│" | "║" | "┃" => Some("|"),
+        "┌" | "┐" | "└" | "┘" | "╭" | "╮" | "╰" | "╯" | "╔" | "╗" | "╚" | "╝" | "┏" | "┓" | "┗"
+        | "┛" => Some("+"),
+        _ => None,
+    }
+}
@@ -0,0 +1,569 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::mpsc,
+};
+
+/// Find where cargo puts build artifacts for the project in `project`.
+///
+/// `CARGO_TARGET_DIR` or `CARGO_BUILD_TARGET_DIR` win, relative to the
+/// current folder. Otherwise cargo reads `build.target-dir` from
+/// `.cargo/config.toml` (or the legacy `.cargo/config`) in the project folder
+/// and all of its parents, the closest one winning, and then from the one in
+/// `CARGO_HOME`. Relative paths are relative to the folder containing `.cargo`.
+pub fn target_dir(project: &Path) -> PathBuf {
+    configured_target_dir(project).map_or_else(|| project.join("target"), |(target, _)| target)
+}
+
+/// Target folder set in the environment or a cargo config, and what set it
+pub fn configured_target_dir(project: &Path) -> Option<(PathBuf, String)> {
+    for var in ["CARGO_TARGET_DIR", "CARGO_BUILD_TARGET_DIR"] {
+        if let Some(target) = std::env::var_os(var).filter(|t| !t.is_empty()) {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            return Some((normalize(&cwd.join(target)), var.to_string()));
+        }
+    }
+
+    let dirs = project.ancestors().map(|dir| dir.join(".cargo"));
+    for dir in dirs.chain(cargo_home()) {
+        for name in ["config.toml", "config"] {
+            let config = dir.join(name);
+            if let Some(target) = read_target_dir(&config) {
+                let base = dir.parent().unwrap_or(&dir);
+                let source = format!("build.target-dir in {}", config.display());
+                return Some((normalize(&base.join(target)), source));
+            }
+        }
+    }
+    None
+}
+
+// `.` and `..` taken out without looking at the disk, so projects pointing at
+// the same shared folder in different ways find it by the same path
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normal.pop();
+            }
+            component => normal.push(component),
+        }
+    }
+    normal
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("CARGO_HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".cargo"))
+}
+
+// Read build.target-dir from a cargo config file, if present
+fn read_target_dir(config: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(config).ok()?;
+    let value = content.parse::<toml::Value>().ok()?;
+
+    value
+        .get("build")?
+        .get("target-dir")?
+        .as_str()
+        .map(PathBuf::from)
+}
+
+/// Find the executables cargo built into `<target>/release`
+pub fn release_binaries(target: &Path) -> Vec<PathBuf> {
+    let Ok(dir) = std::fs::read_dir(target.join("release")) else {
+        return vec![];
+    };
+
+    let mut binaries: Vec<PathBuf> = dir
+        .flatten()
+        .filter(|entry| entry.metadata().is_ok_and(|m| m.is_file()))
+        .map(|entry| entry.path())
+        .filter(|path| is_executable(path))
+        .collect();
+    binaries.sort();
+    binaries
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Shared libraries are executable too, real binaries have no extension
+    path.extension().is_none()
+        && std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "exe")
+}
+
+/// Folder where `cargo install` puts binaries
+pub fn bin_dir() -> Option<PathBuf> {
+    Some(cargo_home()?.join("bin"))
+}
+
+/// Copy release binaries of `target` into cargo's bin folder. Returns the
+/// names of the copied binaries.
+pub fn salvage(target: &Path) -> std::io::Result<Vec<String>> {
+    let binaries = release_binaries(target);
+    if binaries.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let bin = bin_dir().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "cannot find cargo bin folder")
+    })?;
+    std::fs::create_dir_all(&bin)?;
+
+    let mut names = vec![];
+    for binary in binaries {
+        if let Some(name) = binary.file_name() {
+            std::fs::copy(&binary, bin.join(name))?;
+            names.push(name.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(names)
+}
+
+fn manifest(project: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(project.join("Cargo.toml")).ok()?;
+    content.parse().ok()
+}
+
+fn package_name(manifest: &toml::Value) -> Option<String> {
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(String::from)
+}
+
+/// Package names of the workspace rooted at `project`, or None if it's not a
+/// workspace root. The root package counts as a member too.
+pub fn workspace_members(project: &Path) -> Option<Vec<String>> {
+    let manifest = manifest(project)?;
+    let workspace = manifest.get("workspace")?;
+
+    let mut members = vec![];
+    if let Some(name) = package_name(&manifest) {
+        members.push(name);
+    }
+
+    let patterns = workspace.get("members").and_then(|m| m.as_array());
+    for pattern in patterns.into_iter().flatten().filter_map(|p| p.as_str()) {
+        for path in expand_glob(project, pattern) {
+            if let Some(name) = self::manifest(&path).as_ref().and_then(package_name) {
+                members.push(name);
+            }
+        }
+    }
+
+    Some(members)
+}
+
+/// Root of the workspace `project` is a member of, if it is in one and isn't
+/// the root itself. Like cargo, `package.workspace` points to the root, or
+/// else it's the closest parent folder whose `[workspace]` lists the project
+/// as a member and doesn't exclude it.
+pub fn workspace_root(project: &Path) -> Option<PathBuf> {
+    let own = manifest(project)?;
+    if own.get("workspace").is_some() {
+        return None;
+    }
+    let explicit = own
+        .get("package")
+        .and_then(|p| p.get("workspace"))
+        .and_then(|w| w.as_str());
+    if let Some(root) = explicit {
+        let root = project.join(root);
+        return manifest(&root)?.get("workspace").is_some().then_some(root);
+    }
+
+    let project = project.canonicalize().ok()?;
+    for dir in project.ancestors().skip(1) {
+        let Some(workspace) = manifest(dir).and_then(|m| m.get("workspace").cloned()) else {
+            continue;
+        };
+        let listed = |key: &str| {
+            let patterns = workspace.get(key).and_then(|m| m.as_array());
+            patterns
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.as_str())
+                .flat_map(|pattern| expand_glob(dir, pattern))
+                .any(|member| member.canonicalize().is_ok_and(|m| project.starts_with(m)))
+        };
+        return (listed("members") && !listed("exclude")).then(|| dir.to_path_buf());
+    }
+    None
+}
+
+/// Name a workspace is shown with: its root package, or else its folder
+pub fn workspace_name(root: &Path) -> String {
+    manifest(root)
+        .as_ref()
+        .and_then(package_name)
+        .or_else(|| Some(root.file_name()?.to_string_lossy().to_string()))
+        .unwrap_or_else(|| root.display().to_string())
+}
+
+/// Name of the package in `project` and the names of its workspace members
+pub fn crate_names(project: &Path) -> Vec<String> {
+    let mut names = workspace_members(project).unwrap_or_default();
+    if names.is_empty() {
+        names.extend(self::manifest(project).as_ref().and_then(package_name));
+    }
+    names
+}
+
+// Expand member patterns like `crates/*`, one path component at a time
+fn expand_glob(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![base.to_path_buf()];
+
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        if !component.contains(['*', '?']) {
+            paths = paths.into_iter().map(|p| p.join(component)).collect();
+            continue;
+        }
+
+        paths = paths
+            .iter()
+            .filter_map(|p| std::fs::read_dir(p).ok())
+            .flat_map(|dir| dir.flatten())
+            .filter(|entry| wildcard_match(component, &entry.file_name().to_string_lossy()))
+            .map(|entry| entry.path())
+            .collect();
+    }
+
+    paths.retain(|p| p.is_dir());
+    paths.sort();
+    paths
+}
+
+// Match `*` and `?` wildcards
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    fn matches(p: &[char], n: &[char]) -> bool {
+        match (p.first(), n.first()) {
+            (None, None) => true,
+            (Some('*'), _) => matches(&p[1..], n) || (!n.is_empty() && matches(p, &n[1..])),
+            (Some('?'), Some(_)) => matches(&p[1..], &n[1..]),
+            (Some(a), Some(b)) if a == b => matches(&p[1..], &n[1..]),
+            _ => false,
+        }
+    }
+
+    matches(&pattern, &name)
+}
+
+/// How much of `target` each member's artifacts take. Artifacts are named
+/// `<crate>-<hash>` in deps, build, .fingerprint and incremental folders of
+/// every profile, so they can be attributed by name.
+pub fn member_sizes(target: &Path, members: &[String]) -> Vec<(String, u64)> {
+    let mut sizes: Vec<(String, u64)> = members.iter().map(|m| (m.clone(), 0)).collect();
+
+    for profile in profile_dirs(target) {
+        for sub in ["deps", "build", ".fingerprint", "incremental"] {
+            let Ok(dir) = std::fs::read_dir(profile.join(sub)) else {
+                continue;
+            };
+
+            for entry in dir.flatten() {
+                let Some(name) = artifact_crate(&entry.file_name().to_string_lossy()) else {
+                    continue;
+                };
+                // Libraries get a lib prefix in deps
+                let member = sizes.iter_mut().find(|(member, _)| {
+                    let member = member.replace('-', "_");
+                    name == member || name == format!("lib{member}")
+                });
+
+                if let Some((_, size)) = member {
+                    *size += match entry.metadata() {
+                        Ok(meta) if meta.is_dir() => crate::size::dir_size(&entry.path()),
+                        Ok(meta) => crate::size::usage(&meta),
+                        Err(_) => 0,
+                    };
+                }
+            }
+        }
+    }
+
+    sizes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    sizes
+}
+
+/// Profile folders like target/debug or target/x86_64-unknown-linux-gnu/release
+pub fn profile_dirs(target: &Path) -> Vec<PathBuf> {
+    let mut profiles = vec![];
+    let Ok(dir) = std::fs::read_dir(target) else {
+        return profiles;
+    };
+
+    for entry in dir.flatten().filter(|e| e.path().is_dir()) {
+        if entry.path().join("deps").is_dir() {
+            profiles.push(entry.path());
+        } else if let Ok(sub) = std::fs::read_dir(entry.path()) {
+            profiles.extend(
+                sub.flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.join("deps").is_dir()),
+            );
+        }
+    }
+    profiles
+}
+
+/// Crate name (with underscores) from artifact name like `foo_bar-0123456789abcdef.d`
+pub fn artifact_crate(file_name: &str) -> Option<String> {
+    let stem = file_name.split('.').next()?;
+    let (name, hash) = stem.rsplit_once('-')?;
+    if hash.len() != 16 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(name.replace('-', "_"))
+}
+
+/// Remove what takes the most space in `target` but leave the final
+/// binaries, so the next build isn't a full rebuild: incremental caches,
+/// generated docs and in `deps` the artifacts of builds replaced by later
+/// ones.
+#[tracing::instrument]
+pub fn light_clean(target: &Path) -> std::io::Result<()> {
+    remove_if_exists(&target.join("doc"))?;
+    for profile in profile_dirs(target) {
+        remove_if_exists(&profile.join("incremental"))?;
+        for old in old_artifacts(&profile) {
+            if old.is_dir() {
+                std::fs::remove_dir_all(old)?;
+            } else {
+                std::fs::remove_file(old)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Profile folders of `target` by name with their sizes, biggest first, like
+/// `debug`, `release` and `doc`. Folders of other platforms count for the
+/// profile of the same name, `x86_64-unknown-linux-gnu/release` for `release`.
+pub fn profiles(target: &Path) -> Vec<(String, u64)> {
+    let mut sizes: Vec<(String, u64)> = vec![];
+    for dir in profile_folders(target) {
+        let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let size = crate::size::dir_size(&dir);
+        match sizes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, total)) => *total += size,
+            None => sizes.push((name, size)),
+        }
+    }
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    sizes
+}
+
+/// Remove the folders of `profiles` in `target`, for every platform built
+/// for, keeping the other profiles like `release` with the binaries in them
+#[tracing::instrument]
+pub fn clean_profiles(target: &Path, profiles: &[String]) -> std::io::Result<()> {
+    for dir in profile_folders(target) {
+        if dir
+            .file_name()
+            .is_some_and(|name| profiles.iter().any(|p| name == p.as_str()))
+        {
+            remove_if_exists(&dir)?;
+        }
+    }
+    Ok(())
+}
+
+// Profile folders and the doc folders next to them
+fn profile_folders(target: &Path) -> Vec<PathBuf> {
+    let mut folders = profile_dirs(target);
+    let mut docs: Vec<PathBuf> = folders
+        .iter()
+        .filter_map(|profile| profile.parent())
+        .chain([target])
+        .map(|dir| dir.join("doc"))
+        .filter(|doc| doc.is_dir())
+        .collect();
+    docs.sort();
+    docs.dedup();
+    folders.extend(docs);
+    folders
+}
+
+fn remove_if_exists(dir: &Path) -> std::io::Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+// Files in `deps` of a profile from builds that are older than a later
+// build of the same unit by more than `STALE`, like ones from before a
+// feature or toolchain change. Each build writes `<crate>-<hash>.d` next to
+// its outputs, and `.fingerprint/<crate>-<hash>` names the unit, like `lib-foo`
+// or `test-bin-foo`. Builds close in time are kept, two versions of a crate
+// are built together.
+fn old_artifacts(profile: &Path) -> Vec<PathBuf> {
+    let Ok(dir) = std::fs::read_dir(profile.join("deps")) else {
+        return vec![];
+    };
+    let files: Vec<PathBuf> = dir.flatten().map(|entry| entry.path()).collect();
+
+    // Builds as (crate, unit, hash, time of the dep-info file)
+    let mut builds = vec![];
+    for path in files
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "d"))
+    {
+        let (Some(stem), Some(hash)) = (
+            path.file_stem().and_then(|n| n.to_str()),
+            artifact_hash(path),
+        ) else {
+            continue;
+        };
+        let Some(name) = artifact_crate(stem) else {
+            continue;
+        };
+        let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            continue;
+        };
+        builds.push((
+            name,
+            unit(&profile.join(".fingerprint").join(stem)),
+            hash,
+            modified,
+        ));
+    }
+
+    let old: Vec<&str> = builds
+        .iter()
+        .filter(|(name, unit, _, modified)| {
+            builds.iter().any(|(n, u, _, m)| {
+                n == name && u == unit && m.duration_since(*modified).is_ok_and(|d| d > STALE)
+            })
+        })
+        .map(|(_, _, hash, _)| *hash)
+        .collect();
+    files
+        .iter()
+        .filter(|path| artifact_hash(path).is_some_and(|hash| old.contains(&hash)))
+        .cloned()
+        .collect()
+}
+
+// How much older than a later build of the same unit a build is to be removed
+const STALE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+// Unit a fingerprint folder is for, from its `<unit>.json` file
+fn unit(fingerprint: &Path) -> Option<String> {
+    std::fs::read_dir(fingerprint)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|e| e == "json"))
+        .and_then(|path| Some(path.file_stem()?.to_str()?.to_string()))
+}
+
+/// Hash of an artifact like `libfoo_bar-0123456789abcdef.rlib`
+pub fn artifact_hash(path: &Path) -> Option<&str> {
+    let stem = path.file_name()?.to_str()?.split('.').next()?;
+    let (_, hash) = stem.rsplit_once('-')?;
+    (hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// Run `cargo clean` with `args` in `project`, letting cargo remove its own
+/// target folder
+#[tracing::instrument]
+pub fn clean(project: &Path, args: &[String]) -> std::io::Result<()> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = std::process::Command::new(cargo);
+    command.arg("clean").args(args);
+    run(command, project)
+}
+
+/// Run `command`, `cargo check` by default, in `project` to see that it
+/// still builds after its target was removed or cleaned
+pub fn check(project: &Path, command: &[String]) -> std::io::Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        return Ok(());
+    };
+    let program = match program.as_str() {
+        "cargo" => std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()),
+        _ => program.into(),
+    };
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+    run(command, project)
+}
+
+fn run(mut command: std::process::Command, project: &Path) -> std::io::Result<()> {
+    let output = command
+        .current_dir(project)
+        .stdin(std::process::Stdio::null())
+        .output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // Cargo's last line tells what went wrong
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map_or_else(|| output.status.to_string(), |line| line.trim().to_string());
+    Err(std::io::Error::other(error))
+}
+
+/// Checks projects one at a time in the background, so builds don't compete
+pub struct Checker {
+    projects: mpsc::Sender<PathBuf>,
+    pub command: Vec<String>,
+    pub results: mpsc::Receiver<(PathBuf, std::io::Result<()>)>,
+    // Projects sent but not checked yet
+    pub pending: usize,
+}
+
+impl Checker {
+    pub fn spawn(command: Vec<String>) -> Checker {
+        let (projects, queue) = mpsc::channel::<PathBuf>();
+        let (done, results) = mpsc::channel();
+        let run = command.clone();
+        std::thread::spawn(move || {
+            for project in queue {
+                let result = check(&project, &run);
+                if done.send((project, result)).is_err() {
+                    break;
+                }
+            }
+        });
+        Checker {
+            projects,
+            command,
+            results,
+            pending: 0,
+        }
+    }
+
+    pub fn check(&mut self, project: &Path) {
+        if self.projects.send(project.to_path_buf()).is_ok() {
+            self.pending += 1;
+        }
+    }
+}
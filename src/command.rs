@@ -0,0 +1,184 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    args::{self, Type},
+    help::COMMANDS,
+};
+
+/// What rclin is told to do by the first argument that isn't an option or
+/// the value of one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    // No command, the interface
+    Interface,
+    // Print found targets like --list
+    Scan,
+    // Like --clean
+    Clean,
+    Auto,
+    Diff(String),
+    Plan(String),
+    Apply(String),
+    Explain,
+    Duplicates,
+    Watch,
+    PurgeQuarantine(Duration),
+    Config(Option<String>),
+    Completions(String),
+    // Project and target, for --ssh
+    Remove(String, String),
+    SelfUpdate,
+}
+
+/// Asked for instead of anything else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Info {
+    Help,
+    Version,
+}
+
+/// `-h`/`--help` or `-V`/`--version` where a flag can be, so not the value
+/// of an option like `--editor -h`
+pub fn info(args: &[String]) -> Option<Info> {
+    flags(args).find_map(|i| match args[i].as_str() {
+        "-h" | "--help" => Some(Info::Help),
+        "-V" | "--version" => Some(Info::Version),
+        _ => None,
+    })
+}
+
+/// The command in `args` with what it takes, and the arguments left for
+/// [`args::Args::parse`]. Only the first positional argument can be a
+/// command, what follows it are its operands and paths.
+pub fn parse(mut args: Vec<String>) -> Result<(Command, Vec<String>), String> {
+    let Some(at) = positionals(&args).next() else {
+        return Ok((Command::Interface, args));
+    };
+    let name = args[at].clone();
+    if !COMMANDS.iter().any(|(command, ..)| *command == name) {
+        if Path::new(&name).exists() {
+            return Ok((Command::Interface, args));
+        }
+        let suggestion = COMMANDS
+            .iter()
+            .map(|(command, ..)| (args::distance(&name, command), *command))
+            .filter(|(d, _)| *d <= 2)
+            .min();
+        return Err(match suggestion {
+            Some((_, command)) => {
+                format!("unknown command '{name}', did you mean '{command}'?")
+            }
+            None => format!("'{name}' is neither a command nor a folder"),
+        });
+    }
+    args.remove(at);
+
+    // Operands are the positional arguments right after the command
+    let mut operand = || {
+        let at = positionals(&args).next()?;
+        Some(args.remove(at))
+    };
+    let usage = || {
+        let (_, rest, _) = COMMANDS
+            .iter()
+            .find(|(command, ..)| *command == name)
+            .unwrap();
+        format!("usage: rclin {name} {rest}")
+    };
+    let command = match name.as_str() {
+        "scan" => Command::Scan,
+        "clean" => Command::Clean,
+        "auto" => Command::Auto,
+        "diff" => Command::Diff(operand().ok_or_else(usage)?),
+        "plan" => Command::Plan(operand().ok_or_else(usage)?),
+        "apply" => Command::Apply(operand().ok_or_else(usage)?),
+        "explain" => Command::Explain,
+        "duplicates" => Command::Duplicates,
+        "watch" => Command::Watch,
+        "purge-quarantine" => {
+            let age = operand().ok_or_else(usage)?;
+            let age = args::parse_age(&age).map_err(|e| format!("invalid age: {e}"))?;
+            // An age of 0 would purge everything without asking
+            Command::PurgeQuarantine(age.ok_or("invalid age: must be more than 0")?)
+        }
+        "config" => Command::Config(operand()),
+        "completions" => Command::Completions(operand().ok_or_else(usage)?),
+        "remove" => match (operand(), operand()) {
+            (Some(project), Some(target)) => Command::Remove(project, target),
+            _ => return Err(usage()),
+        },
+        "self-update" => Command::SelfUpdate,
+        _ => unreachable!("every command is matched"),
+    };
+    Ok((command, args))
+}
+
+// Indexes of arguments that aren't options or their values
+fn positionals(args: &[String]) -> impl Iterator<Item = usize> + '_ {
+    kinds(args).filter_map(|(i, flag)| (!flag).then_some(i))
+}
+
+// Indexes of options that take no value, or whose value is next
+fn flags(args: &[String]) -> impl Iterator<Item = usize> + '_ {
+    kinds(args).filter_map(|(i, flag)| flag.then_some(i))
+}
+
+// Arguments by index, true for flags and options, leaving out their values
+fn kinds(args: &[String]) -> impl Iterator<Item = (usize, bool)> + '_ {
+    let mut value = false;
+    args.iter().enumerate().filter_map(move |(i, arg)| {
+        if std::mem::take(&mut value) {
+            return None;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some((i, false));
+        }
+        if let Some(name) = arg.strip_prefix("--") {
+            value = args::find(name).is_some_and(|opt| opt.ty != Type::Bool);
+        }
+        Some((i, true))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn takes_one_command_where_a_command_can_be() {
+        let parsed = parse(strings(&[
+            "--no-history",
+            "diff",
+            "--min-size",
+            "1MB",
+            "old.json",
+            "/tmp",
+        ]));
+        assert_eq!(
+            parsed.unwrap(),
+            (
+                Command::Diff("old.json".to_string()),
+                strings(&["--no-history", "--min-size", "1MB", "/tmp"])
+            )
+        );
+        // Only the first is a command, the rest are paths
+        let (command, rest) = parse(strings(&["explain", "auto"])).unwrap();
+        assert_eq!((command, rest), (Command::Explain, strings(&["auto"])));
+        assert_eq!(parse(strings(&["/"])).unwrap().0, Command::Interface);
+        assert!(parse(strings(&["claen"])).unwrap_err().contains("'clean'"));
+        assert!(parse(strings(&["diff"])).is_err());
+        assert!(parse(strings(&["purge-quarantine", "0"])).is_err());
+        assert_eq!(
+            parse(strings(&["purge-quarantine", "2d"])).unwrap().0,
+            Command::PurgeQuarantine(Duration::from_secs(2 * 24 * 60 * 60))
+        );
+
+        assert_eq!(info(&strings(&["--editor", "-h"])), None);
+        assert_eq!(info(&strings(&["scan", "-h"])), Some(Info::Help));
+        assert_eq!(info(&strings(&["--no-color", "-V"])), Some(Info::Version));
+    }
+}
@@ -0,0 +1,301 @@
+use std::path::PathBuf;
+
+use crate::{
+    args::{self, Args, Type},
+    editors,
+};
+
+/// `rclin config <command>`, returns the exit code
+pub fn run(command: Option<&str>) -> i32 {
+    match command {
+        Some("check") => check(),
+        Some("init") => init(),
+        Some("edit") => edit(),
+        Some("path") => match path() {
+            Some(path) => {
+                println!("{}", path.display());
+                0
+            }
+            None => {
+                println!("Cannot find config folder");
+                1
+            }
+        },
+        _ => {
+            println!("Usage: rclin config <init|path|edit|check>");
+            1
+        }
+    }
+}
+
+/// Location of the config file, `~/.config/rclin/config.toml` on Linux.
+/// `RCLIN_CONFIG` overrides it.
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RCLIN_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    let dir = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        PathBuf::from(dir)
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?).join(".config")
+    };
+
+    Some(dir.join("rclin").join("config.toml"))
+}
+
+/// Apply the config file to `args`, if there is one. Returns every problem found.
+pub fn load(args: &mut Args) -> Result<(), Vec<String>> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+
+    apply(&content, args)
+}
+
+/// Validate config file content and apply it to `args`
+pub fn apply(content: &str, args: &mut Args) -> Result<(), Vec<String>> {
+    let table = match content.parse::<toml::Value>() {
+        Ok(toml::Value::Table(table)) => table,
+        Ok(_) => return Err(vec!["config must be a table".to_string()]),
+        Err(e) => return Err(vec![e.to_string()]),
+    };
+
+    let mut errors = vec![];
+    for (key, value) in &table {
+        if let Err(e) = apply_value(key, value, args) {
+            errors.push(e);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Environment variable for option `name`, e.g. `RCLIN_SIZE_COLORS`
+pub fn env_name(name: &str) -> String {
+    format!("RCLIN_{}", name.to_uppercase().replace('-', "_"))
+}
+
+/// Apply `RCLIN_*` environment variables to `args`. Lists are comma separated
+/// like on the command line.
+pub fn load_env(args: &mut Args) -> Result<(), Vec<String>> {
+    let mut errors = vec![];
+
+    let names = args::OPTIONS.iter().map(|opt| opt.name);
+    for name in names.chain(args::ALIASES.iter().map(|(old, _)| *old)) {
+        let var = env_name(name);
+        if let Ok(value) = std::env::var(&var) {
+            if let Err(e) = args.set(name, &value) {
+                errors.push(format!("{var}: {e}"));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn apply_value(key: &str, value: &toml::Value, args: &mut Args) -> Result<(), String> {
+    let Some(opt) = args::find(key) else {
+        return Err(match args::suggest(key) {
+            Some(s) => format!("`{key}`: unknown key, did you mean `{s}`?"),
+            None => format!("`{key}`: unknown key"),
+        });
+    };
+
+    // Convert to the same string form that is used on the command line
+    let string = match (opt.ty, value) {
+        (Type::Bool, toml::Value::Boolean(b)) => b.to_string(),
+        (Type::Number, toml::Value::Integer(i)) => i.to_string(),
+        (Type::String, toml::Value::String(s)) => s.clone(),
+        (Type::List, toml::Value::String(s)) => s.clone(),
+        (Type::List, toml::Value::Array(items)) => {
+            let items: Option<Vec<&str>> = items.iter().map(|i| i.as_str()).collect();
+            items
+                .ok_or(format!("`{key}`: expected {}", opt.ty.name()))?
+                .join(",")
+        }
+        _ => {
+            return Err(format!(
+                "`{key}`: expected {}, found {} ({})",
+                opt.ty.name(),
+                value.type_str(),
+                opt.help
+            ))
+        }
+    };
+
+    args.set(key, &string).map_err(|e| format!("`{key}`: {e}"))
+}
+
+/// `rclin config check`: validate the config file without running
+pub fn check() -> i32 {
+    let Some(path) = path() else {
+        println!("Cannot find config folder");
+        return 1;
+    };
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Cannot read {}: {e}", path.display());
+            return 1;
+        }
+    };
+
+    match apply(&content, &mut Args::default()) {
+        Ok(()) => {
+            println!("{} is valid", path.display());
+            0
+        }
+        Err(errors) => {
+            print_errors(&path, &errors);
+            1
+        }
+    }
+}
+
+pub fn print_errors(path: &std::path::Path, errors: &[String]) {
+    println!("Invalid config {}:", path.display());
+    for e in errors {
+        println!("  {e}");
+    }
+}
+
+/// Default config with every option commented out
+pub fn template() -> String {
+    let mut out = String::from("# rclin config. Command line arguments override these.\n");
+    for opt in args::OPTIONS {
+        out.push_str(&format!(
+            "\n# {}\n# {} = {}\n",
+            opt.help, opt.name, opt.default
+        ));
+    }
+    out
+}
+
+/// `rclin config init`: write the default config, unless one exists
+pub fn init() -> i32 {
+    let Some(path) = path() else {
+        println!("Cannot find config folder");
+        return 1;
+    };
+    if path.exists() {
+        println!("{} already exists", path.display());
+        return 1;
+    }
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, template()));
+
+    match written {
+        Ok(()) => {
+            println!("Wrote {}", path.display());
+            0
+        }
+        Err(e) => {
+            println!("Cannot write {}: {e}", path.display());
+            1
+        }
+    }
+}
+
+/// `rclin config edit`: open the config in $VISUAL or $EDITOR, creating it first
+pub fn edit() -> i32 {
+    let Some(path) = path() else {
+        println!("Cannot find config folder");
+        return 1;
+    };
+    if !path.exists() && init() != 0 {
+        return 1;
+    }
+
+    let editor = editors::editor("");
+    match editors::edit(&editor, &path) {
+        Ok(status) if status.success() => check(),
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            println!("Cannot run {editor}: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_beats_environment_beats_config() {
+        let mut args = Args::default();
+        apply(
+            "max-depth = 3\nproject-depth = 2\nthreads = 1\nexclude = [\"a\", \"b\"]\n",
+            &mut args,
+        )
+        .unwrap();
+        assert_eq!(args.exclude, ["a", "b"]);
+
+        // Only this test sets these
+        std::env::set_var(env_name("max-depth"), "5");
+        std::env::set_var(env_name("project-depth"), "4");
+        let loaded = load_env(&mut args);
+        std::env::set_var(env_name("min-size"), "lots");
+        let bad = load_env(&mut args);
+        for name in ["max-depth", "project-depth", "min-size"] {
+            std::env::remove_var(env_name(name));
+        }
+        loaded.unwrap();
+        assert!(bad.unwrap_err()[0].starts_with("RCLIN_MIN_SIZE: "));
+
+        args.parse(["--max-depth".to_string(), "7".to_string()])
+            .unwrap();
+        assert_eq!(
+            (args.max_depth, args.project_depth, args.threads),
+            (7, 4, 1)
+        );
+    }
+
+    #[test]
+    fn tells_every_bad_value() {
+        let mut args = Args::default();
+        let errors = apply(
+            "max-depth = \"deep\"\nsalvage = 1\nmax-dpth = 2\nmin-size = \"lots\"\n",
+            &mut args,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("did you mean `max-depth`?")));
+        assert!(errors.iter().any(|e| e.starts_with("`salvage`: expected")));
+        assert_eq!(args.max_depth, 0);
+
+        assert!(apply("max-depth = ", &mut args).is_err());
+        // What the template documents is what's used without a config
+        let template = template();
+        let settings: Vec<&str> = template
+            .lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.contains(" = "))
+            .collect();
+        let mut documented = Args::default();
+        assert_eq!(apply(&settings.join("\n"), &mut documented), Ok(()));
+        assert_eq!(documented.quarantine, None);
+        assert_eq!(documented.report, None);
+        assert_eq!(documented.log_file, None);
+        assert_eq!(documented.trace_output, None);
+    }
+}
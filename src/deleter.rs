@@ -0,0 +1,320 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use crate::{
+    cargo,
+    error::{self, Error},
+    guard,
+    journal::Journal,
+    remote,
+    scan::{Entry, Kind},
+    trashcan::{self, Trashed},
+};
+
+/// How a folder was cleaned up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removal {
+    Trashed,
+    // From a Windows drive under WSL
+    Recycled,
+    // Into the quarantine folder instead of the trash
+    Quarantined,
+    Deleted,
+    // By cargo clean or a light clean, which leave some of it
+    Cleaned,
+}
+
+impl Removal {
+    /// Message template telling it's done
+    pub fn done(self) -> &'static str {
+        match self {
+            Removal::Trashed => "Trashed {}",
+            Removal::Recycled => "Moved {} to the recycle bin",
+            Removal::Quarantined => "Moved {} to the quarantine",
+            Removal::Deleted => "Deleted {}",
+            Removal::Cleaned => "Cleaned {}",
+        }
+    }
+}
+
+/// How Rust targets are cleaned up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cleanup {
+    // Trashed or deleted like other targets
+    Remove,
+    // By cargo clean
+    Cargo,
+    // Only incremental caches, docs and old artifacts, keeping binaries
+    Light,
+    // Only the folders of these profiles, like debug and doc
+    Profiles(Vec<String>),
+}
+
+/// Target to clean up and how
+pub struct Job {
+    pub entry: Entry,
+    pub permanent: bool,
+    pub cleanup: Cleanup,
+    // Install its release binaries first, keeping it if that fails
+    pub salvage: bool,
+}
+
+/// How a job went
+pub struct Finished {
+    pub path: PathBuf,
+    // Binaries salvaged from it
+    pub installed: Vec<String>,
+    pub result: error::Result<Removal>,
+}
+
+enum Update {
+    Started(PathBuf),
+    Finished(Finished),
+}
+
+/// Deletes targets one at a time in the background, so the list stays
+/// responsive while big ones go. Every run of deletes is journaled until
+/// nothing is left to do.
+pub struct Deleter {
+    jobs: mpsc::Sender<Vec<Job>>,
+    updates: mpsc::Receiver<Update>,
+    // Paths sent but not finished yet
+    queued: HashSet<PathBuf>,
+    // Path being deleted right now
+    current: Option<PathBuf>,
+}
+
+impl Deleter {
+    pub fn spawn(
+        roots: Vec<PathBuf>,
+        cargo_clean_args: Vec<String>,
+        journal: Option<PathBuf>,
+    ) -> Deleter {
+        let (jobs, queue) = mpsc::channel::<Vec<Job>>();
+        let (updates, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut todo = VecDeque::new();
+            let mut progress: Option<Journal> = None;
+            loop {
+                // Wait for more only when there's nothing left to do
+                if todo.is_empty() {
+                    if let Some(journal) = progress.take() {
+                        journal.finish();
+                    }
+                    match queue.recv() {
+                        Ok(jobs) => todo.extend(jobs),
+                        Err(_) => break,
+                    }
+                    let paths: Vec<PathBuf> =
+                        todo.iter().map(|j: &Job| j.entry.path.clone()).collect();
+                    progress = Some(Journal::start(journal.as_deref(), &paths));
+                }
+                let journal = progress.as_mut().unwrap();
+                for job in queue.try_iter().flatten() {
+                    journal.add(&job.entry.path);
+                    todo.push_back(job);
+                }
+
+                let Some(job) = todo.pop_front() else {
+                    continue;
+                };
+                let path = job.entry.path.clone();
+                if updates.send(Update::Started(path.clone())).is_err() {
+                    break;
+                }
+                let _span = tracing::info_span!("delete", path = %path.display()).entered();
+                journal.begin(&path);
+                let (installed, result) = run(&job, &roots, &cargo_clean_args);
+                match &result {
+                    Ok(removal) => {
+                        journal.done(&path);
+                        // The path is on the span
+                        let size = job.entry.size;
+                        tracing::info!(target: "rclin::delete", size, "{removal:?}");
+                    }
+                    Err(e) => {
+                        journal.failed(&path);
+                        tracing::warn!(target: "rclin::delete", "{e}");
+                    }
+                }
+                let finished = Finished {
+                    path,
+                    installed,
+                    result,
+                };
+                if updates.send(Update::Finished(finished)).is_err() {
+                    break;
+                }
+            }
+        });
+        Deleter {
+            jobs,
+            updates: receiver,
+            queued: HashSet::new(),
+            current: None,
+        }
+    }
+
+    /// Start on `jobs` once the ones before are done
+    pub fn delete(&mut self, jobs: Vec<Job>) {
+        let paths: Vec<PathBuf> = jobs.iter().map(|j| j.entry.path.clone()).collect();
+        if self.jobs.send(jobs).is_ok() {
+            self.queued.extend(paths);
+        }
+    }
+
+    /// Whether `path` is waiting to be deleted or being deleted
+    pub fn is_queued(&self, path: &Path) -> bool {
+        self.queued.contains(path)
+    }
+
+    pub fn is_current(&self, path: &Path) -> bool {
+        self.current.as_deref() == Some(path)
+    }
+
+    /// Number of targets waiting or being deleted
+    pub fn pending(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Jobs finished since last asked. With `wait`, blocks until one
+    /// finishes unless none are pending.
+    pub fn finished(&mut self, wait: bool) -> Vec<Finished> {
+        let mut finished = vec![];
+        loop {
+            let update = if wait && finished.is_empty() && !self.queued.is_empty() {
+                self.updates.recv().ok()
+            } else {
+                self.updates.try_recv().ok()
+            };
+            match update {
+                Some(Update::Started(path)) => self.current = Some(path),
+                Some(Update::Finished(done)) => {
+                    self.queued.remove(&done.path);
+                    if self.is_current(&done.path) {
+                        self.current = None;
+                    }
+                    finished.push(done);
+                }
+                None => break,
+            }
+        }
+        // The worker is gone, nothing it had left will finish
+        if wait && finished.is_empty() {
+            self.queued.clear();
+        }
+        finished
+    }
+}
+
+/// Clean up the target of `job` if it's still safe to, salvaging its binaries
+/// first if asked to. Returns the binaries installed and how the rest went.
+/// Over SSH the host does it all.
+pub fn run(
+    job: &Job,
+    roots: &[PathBuf],
+    cargo_clean_args: &[String],
+) -> (Vec<String>, error::Result<Removal>) {
+    let path = &job.entry.path;
+    if let Some(remote) = remote::get() {
+        return (vec![], remote::remove(remote, job, cargo_clean_args));
+    }
+    if let Some(reason) = guard::refuse(&job.entry, roots) {
+        let path = path.clone();
+        return (vec![], Err(Error::Refused { path, reason }));
+    }
+    let mut installed = vec![];
+    if job.salvage {
+        match cargo::salvage(path) {
+            Ok(names) => installed = names,
+            Err(source) => {
+                let path = path.clone();
+                return (installed, Err(Error::Salvage { path, source }));
+            }
+        }
+    }
+    (installed, remove(job, cargo_clean_args))
+}
+
+// Send the target to trash or remove it for good. Rust targets are only
+// cleaned instead with a cleanup.
+fn remove(job: &Job, cargo_clean_args: &[String]) -> error::Result<Removal> {
+    let entry = &job.entry;
+    if entry.kind == Kind::Rust {
+        match &job.cleanup {
+            Cleanup::Remove => (),
+            Cleanup::Cargo => {
+                cargo::clean(&entry.project, cargo_clean_args).map_err(|source| Error::Clean {
+                    path: entry.project.clone(),
+                    source,
+                })?;
+                return Ok(Removal::Cleaned);
+            }
+            Cleanup::Light => {
+                cargo::light_clean(&entry.path).map_err(|source| Error::Delete {
+                    path: entry.path.clone(),
+                    source,
+                })?;
+                return Ok(Removal::Cleaned);
+            }
+            Cleanup::Profiles(profiles) => {
+                cargo::clean_profiles(&entry.path, profiles).map_err(|source| Error::Delete {
+                    path: entry.path.clone(),
+                    source,
+                })?;
+                return Ok(Removal::Cleaned);
+            }
+        }
+    }
+    Ok(match trashcan::remove(entry, job.permanent)? {
+        Trashed::Trash => Removal::Trashed,
+        Trashed::RecycleBin => Removal::Recycled,
+        Trashed::Quarantine => Removal::Quarantined,
+        Trashed::Deleted => Removal::Deleted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+
+    fn job(entry: Entry) -> Job {
+        Job {
+            entry,
+            permanent: true,
+            cleanup: Cleanup::Remove,
+            salvage: false,
+        }
+    }
+
+    #[test]
+    fn deletes_targets_and_refuses_swapped_ones() {
+        let fx = Fixture::new("deleter");
+        let entry = |project: &str| {
+            let target = fx.project(project);
+            Entry::new(target, fx.path(project), Kind::Rust, &|_| false)
+        };
+        let (a, b) = (entry("a"), entry("b"));
+        // Swapped for something else since the scan
+        std::fs::remove_dir_all(&b.path).unwrap();
+        std::fs::write(&b.path, "").unwrap();
+
+        let mut deleter = Deleter::spawn(vec![fx.root.clone()], vec![], None);
+        deleter.delete(vec![job(a.clone()), job(b.clone())]);
+        assert_eq!(deleter.pending(), 2);
+        let mut finished = vec![];
+        while deleter.pending() > 0 {
+            finished.extend(deleter.finished(true));
+        }
+
+        assert_eq!(finished[0].path, a.path);
+        assert_eq!(finished[0].result.as_ref().ok(), Some(&Removal::Deleted));
+        assert!(!a.path.exists());
+        assert!(matches!(finished[1].result, Err(Error::Refused { .. })));
+        assert!(b.path.exists());
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{cargo, scan::Entry, size};
+
+/// Extra information about one entry, computed when it gets selected
+pub struct Details {
+    pub path: PathBuf,
+    // Workspace members and their share of the target, biggest first
+    pub members: Option<Vec<(String, u64)>>,
+    // Data shared through reflinks or snapshots
+    pub reflinked: u64,
+    // Folders at the top of the target, like `debug`, `release` and `doc`,
+    // with their sizes, biggest first
+    pub folders: Vec<(String, u64)>,
+    // Latest modification of a file in the target
+    pub newest: Option<SystemTime>,
+    // Checked out git branch of the project, or the commit when detached
+    pub branch: Option<String>,
+}
+
+impl Details {
+    pub fn new(entry: &Entry) -> Details {
+        let members = cargo::workspace_members(&entry.project)
+            .map(|members| cargo::member_sizes(&entry.path, &members));
+        let (folders, newest) = folders(entry);
+
+        Details {
+            path: entry.path.clone(),
+            members,
+            reflinked: size::reflinked(&entry.path),
+            folders,
+            newest,
+            branch: branch(&entry.project),
+        }
+    }
+}
+
+// Folders at the top of the target with their sizes, and the latest
+// modification inside, in one walk. Excluded folders are left out like in the
+// size of the target.
+fn folders(entry: &Entry) -> (Vec<(String, u64)>, Option<SystemTime>) {
+    let Ok(dir) = std::fs::read_dir(&entry.path) else {
+        return (vec![], None);
+    };
+    let mut folders = vec![];
+    let mut newest = None;
+    for file in dir.flatten() {
+        let Ok(meta) = file.metadata() else {
+            continue;
+        };
+        if meta.is_dir() && entry.is_kept(&file.path()) {
+            continue;
+        }
+        newest = newest.max(meta.modified().ok());
+        if meta.is_dir() {
+            let stats = size::dir_stats_except(&file.path(), &|dir| entry.is_kept(dir));
+            newest = newest.max(stats.modified);
+            let name = file.file_name().to_string_lossy().to_string();
+            folders.push((name, stats.size));
+        }
+    }
+    folders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    (folders, newest)
+}
+
+// From `HEAD` in the closest `.git`, which in worktrees and submodules is a
+// file pointing at the real one
+fn branch(project: &Path) -> Option<String> {
+    let git = project
+        .ancestors()
+        .map(|p| p.join(".git"))
+        .find(|g| g.exists())?;
+    let dir = if git.is_file() {
+        let content = std::fs::read_to_string(&git).ok()?;
+        let dir = PathBuf::from(content.strip_prefix("gitdir:")?.trim());
+        git.parent()?.join(dir)
+    } else {
+        git
+    };
+
+    let head = std::fs::read_to_string(dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(name) => Some(name.strip_prefix("refs/heads/").unwrap_or(name).to_string()),
+        None => Some(head.chars().take(8).collect()),
+    }
+}
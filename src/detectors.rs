@@ -0,0 +1,194 @@
+use std::{
+    collections::HashSet,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{cargo, disk, scan::Kind};
+
+/// Knows how to recognize one kind of project and where its build artifacts are
+pub struct Detector {
+    pub kind: Kind,
+    // Files that make a folder a project of this kind, any of them will do.
+    // Without any, every folder is looked into.
+    pub manifests: &'static [&'static str],
+    // Artifact folders of the project in a folder, existing or not
+    pub artifacts: fn(&Path) -> Vec<PathBuf>,
+}
+
+pub const DETECTORS: &[Detector] = &[
+    Detector {
+        kind: Kind::Rust,
+        manifests: &["Cargo.toml"],
+        artifacts: rust,
+    },
+    Detector {
+        kind: Kind::Node,
+        manifests: &["package.json"],
+        artifacts: node,
+    },
+    Detector {
+        kind: Kind::Python,
+        manifests: &[
+            "pyproject.toml",
+            "setup.py",
+            "setup.cfg",
+            "requirements.txt",
+        ],
+        artifacts: python,
+    },
+    Detector {
+        kind: Kind::CMake,
+        manifests: &["CMakeLists.txt"],
+        artifacts: cmake,
+    },
+    Detector {
+        kind: Kind::Gradle,
+        manifests: &[
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ],
+        artifacts: gradle,
+    },
+    // Last, so the cache folders of the projects above are listed with them
+    Detector {
+        kind: Kind::Cache,
+        manifests: &[],
+        artifacts: cache,
+    },
+];
+
+// First line of a CACHEDIR.TAG, see https://bford.info/cachedir/
+const CACHEDIR_SIGNATURE: &[u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+/// Artifact folders of the projects in `dir`, looking only for `kinds`
+pub fn find(dir: &Path, kinds: &[Kind]) -> std::io::Result<Vec<(Kind, PathBuf)>> {
+    // Manifests that are symlinks are not followed
+    let files: HashSet<OsString> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.file_name())
+        .collect();
+
+    let mut found = vec![];
+    for detector in DETECTORS.iter().filter(|d| kinds.contains(&d.kind)) {
+        if detector.manifests.is_empty()
+            || detector
+                .manifests
+                .iter()
+                .any(|m| files.contains(OsStr::new(m)))
+        {
+            let artifacts = (detector.artifacts)(dir).into_iter();
+            found.extend(artifacts.filter(|p| p.is_dir()).map(|p| (detector.kind, p)));
+        }
+    }
+    Ok(found)
+}
+
+/// When a manifest of the `kind` project in `project` was last modified
+pub fn manifest_modified(project: &Path, kind: Kind) -> Option<SystemTime> {
+    let detector = DETECTORS.iter().find(|d| d.kind == kind)?;
+    detector
+        .manifests
+        .iter()
+        .filter_map(|m| std::fs::metadata(project.join(m)).ok()?.modified().ok())
+        .max()
+}
+
+/// Whether the `kind` project in `project` looks like it was never built:
+/// a Rust project without Cargo.lock, or an artifact folder holding nothing
+/// but a CACHEDIR.TAG
+pub fn is_unbuilt(kind: Kind, project: &Path, artifact: &Path) -> bool {
+    if kind == Kind::Rust && !project.join("Cargo.lock").is_file() {
+        return true;
+    }
+    std::fs::read_dir(artifact).is_ok_and(|dir| {
+        dir.flatten()
+            .all(|entry| entry.file_name() == "CACHEDIR.TAG")
+    })
+}
+
+/// Whether `dir` is tagged as a cache with a valid CACHEDIR.TAG, as Cargo does
+/// with target folders
+pub fn is_cache_dir(dir: &Path) -> bool {
+    use std::io::Read;
+
+    let tag = dir.join("CACHEDIR.TAG");
+    // Not worth downloading an online-only tag for
+    if cfg!(windows) && std::fs::metadata(&tag).is_ok_and(|m| disk::is_placeholder(&m)) {
+        return false;
+    }
+    let mut signature = [0; CACHEDIR_SIGNATURE.len()];
+    std::fs::File::open(tag)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok_and(|()| signature == CACHEDIR_SIGNATURE)
+}
+
+fn rust(dir: &Path) -> Vec<PathBuf> {
+    let target = cargo::target_dir(dir);
+    // Left from before cargo was told to build elsewhere. Cargo tags its
+    // target folders, other `target` folders aren't its business.
+    let own = dir.join("target");
+    let own = (!target.starts_with(&own) && is_cache_dir(&own)).then_some(own);
+    // A config can point anywhere, like a cloned repo at ~/Documents. Outside
+    // the project only what cargo tagged is taken.
+    let target = (target.starts_with(dir) || is_cache_dir(&target)).then_some(target);
+    target.into_iter().chain(own).collect()
+}
+
+fn node(dir: &Path) -> Vec<PathBuf> {
+    vec![dir.join("node_modules")]
+}
+
+// Virtual environments and bytecode caches. A plain `venv` folder could be
+// source, only take it when the venv config is in there.
+fn python(dir: &Path) -> Vec<PathBuf> {
+    let mut artifacts = vec![dir.join(".venv"), dir.join("__pycache__")];
+    if dir.join("venv").join("pyvenv.cfg").is_file() {
+        artifacts.push(dir.join("venv"));
+    }
+    artifacts
+}
+
+// `build` and CLion's `cmake-build-*` folders that CMake has configured
+fn cmake(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut artifacts: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name == "build" || name.starts_with("cmake-build-")
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.join("CMakeCache.txt").is_file())
+        .collect();
+    artifacts.sort();
+    artifacts
+}
+
+fn gradle(dir: &Path) -> Vec<PathBuf> {
+    vec![dir.join(".gradle"), dir.join("build")]
+}
+
+// Tagged folders of any tool
+fn cache(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut artifacts: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| is_cache_dir(path))
+        .collect();
+    artifacts.sort();
+    artifacts
+}
@@ -0,0 +1,207 @@
+use std::{collections::HashMap, path::Path};
+
+use serde_json::Value;
+
+use crate::{args::Args, headless, id, output::SCHEMA, scan::Entry, size::format_size};
+
+// Exit codes like the other commands without the interface
+const OK: i32 = 0;
+const SAME: i32 = 1;
+const FAILED: i32 = 2;
+
+/// Target of an earlier `--output json`
+#[derive(Debug)]
+pub struct Exported {
+    pub id: String,
+    pub path: String,
+    // Empty in exports from before it was written
+    pub project: String,
+    pub size: u64,
+    // As written, like `2024-01-31T23:59:59Z`
+    pub modified: Option<String>,
+}
+
+/// `rclin diff <old.json>`: scan now and tell which targets appeared,
+/// disappeared, grew or shrank since `old` was exported with `--output json`.
+/// Returns exit code, 1 when nothing changed.
+pub fn diff(args: &Args, old: &str) -> i32 {
+    let old = match std::fs::read_to_string(old)
+        .map_err(|e| e.to_string())
+        .and_then(|json| parse(&json))
+    {
+        Ok(old) => old,
+        Err(e) => {
+            eprintln!("Cannot read {old}: {e}");
+            return FAILED;
+        }
+    };
+    let Some(now) = headless::find(args) else {
+        return FAILED;
+    };
+
+    let changes = compare(&old, &now);
+    for change in &changes {
+        println!("{change}");
+    }
+
+    let before: u64 = old.iter().map(|t| t.size).sum();
+    let after: u64 = now.iter().map(|e| e.size).sum();
+    println!(
+        "{} changes, total {} -> {} ({})",
+        changes.len(),
+        format_size(before),
+        format_size(after),
+        signed(after as i64 - before as i64)
+    );
+    if changes.is_empty() {
+        SAME
+    } else {
+        OK
+    }
+}
+
+enum Change<'a> {
+    New(&'a Entry),
+    Gone(&'a Exported),
+    Resized(&'a Exported, &'a Entry),
+}
+
+impl Change<'_> {
+    fn growth(&self) -> i64 {
+        match self {
+            Change::New(e) => e.size as i64,
+            Change::Gone(t) => -(t.size as i64),
+            Change::Resized(t, e) => e.size as i64 - t.size as i64,
+        }
+    }
+}
+
+impl std::fmt::Display for Change<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let growth = signed(self.growth());
+        match self {
+            Change::New(e) => write!(f, "new     {growth:>11}  {}", e.path.display()),
+            Change::Gone(t) => write!(f, "gone    {growth:>11}  {}", t.path),
+            Change::Resized(t, e) => {
+                let verb = if e.size > t.size { "grew" } else { "shrank" };
+                write!(
+                    f,
+                    "{verb:<6}  {growth:>11}  {}  (now {})",
+                    e.path.display(),
+                    format_size(e.size)
+                )
+            }
+        }
+    }
+}
+
+// By ID, so the same folder reached through another path or on a machine with
+// another home folder isn't new. Biggest changes first.
+fn compare<'a>(old: &'a [Exported], now: &'a [Entry]) -> Vec<Change<'a>> {
+    let before: HashMap<&str, &Exported> = old.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut changes: Vec<Change> = now
+        .iter()
+        .filter_map(|e| match before.get(e.id.as_str()) {
+            None => Some(Change::New(e)),
+            Some(t) if t.size != e.size => Some(Change::Resized(t, e)),
+            Some(_) => None,
+        })
+        .collect();
+
+    let after: HashMap<&str, &Entry> = now.iter().map(|e| (e.id.as_str(), e)).collect();
+    changes.extend(
+        old.iter()
+            .filter(|t| !after.contains_key(t.id.as_str()))
+            .map(Change::Gone),
+    );
+    changes.sort_by_key(|c| std::cmp::Reverse(c.growth().unsigned_abs()));
+    changes
+}
+
+/// Size with its sign, like `+1.2 MB`
+pub fn signed(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_size(bytes.unsigned_abs()))
+}
+
+/// Reads the array of objects `--output json` writes, also after going
+/// through other tools that reformat it. Targets without an ID get one from
+/// their path, ones without a schema are from before it was versioned.
+pub fn parse(json: &str) -> Result<Vec<Exported>, String> {
+    let targets: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    targets
+        .as_array()
+        .ok_or("expected an array of targets")?
+        .iter()
+        .map(exported)
+        .collect()
+}
+
+// One object of the array. Fields of older versions mean the same, not so
+// for newer ones.
+fn exported(target: &Value) -> Result<Exported, String> {
+    let target = target
+        .as_object()
+        .ok_or("expected an object for a target")?;
+    let string = |key: &str| target.get(key).and_then(Value::as_str).map(String::from);
+    if let Some(schema) = target.get("schema") {
+        let schema = schema.as_u64().ok_or("schema is not a number")?;
+        if schema > SCHEMA {
+            return Err(format!(
+                "schema {schema} is newer than {SCHEMA}, update rclin to read it"
+            ));
+        }
+    }
+    let path = string("path").ok_or("target without a path")?;
+    Ok(Exported {
+        id: string("id").unwrap_or_else(|| id::of(Path::new(&path))),
+        size: target
+            .get("size")
+            .and_then(Value::as_u64)
+            .ok_or("target without a size")?,
+        project: string("project").unwrap_or_default(),
+        modified: string("modified"),
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{output::Output, scan::Kind, size::Stats};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn reads_back_what_it_exported() {
+        let stats = Stats {
+            size: 4096,
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            files: 0,
+            shared: 0,
+            skipped: vec![],
+        };
+        let odd = "/p/\"q\\ote\"\n\t🦀 é/target";
+        let entry = Entry::with_stats(odd.into(), "/p".into(), Kind::Rust, stats);
+        let read = parse(&Output::Json.format(std::slice::from_ref(&entry))).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].id, entry.id);
+        assert_eq!(read[0].path, odd);
+        assert_eq!(read[0].project, "/p");
+        assert_eq!(read[0].size, 4096);
+        assert_eq!(read[0].modified.as_deref(), Some("2023-11-14T22:13:20Z"));
+
+        // Reformatted by another tool, escaping what's outside of ASCII, the
+        // crab as a surrogate pair
+        let json = r#"[ { "path" : "/p/\ud83e\udd80\u00e9/target", "size" : 1, "extra": [1, {"x": null}] } ]"#;
+        let read = parse(json).unwrap();
+        assert_eq!(read[0].path, "/p/🦀é/target");
+        assert_eq!(read[0].id, id::of(Path::new("/p/🦀é/target")));
+        assert_eq!(read[0].modified, None);
+
+        assert!(parse(r#"[{"schema": 2, "path": "/p", "size": 1}]"#)
+            .unwrap_err()
+            .contains("newer"));
+        assert!(parse(r#"[{"path": "/p"}]"#).is_err());
+        assert!(parse("[").is_err());
+    }
+}
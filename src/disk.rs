@@ -0,0 +1,174 @@
+use std::{
+    fs::Metadata,
+    path::{Path, PathBuf},
+};
+
+/// Free space available to the user on the filesystem containing `path`
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    let stat = statvfs(path)?;
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Size of the filesystem containing `path`
+#[cfg(unix)]
+pub fn total_space(path: &Path) -> Option<u64> {
+    let stat = statvfs(path)?;
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_blocks as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+pub fn total_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn statvfs(path: &Path) -> Option<libc::statvfs> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+    // SAFETY: path is a valid C string and stat is a valid out pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat)
+}
+
+/// Device of the filesystem `path` is on
+#[cfg(unix)]
+pub fn device(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Whether `meta` is of a cloud placeholder, like a OneDrive file or folder
+/// kept only online. Its metadata is local, reading its contents downloads it.
+#[cfg(windows)]
+pub fn is_placeholder(meta: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+
+    meta.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+#[cfg(not(windows))]
+pub fn is_placeholder(_meta: &Metadata) -> bool {
+    false
+}
+
+/// `path` without the `\\?\` extended-length prefix Windows puts on
+/// canonical paths, like `C:\work` for `\\?\C:\work` and `\\server\share`
+/// for `\\?\UNC\server\share`. Long paths work without it, the standard
+/// library adds it back where it's needed. Other platforms keep `path` as is.
+pub fn plain(path: &Path) -> PathBuf {
+    let Some(text) = path.to_str().filter(|_| cfg!(windows)) else {
+        return path.to_path_buf();
+    };
+    if let Some(share) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{share}"))
+    } else if let Some(drive) = text
+        .strip_prefix(r"\\?\")
+        .filter(|rest| rest.as_bytes().get(1) == Some(&b':'))
+    {
+        PathBuf::from(drive)
+    } else {
+        // Volume GUID paths have no other name
+        path.to_path_buf()
+    }
+}
+
+/// Whether `path` is on a network share, by UNC path or a mapped drive.
+/// Shares have no recycle bin, trashing there deletes for good.
+#[cfg(windows)]
+pub fn is_network(path: &Path) -> bool {
+    use std::path::Prefix;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDriveTypeW(root: *const u16) -> u32;
+    }
+    const DRIVE_REMOTE: u32 = 4;
+
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let Some(std::path::Component::Prefix(prefix)) = path.components().next() else {
+        return false;
+    };
+    match prefix.kind() {
+        Prefix::UNC(..) | Prefix::VerbatimUNC(..) => true,
+        Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => {
+            let root: Vec<u16> = format!("{}:\\", char::from(drive))
+                .encode_utf16()
+                .chain([0])
+                .collect();
+            // SAFETY: root is a valid null terminated wide string
+            unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn is_network(_path: &Path) -> bool {
+    false
+}
+
+/// Filesystem some targets are on
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    pub device: u64,
+    // Topmost folder on the same device
+    pub mount: PathBuf,
+    pub free: Option<u64>,
+    pub size: Option<u64>,
+}
+
+impl Filesystem {
+    /// Filesystem containing `path`
+    pub fn of(path: &Path) -> Option<Filesystem> {
+        let device = device(path)?;
+        let path = plain(&path.canonicalize().ok()?);
+        let mount = path
+            .ancestors()
+            .take_while(|p| self::device(p) == Some(device))
+            .last()
+            .unwrap_or(&path)
+            .to_path_buf();
+
+        Some(Filesystem {
+            device,
+            free: free_space(&mount),
+            size: total_space(&mount),
+            mount,
+        })
+    }
+
+    /// Share of the filesystem still free, 1 when unknown
+    pub fn free_fraction(&self) -> f64 {
+        match (self.free, self.size) {
+            (Some(free), Some(size)) if size > 0 => free as f64 / size as f64,
+            _ => 1.0,
+        }
+    }
+}
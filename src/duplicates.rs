@@ -0,0 +1,193 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    hash::Hasher,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{cargo, size};
+
+/// Dependency built the same in several targets
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    // Crate name, with underscores
+    pub name: String,
+    // Targets it's in, at least two
+    pub targets: Vec<String>,
+    // What one copy of its artifacts takes
+    pub size: u64,
+    // What all copies but one take
+    pub savings: u64,
+}
+
+/// Dependency artifacts of Rust targets compared across them
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub targets: usize,
+    // What the deps folders of all the targets take
+    pub total: u64,
+    // Most to save first
+    pub duplicates: Vec<Duplicate>,
+}
+
+impl Report {
+    /// What one target folder shared by all the projects would save
+    pub fn savings(&self) -> u64 {
+        self.duplicates.iter().map(|d| d.savings).sum()
+    }
+}
+
+// Artifact in the deps folder of a target
+struct Artifact {
+    target: usize,
+    path: PathBuf,
+    usage: u64,
+}
+
+/// Compare the artifacts in the deps folders of `targets`. Artifacts are
+/// named `<crate>-<hash>` by the crate version, features, profile and
+/// compiler, so ones with the same name and length in several targets are
+/// likely the same build. What's in them is compared to be sure.
+#[tracing::instrument(skip_all, fields(targets = targets.len()))]
+pub fn find(targets: &[&Path]) -> Report {
+    let mut report = Report {
+        targets: targets.len(),
+        ..Report::default()
+    };
+    let mut by_name: HashMap<(String, u64), Vec<Artifact>> = HashMap::new();
+    for (i, target) in targets.iter().enumerate() {
+        for profile in cargo::profile_dirs(target) {
+            let Ok(dir) = std::fs::read_dir(profile.join("deps")) else {
+                continue;
+            };
+            for entry in dir.flatten() {
+                let Some(meta) = entry.metadata().ok().filter(|meta| meta.is_file()) else {
+                    continue;
+                };
+                let usage = size::usage(&meta);
+                report.total += usage;
+                let name = entry.file_name().to_string_lossy().to_string();
+                by_name
+                    .entry((name, meta.len()))
+                    .or_default()
+                    .push(Artifact {
+                        target: i,
+                        path: entry.path(),
+                        usage,
+                    });
+            }
+        }
+    }
+
+    // Artifacts of a build by its hash, with the crate name
+    let mut builds: HashMap<String, Duplicate> = HashMap::new();
+    for ((name, _), artifacts) in by_name {
+        if !in_several(&artifacts) {
+            continue;
+        }
+        let (Some(hash), Some(krate)) = (
+            cargo::artifact_hash(Path::new(&name)),
+            cargo::artifact_crate(&name),
+        ) else {
+            continue;
+        };
+        // Libraries are `lib<crate>`, other artifacts have the plain name
+        let krate = match krate.strip_prefix("lib") {
+            Some(plain) if is_library(&name) => plain.to_string(),
+            _ => krate,
+        };
+        let mut by_content: HashMap<u64, Vec<Artifact>> = HashMap::new();
+        for artifact in artifacts {
+            if let Ok(digest) = digest(&artifact.path) {
+                by_content.entry(digest).or_default().push(artifact);
+            }
+        }
+
+        for same in by_content.values().filter(|same| in_several(same)) {
+            let copies: BTreeSet<usize> = same.iter().map(|a| a.target).collect();
+            let build = builds.entry(hash.to_string()).or_insert_with(|| Duplicate {
+                name: krate.clone(),
+                targets: vec![],
+                size: 0,
+                savings: 0,
+            });
+            for target in &copies {
+                let target = targets[*target].display().to_string();
+                if !build.targets.contains(&target) {
+                    build.targets.push(target);
+                }
+            }
+            build.size += same[0].usage;
+            build.savings += same[0].usage * (copies.len() as u64 - 1);
+        }
+    }
+
+    report.duplicates = builds.into_values().collect();
+    report
+        .duplicates
+        .sort_by(|a, b| b.savings.cmp(&a.savings).then(a.name.cmp(&b.name)));
+    report
+}
+
+fn is_library(file_name: &str) -> bool {
+    Path::new(file_name).extension().is_some_and(|e| {
+        ["rlib", "rmeta", "a", "so", "dylib"]
+            .iter()
+            .any(|l| e == *l)
+    })
+}
+
+fn in_several(artifacts: &[Artifact]) -> bool {
+    artifacts.iter().any(|a| a.target != artifacts[0].target)
+}
+
+// Hash of what's in the file
+fn digest(path: &Path) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buf[..read]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn finds_the_same_builds_in_several_targets() {
+        let fx = Fixture::new("duplicates");
+        let write = |relative: &str, content: &str| fx.file(relative, content);
+        for project in ["a", "b", "c"] {
+            let deps = format!("{project}/target/debug/deps");
+            write(&format!("{deps}/libserde-0123456789abcdef.rlib"), "serde");
+            write(&format!("{deps}/serde-0123456789abcdef.d"), "deps");
+            write(&format!("{deps}/{project}-fedcba9876543210"), project);
+        }
+        // Same name and length, built differently
+        write(
+            "c/target/debug/deps/libserde-0123456789abcdef.rlib",
+            "SERDE",
+        );
+
+        let targets: Vec<PathBuf> = ["a", "b", "c"].map(|p| fx.path(p).join("target")).into();
+        let targets: Vec<&Path> = targets.iter().map(PathBuf::as_path).collect();
+        let report = find(&targets);
+        assert_eq!(report.duplicates.len(), 1);
+        let serde = &report.duplicates[0];
+        assert_eq!(serde.name, "serde");
+        assert_eq!(serde.targets.len(), 3);
+        // The rlib is the same in two targets, the dep-info file in all three
+        let one = |relative: &str| size::usage(&std::fs::metadata(fx.path(relative)).unwrap());
+        let rlib = one("a/target/debug/deps/libserde-0123456789abcdef.rlib");
+        let info = one("a/target/debug/deps/serde-0123456789abcdef.d");
+        assert_eq!(serde.savings, rlib + 2 * info);
+        assert_eq!(report.savings(), serde.savings);
+    }
+}
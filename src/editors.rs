@@ -0,0 +1,193 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+/// What to do with projects that are open in an editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenProjects {
+    // Don't look for open projects
+    Off,
+    // Flag them in the list
+    Warn,
+    // Flag them and leave them out of bulk deletes
+    Skip,
+}
+
+impl FromStr for OpenProjects {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(OpenProjects::Off),
+            "warn" => Ok(OpenProjects::Warn),
+            "skip" => Ok(OpenProjects::Skip),
+            _ => Err(format!("unknown value '{s}', expected off, warn or skip")),
+        }
+    }
+}
+
+/// Editor to open files and projects with: `configured`, or `$VISUAL` or
+/// `$EDITOR`, or the system's basic one. It can come with arguments, like
+/// `code --wait`.
+pub fn editor(configured: &str) -> String {
+    if !configured.trim().is_empty() {
+        return configured.to_string();
+    }
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Run `editor` on `path`, waiting for it to quit
+pub fn edit(editor: &str, path: &Path) -> std::io::Result<ExitStatus> {
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "editor is empty"))?;
+    Command::new(program).args(parts).arg(path).status()
+}
+
+/// Show the folder at `path` in the system file manager, without waiting
+pub fn open_folder(path: &Path) -> std::io::Result<()> {
+    let program = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(program)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| std::io::Error::new(e.kind(), format!("{program}: {e}")))?;
+    // Reap it whenever it's done
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// VS Code workspaces used within this time are considered open
+const RECENT: Duration = Duration::from_secs(60 * 60);
+
+/// Folders that look like they are open in an editor or IDE right now
+pub fn open_folders() -> Vec<PathBuf> {
+    let mut folders = rust_analyzer_folders();
+    folders.extend(vscode_folders());
+
+    // Editors started from home would match everything
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    folders.retain(|f| f.parent().is_some() && Some(f) != home.as_ref());
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+/// Whether `project` is one of, inside of, or contains one of `open` folders
+pub fn is_open(project: &Path, open: &[PathBuf]) -> bool {
+    open.iter()
+        .any(|folder| project.starts_with(folder) || folder.starts_with(project))
+}
+
+// rust-analyzer runs with the workspace root as its working directory
+#[cfg(target_os = "linux")]
+fn rust_analyzer_folders() -> Vec<PathBuf> {
+    let Ok(proc) = std::fs::read_dir("/proc") else {
+        return vec![];
+    };
+
+    proc.flatten()
+        .filter(|entry| {
+            std::fs::read_to_string(entry.path().join("comm"))
+                .is_ok_and(|comm| comm.trim() == "rust-analyzer")
+        })
+        .filter_map(|entry| std::fs::read_link(entry.path().join("cwd")).ok())
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rust_analyzer_folders() -> Vec<PathBuf> {
+    vec![]
+}
+
+// VS Code keeps per workspace state in workspaceStorage/<hash>/, with the
+// opened folder in workspace.json. Storage that was written to recently
+// belongs to a window that is most likely still open.
+fn vscode_folders() -> Vec<PathBuf> {
+    let Some(storage) = vscode_storage() else {
+        return vec![];
+    };
+    let Ok(dir) = std::fs::read_dir(storage) else {
+        return vec![];
+    };
+
+    let now = SystemTime::now();
+    dir.flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| {
+                    now.duration_since(modified)
+                        .is_ok_and(|since| since < RECENT)
+                })
+        })
+        .filter_map(|entry| {
+            let json = std::fs::read_to_string(entry.path().join("workspace.json")).ok()?;
+            folder_from_workspace_json(&json)
+        })
+        .collect()
+}
+
+fn vscode_storage() -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?);
+
+    let config = if cfg!(target_os = "macos") {
+        home.join("Library").join("Application Support")
+    } else if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME").map_or_else(|| home.join(".config"), PathBuf::from)
+    };
+
+    Some(config.join("Code").join("User").join("workspaceStorage"))
+}
+
+// Pull the local folder out of `{"folder": "file:///path/to/project"}`
+fn folder_from_workspace_json(json: &str) -> Option<PathBuf> {
+    let rest = &json[json.find("\"folder\"")? + "\"folder\"".len()..];
+    let rest = &rest[rest.find('"')? + 1..];
+    let url = &rest[..rest.find('"')?];
+    let path = percent_decode(url.strip_prefix("file://")?);
+
+    // file:///c:/Users/... on Windows
+    if cfg!(windows) {
+        return Some(PathBuf::from(path.trim_start_matches('/')));
+    }
+    Some(PathBuf::from(path))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,113 @@
+use std::{fmt, io, path::PathBuf};
+
+use crate::i18n::{t, tf};
+
+/// Something that went wrong while scanning, deleting or drawing
+#[derive(Debug)]
+pub enum Error {
+    // Terminal or other I/O not about a single folder
+    Io(io::Error),
+    // Listing a folder failed while scanning
+    Read { path: PathBuf, source: io::Error },
+    Delete { path: PathBuf, source: io::Error },
+    Trash { path: PathBuf, source: trash::Error },
+    // Sending to the Windows recycle bin from WSL failed
+    Recycle { path: PathBuf, source: io::Error },
+    // Moving into the quarantine folder failed
+    Quarantine { path: PathBuf, source: io::Error },
+    Salvage { path: PathBuf, source: io::Error },
+    // Running cargo clean in the project failed
+    Clean { path: PathBuf, source: io::Error },
+    // The project doesn't build after its target was removed
+    Check { path: PathBuf, source: io::Error },
+    // A guard decided the folder must not be deleted
+    Refused { path: PathBuf, reason: &'static str },
+    // Running rclin on the --ssh host failed, or what it ran there did
+    Remote { path: PathBuf, source: io::Error },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Error::Io(e) => e.to_string(),
+            Error::Read { path, source } => tf("Cannot read {}: {}", &[&path.display(), source]),
+            Error::Delete { path, source } => {
+                tf("Deleting {} failed: {}", &[&path.display(), source])
+            }
+            Error::Trash { path, source } => {
+                tf("Trashing {} failed: {}", &[&path.display(), source])
+            }
+            Error::Recycle { path, source } => tf(
+                "Moving {} to the recycle bin failed: {}",
+                &[&path.display(), source],
+            ),
+            Error::Quarantine { path, source } => tf(
+                "Moving {} to the quarantine failed: {}",
+                &[&path.display(), source],
+            ),
+            Error::Salvage { path, source } => tf(
+                "Failed to salvage binaries from {}: {}",
+                &[&path.display(), source],
+            ),
+            Error::Clean { path, source } => {
+                tf("Cargo clean in {} failed: {}", &[&path.display(), source])
+            }
+            Error::Check { path, source } => tf(
+                "{} doesn't build after cleaning: {}",
+                &[&path.display(), source],
+            ),
+            Error::Refused { path, reason } => {
+                tf("Refusing to delete {}: {}", &[&path.display(), &t(reason)])
+            }
+            Error::Remote { path, source } => {
+                tf("{} over SSH failed: {}", &[&path.display(), source])
+            }
+        };
+        f.write_str(&message)
+    }
+}
+
+impl Error {
+    /// What went wrong without the path, for next to it
+    pub fn reason(&self) -> String {
+        match self {
+            Error::Trash { source, .. } => source.to_string(),
+            Error::Refused { reason, .. } => t(reason).to_string(),
+            Error::Io(source)
+            | Error::Read { source, .. }
+            | Error::Delete { source, .. }
+            | Error::Recycle { source, .. }
+            | Error::Quarantine { source, .. }
+            | Error::Salvage { source, .. }
+            | Error::Clean { source, .. }
+            | Error::Check { source, .. }
+            | Error::Remote { source, .. } => source.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(source)
+            | Error::Read { source, .. }
+            | Error::Delete { source, .. }
+            | Error::Recycle { source, .. }
+            | Error::Quarantine { source, .. }
+            | Error::Salvage { source, .. }
+            | Error::Clean { source, .. }
+            | Error::Check { source, .. }
+            | Error::Remote { source, .. } => Some(source),
+            Error::Trash { source, .. } => Some(source),
+            Error::Refused { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,34 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::history;
+
+/// File the projects excluded from the list with `x` are kept in, next to the
+/// history
+pub fn path() -> Option<PathBuf> {
+    Some(history::path()?.with_file_name("excluded"))
+}
+
+/// Excluded projects, one folder per line. None without the file.
+pub fn load(path: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Replace the excluded projects with `projects`
+pub fn save(path: &Path, projects: &[PathBuf]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::File::create(path)?;
+    for project in projects {
+        writeln!(file, "{}", project.display())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use crate::{
+    cargo,
+    detectors::{self, DETECTORS},
+    scan::{self, Kind},
+    size,
+};
+
+/// `rclin explain <path>`: tell why `path` is or isn't listed, deciding it
+/// with the detectors and skip rules of the scan. Returns exit code, 0 when
+/// it would be listed.
+pub fn explain(path: &Path, options: &scan::Options) -> i32 {
+    println!("{}", path.display());
+
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            println!("  not scanned: {e}");
+            return 1;
+        }
+    };
+    if meta.is_symlink() && !options.follow_symlinks {
+        println!("  not scanned: symbolic link, see --follow-symlinks");
+        return 1;
+    }
+    if !path.is_dir() {
+        println!("  not scanned: not a folder");
+        return 1;
+    }
+    let skipped = scan::skipped(path, options);
+    if let Some(reason) = skipped {
+        println!("  not scanned when scanning a parent folder: {reason}");
+    }
+
+    let found = match detectors::find(path, &options.kinds) {
+        Ok(found) => found,
+        Err(e) => {
+            println!("  not scanned: cannot read folder: {e}");
+            return 1;
+        }
+    };
+    if found.is_empty() {
+        not_found(path, options);
+        return 1;
+    }
+
+    let mut listed = false;
+    for (kind, artifact) in &found {
+        let size = size::format_size(size::dir_size(artifact));
+        println!("  {} project found", kind.name());
+        if *kind == Kind::Rust {
+            print_target_source(path);
+        }
+        if detectors::is_cache_dir(artifact) {
+            println!(
+                "  {} is tagged as a cache by CACHEDIR.TAG",
+                artifact.display()
+            );
+        }
+        if options.skip_unbuilt && detectors::is_unbuilt(*kind, path, artifact) {
+            println!(
+                "  not listed: {} was never built, see --skip-unbuilt",
+                artifact.display()
+            );
+        } else if skipped.is_some() {
+            println!(
+                "  {} ({size}) is only listed when scanning inside {}",
+                artifact.display(),
+                path.display()
+            );
+        } else {
+            println!("  listed: {} ({size})", artifact.display());
+            listed = true;
+        }
+    }
+    if listed {
+        0
+    } else {
+        1
+    }
+}
+
+// Why no artifact folder of any kind looked for is in `dir`
+fn not_found(dir: &Path, options: &scan::Options) {
+    let kinds: Vec<&str> = options.kinds.iter().map(|k| k.name()).collect();
+    let detectors = DETECTORS.iter().filter(|d| options.kinds.contains(&d.kind));
+    let mut manifest = false;
+    for detector in detectors {
+        // Manifests that are symlinks are not followed
+        for name in detector.manifests {
+            let meta = std::fs::symlink_metadata(dir.join(name));
+            if meta.as_ref().is_ok_and(|m| m.is_symlink()) {
+                println!("  {name} is a symbolic link, links are not followed");
+            } else if meta.is_ok_and(|m| m.is_file()) {
+                println!("  {name} found, no {} artifacts yet", detector.kind.name());
+                manifest = true;
+            }
+        }
+        if detector.kind == Kind::Rust && dir.join("Cargo.toml").is_file() {
+            print_target_source(dir);
+            let target = cargo::target_dir(dir);
+            if target.is_dir() {
+                println!(
+                    "  not listed: {} is outside the project and not tagged as a cache by cargo",
+                    target.display()
+                );
+            } else {
+                println!("  not listed: {} does not exist", target.display());
+            }
+        }
+    }
+    if !manifest {
+        println!("  not a project: no manifest of {} found", kinds.join(", "));
+        if dir.file_name().is_some_and(|n| n == "target") {
+            println!("  this looks like a target folder, explain its project folder instead");
+        }
+    }
+}
+
+fn print_target_source(project: &Path) {
+    match cargo::configured_target_dir(project) {
+        Some((target, source)) => {
+            println!("  target folder {} set by {source}", target.display())
+        }
+        None => println!("  target folder is the default, next to Cargo.toml"),
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    time::{Duration, SystemTime},
+};
+
+/// Whether `project` looks like it's being worked on: its git repository
+/// has uncommitted changes in the project's folder, or commits to it within
+/// `within`. False outside git repositories and without git.
+pub fn is_active(project: &Path, target: &Path, within: Duration) -> bool {
+    has_changes(project, target) || last_commit(project).is_some_and(|c| is_recent(c, within))
+}
+
+// Changed, staged or untracked files in the project besides its target, the
+// target isn't always ignored
+fn has_changes(project: &Path, target: &Path) -> bool {
+    let mut status = git(project);
+    status.args(["status", "--porcelain", "--", "."]);
+    if let Ok(target) = target.strip_prefix(project) {
+        status.arg(format!(":(exclude){}", target.display()));
+    }
+    output(status).is_some_and(|out| !out.trim().is_empty())
+}
+
+// Time of the latest commit touching the project's folder. In a monorepo
+// commits elsewhere don't count.
+fn last_commit(project: &Path) -> Option<SystemTime> {
+    let mut log = git(project);
+    log.args(["log", "-1", "--format=%ct", "--", "."]);
+    let secs = output(log)?.trim().parse().ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+fn is_recent(time: SystemTime, within: Duration) -> bool {
+    SystemTime::now()
+        .duration_since(time)
+        .map_or(true, |age| age < within)
+}
+
+fn git(project: &Path) -> Command {
+    let mut git = Command::new("git");
+    git.arg("-C")
+        .arg(project)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    git
+}
+
+// Standard output of a command that succeeded
+fn output(mut command: Command) -> Option<String> {
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,167 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Ignore rules of a folder, from its `.gitignore` and `.ignore` files, and of
+/// the folders above it. Only folders are ever matched, the scanner doesn't
+/// look at files.
+#[derive(Debug)]
+pub struct Rules {
+    // Folder the patterns are relative to
+    base: PathBuf,
+    patterns: Vec<Pattern>,
+    parent: Option<Arc<Rules>>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    glob: Vec<char>,
+    // Starts with `!`, un-ignores what an earlier pattern ignored
+    negated: bool,
+    // Has a slash, matched against the path below the base instead of the name
+    anchored: bool,
+}
+
+impl Rules {
+    /// Rules from gitignore style `lines`, relative to `base`
+    pub fn new<'a>(base: &Path, lines: impl IntoIterator<Item = &'a str>) -> Rules {
+        Rules {
+            base: base.to_path_buf(),
+            patterns: lines.into_iter().filter_map(Pattern::parse).collect(),
+            parent: None,
+        }
+    }
+
+    /// Rules for the contents of `dir`: its own ignore files on top of
+    /// `parent`. Returns `parent` if there are no ignore files.
+    pub fn load(dir: &Path, parent: Option<Arc<Rules>>) -> Option<Arc<Rules>> {
+        // `.ignore` comes last to win over `.gitignore`
+        let content: String = [".gitignore", ".ignore"]
+            .iter()
+            .filter_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut rules = Rules::new(dir, content.lines());
+        if rules.patterns.is_empty() {
+            return parent;
+        }
+        rules.parent = parent;
+        Some(Arc::new(rules))
+    }
+
+    /// Whether folder `path` is ignored
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.decide(path).unwrap_or(false)
+    }
+
+    // Last matching pattern of the closest ignore file decides
+    fn decide(&self, path: &Path) -> Option<bool> {
+        let own = path.strip_prefix(&self.base).ok().and_then(|relative| {
+            let relative: Vec<char> = relative
+                .to_string_lossy()
+                .replace('\\', "/")
+                .chars()
+                .collect();
+            let name: Vec<char> = path.file_name()?.to_string_lossy().chars().collect();
+
+            self.patterns.iter().rev().find_map(|pattern| {
+                let text = if pattern.anchored { &relative } else { &name };
+                glob_match(&pattern.glob, text).then_some(!pattern.negated)
+            })
+        });
+        own.or_else(|| self.parent.as_ref()?.decide(path))
+    }
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Option<Pattern> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        // Only folders are matched, so a trailing slash changes nothing
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Pattern {
+            glob: line.chars().collect(),
+            negated,
+            anchored,
+        })
+    }
+}
+
+/// Match a gitignore style glob: `*` and `?` stay within a path component,
+/// `**` spans components and `[a-z]` matches a class of characters
+pub fn glob_match(p: &[char], t: &[char]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => {
+            let rest = &p[2..];
+            match rest.first() {
+                None => true,
+                // `**/` matches zero or more whole folders
+                Some('/') => (0..=t.len())
+                    .filter(|&i| i == 0 || t[i - 1] == '/')
+                    .any(|i| glob_match(&rest[1..], &t[i..])),
+                Some(_) => (0..=t.len()).any(|i| glob_match(rest, &t[i..])),
+            }
+        }
+        Some('*') => {
+            for i in 0..=t.len() {
+                if glob_match(&p[1..], &t[i..]) {
+                    return true;
+                }
+                if t.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => t.first().is_some_and(|c| *c != '/') && glob_match(&p[1..], &t[1..]),
+        Some('[') => match class(&p[1..], t.first().copied()) {
+            Some((matched, len)) => matched && glob_match(&p[1 + len..], &t[1..]),
+            // No closing bracket, it's a plain character
+            None => t.first() == Some(&'[') && glob_match(&p[1..], &t[1..]),
+        },
+        Some('\\') if p.len() > 1 => t.first() == Some(&p[1]) && glob_match(&p[2..], &t[1..]),
+        Some(c) => t.first() == Some(c) && glob_match(&p[1..], &t[1..]),
+    }
+}
+
+// Match `c` against the class starting after `[`. Returns whether it matched
+// and the length of the class including the closing `]`.
+fn class(p: &[char], c: Option<char>) -> Option<(bool, usize)> {
+    let negated = matches!(p.first(), Some('!' | '^'));
+    let start = usize::from(negated);
+    // `]` right after the opening bracket is a member
+    let end = start + 1 + p.get(start + 1..)?.iter().position(|&ch| ch == ']')?;
+    let members = &p[start..end];
+
+    let Some(c) = c.filter(|c| *c != '/') else {
+        return Some((false, end + 1));
+    };
+    let mut matched = false;
+    let mut i = 0;
+    while i < members.len() {
+        if i + 2 < members.len() && members[i + 1] == '-' {
+            matched |= (members[i]..=members[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            matched |= members[i] == c;
+            i += 1;
+        }
+    }
+    Some((matched != negated, end + 1))
+}
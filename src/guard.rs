@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    detectors,
+    scan::{Entry, Kind},
+};
+
+/// Why deleting `entry` must not happen, checked right before deleting it
+pub fn refuse(entry: &Entry, roots: &[PathBuf]) -> Option<&'static str> {
+    untagged(entry)
+        .or_else(|| changed(entry))
+        .or_else(|| protected(&entry.path, roots))
+}
+
+// Whether a Rust target is outside its project without cargo's tag, so set
+// by a config to something that isn't a target folder
+fn untagged(entry: &Entry) -> Option<&'static str> {
+    let outside = entry.kind == Kind::Rust && !entry.path.starts_with(&entry.project);
+    (outside && !detectors::is_cache_dir(&entry.path))
+        .then_some("it is outside its project and not tagged as a cache by cargo")
+}
+
+// Whether the folder still is what the scan found. Things can change between
+// scanning and deleting, a folder swapped for a symlink would have the link
+// target deleted.
+fn changed(entry: &Entry) -> Option<&'static str> {
+    let path = entry.path.as_path();
+    let project = entry.project.as_path();
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_symlink() => return Some("it has been replaced by a symbolic link"),
+        Ok(meta) if !meta.is_dir() => return Some("it is not a folder anymore"),
+        Ok(_) => (),
+        Err(_) => return Some("it cannot be found anymore"),
+    }
+
+    let mut manifests = detectors::DETECTORS
+        .iter()
+        .filter(|d| d.kind == entry.kind)
+        .flat_map(|d| d.manifests)
+        .peekable();
+    // Kinds without manifests are found in any folder
+    let manifest = manifests.peek().is_none()
+        || manifests
+            .any(|m| std::fs::symlink_metadata(project.join(m)).is_ok_and(|meta| meta.is_file()));
+    if !manifest {
+        return Some("its project has no manifest anymore");
+    }
+
+    // Same artifact folder the detector would find now, by name and place
+    let found = detectors::find(project, &[entry.kind]).unwrap_or_default();
+    if !found.iter().any(|(_, artifact)| artifact == path) {
+        return Some("it is no longer an artifact folder of its project");
+    }
+    None
+}
+
+// Whether `path` is the current folder, a scanned root or a folder containing
+// either. Catches misbehaving detectors and crafted folder layouts, like a
+// `.cargo/config.toml` pointing the target folder to a parent.
+fn protected(path: &Path, roots: &[PathBuf]) -> Option<&'static str> {
+    // Compare real locations, symlinks and `..` could hide a match
+    let path = path.canonicalize().ok()?;
+    let contains = |other: &Path| other.canonicalize().is_ok_and(|o| o.starts_with(&path));
+
+    if std::env::current_dir().is_ok_and(|cwd| contains(&cwd)) {
+        Some("it is or contains the current folder")
+    } else if roots.iter().any(|root| contains(root)) {
+        Some("it is or contains a scanned folder")
+    } else {
+        None
+    }
+}
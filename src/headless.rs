@@ -0,0 +1,846 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    args::Args,
+    auto::{self, Reason},
+    cargo,
+    deleter::{self, Cleanup, Job, Removal},
+    diff, disk, duplicates,
+    editors::{self, OpenProjects},
+    error::Error,
+    guard,
+    journal::{self, Interrupted, Journal},
+    output::Output,
+    quarantine, quiet,
+    report::{self, Report},
+    scan::{self, Entry, Kind},
+    size::{self, format_size},
+    trashcan, users,
+};
+
+// Exit codes of --list and --clean
+const OK: i32 = 0;
+// Nothing found, or nothing done
+const NOTHING: i32 = 1;
+const FAILED: i32 = 2;
+
+/// `rclin --list`: print found targets and their sizes, biggest first
+pub fn list(args: &Args) -> i32 {
+    let Some(entries) = find(args) else {
+        return FAILED;
+    };
+
+    for entry in &entries {
+        let open = if entry.open { "  (open in editor)" } else { "" };
+        let active = if entry.active {
+            "  (active in git)"
+        } else {
+            ""
+        };
+        let placement = if entry.shared_dir {
+            "  (shared target-dir)"
+        } else if entry.stale {
+            "  (stale, cargo builds elsewhere)"
+        } else {
+            ""
+        };
+        println!(
+            "{:>10}  {}{placement}{open}{active}",
+            format_size(entry.size),
+            entry.path.display()
+        );
+    }
+
+    if entries.is_empty() {
+        NOTHING
+    } else {
+        OK
+    }
+}
+
+/// `rclin --output json|csv`: print found targets, biggest first, for other
+/// tools
+pub fn output(args: &Args, output: Output) -> i32 {
+    let Some(entries) = find(args) else {
+        return FAILED;
+    };
+
+    print!("{}", output.format(&entries));
+    if entries.is_empty() {
+        NOTHING
+    } else {
+        OK
+    }
+}
+
+/// `rclin --clean`: trash, or with `--permanent` delete, every found target
+/// without the interface. Asks first unless `--yes` is given, in which case
+/// nothing is done during quiet hours.
+pub fn clean(args: &Args) -> i32 {
+    if let Some(quiet) = args
+        .quiet_hours
+        .filter(|q| args.yes && q.contains(quiet::now()))
+    {
+        println!("Quiet hours until {}, not cleaning", quiet.end());
+        return NOTHING;
+    }
+    let Some(mut entries) = find(args) else {
+        return FAILED;
+    };
+
+    skip_busy(args, &mut entries);
+    if entries.is_empty() {
+        println!("No target folders found!");
+        return NOTHING;
+    }
+
+    if let Some(budget) = args.max_total {
+        entries = within_budget(args, entries, budget);
+        if entries.is_empty() {
+            return NOTHING;
+        }
+    }
+
+    if let Some(warning) = trashcan::deletes_instead(&args.paths).filter(|_| !args.permanent) {
+        eprintln!("{warning}");
+    }
+    if args.dry_run {
+        return dry_run(args, &entries);
+    }
+
+    let total = format_size(entries.iter().map(|e| e.size).sum());
+    let question = format!("{} {} target folders ({total})?", verb(args), entries.len());
+    if !args.yes && !confirm(&question) {
+        println!("Nothing deleted");
+        return NOTHING;
+    }
+
+    delete_all(args, entries).0
+}
+
+/// `rclin auto`: delete what the auto rules pick without asking, for cron
+/// or a systemd timer, and log what was done. Nothing is done during quiet
+/// hours.
+pub fn auto(args: &Args) -> i32 {
+    if args.auto.is_empty() {
+        eprintln!("No rules for rclin auto, set auto-older-than or auto-max-total in the config");
+        return FAILED;
+    }
+    if let Some(quiet) = args.quiet_hours.filter(|q| q.contains(quiet::now())) {
+        println!("Quiet hours until {}, not cleaning", quiet.end());
+        return NOTHING;
+    }
+    let Some(mut entries) = find(args) else {
+        return FAILED;
+    };
+    skip_busy(args, &mut entries);
+
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    println!(
+        "Found {} target folders ({})",
+        entries.len(),
+        format_size(total)
+    );
+    let picked: Vec<(Entry, Reason)> = args
+        .auto
+        .pick(&entries, |e| guard::refuse(e, &args.paths).is_none())
+        .into_iter()
+        .map(|(e, reason)| (e.clone(), reason))
+        .collect();
+    if picked.is_empty() {
+        println!("Nothing to clean by the rules");
+        return NOTHING;
+    }
+    for (entry, reason) in &picked {
+        let why = match reason {
+            Reason::Old => "unused since",
+            Reason::OverBudget => "over the budget, last built",
+        };
+        let built = entry
+            .modified
+            .map_or("an unknown time".to_string(), report::format_time);
+        println!(
+            "  {}  {:>10}  {why} {built}",
+            entry.path.display(),
+            format_size(entry.size)
+        );
+    }
+
+    if let Some(warning) = trashcan::deletes_instead(&args.paths).filter(|_| !args.permanent) {
+        eprintln!("{warning}");
+    }
+    let entries: Vec<Entry> = picked.into_iter().map(|(e, _)| e).collect();
+    if args.dry_run {
+        return dry_run(args, &entries);
+    }
+
+    let (code, lines) = delete_all(args, entries);
+    let Some(path) = auto::log_path() else {
+        return code;
+    };
+    let roots: Vec<String> = args.paths.iter().map(|p| p.display().to_string()).collect();
+    let mut log = format!(
+        "{} rclin auto in {}\n",
+        report::format_time(SystemTime::now()),
+        roots.join(", ")
+    );
+    for line in lines {
+        log.push_str(&format!("  {line}\n"));
+    }
+    if let Err(e) = auto::log(&path, &log) {
+        eprintln!("Writing log to {} failed: {e}", path.display());
+        return FAILED;
+    }
+    code
+}
+
+/// `rclin purge-quarantine <age>`: delete folders moved into the quarantine
+/// at least `age` ago for good
+pub fn purge_quarantine(args: &Args, age: Duration) -> i32 {
+    let Some(dir) = &args.quarantine else {
+        eprintln!("No quarantine to purge, set one with --quarantine or in the config");
+        return FAILED;
+    };
+    let old: Vec<quarantine::Item> = quarantine::items(dir)
+        .into_iter()
+        .filter(|item| item.is_older(age))
+        .collect();
+    let verb = if args.dry_run {
+        "Would purge"
+    } else {
+        "Purged"
+    };
+
+    let mut count = 0;
+    let mut freed = 0;
+    let mut failed = false;
+    for item in &old {
+        let size = size::dir_size(&item.dir);
+        let path = item.origin.display().to_string();
+        if !args.dry_run {
+            if let Err(e) = quarantine::purge(item) {
+                tracing::warn!(target: "rclin::delete", path, "purging from quarantine failed: {e}");
+                eprintln!("Purging {path} from the quarantine failed: {e}");
+                failed = true;
+                continue;
+            }
+            tracing::info!(target: "rclin::delete", path, size, "Purged from quarantine");
+        }
+        println!("{verb} {path} ({})", format_size(size));
+        count += 1;
+        freed += size;
+    }
+    println!(
+        "{verb} {count} folders from {}, freeing {}",
+        dir.display(),
+        format_size(freed)
+    );
+
+    if failed {
+        FAILED
+    } else if count == 0 {
+        NOTHING
+    } else {
+        OK
+    }
+}
+
+/// `rclin --all-users`: scan the home folders of all users and print how much
+/// each could reclaim
+pub fn users(args: &Args) -> i32 {
+    let users = users::users();
+    if users.is_empty() {
+        println!("No users found!");
+        return NOTHING;
+    }
+
+    let mut results = vec![];
+    for user in users {
+        println!("Scanning {}...", user.home.display());
+        let mut entries = vec![];
+        let options = args.scan_options(Some(&user.home));
+        if let Err(e) = scan::scan(std::slice::from_ref(&user.home), &options, &mut entries) {
+            println!("Scanning {} failed: {e}", user.home.display());
+        }
+        results.push((user, entries));
+    }
+
+    println!();
+    report::print_users(&mut results);
+
+    if let Some(dest) = &args.report {
+        let report = Report {
+            groups: results
+                .into_iter()
+                .map(|(user, entries)| (user.name, entries))
+                .collect(),
+            deleted: vec![],
+        };
+        if let Err(e) = report.write(args.report_format, dest) {
+            println!("Writing report to {dest} failed: {e}");
+            return FAILED;
+        }
+    }
+    OK
+}
+
+/// `rclin remove <project> <target>`: clean up `target` like `--clean --yes`
+/// would, if a scan of `project` in `args` still finds it. The other end of
+/// `--ssh`.
+pub fn remove(args: &Args, target: &str) -> i32 {
+    let Some(entries) = find(args) else {
+        return FAILED;
+    };
+    let Some(entry) = entries.into_iter().find(|e| e.path == Path::new(target)) else {
+        eprintln!(
+            "{target} is not a target of {} anymore, nothing deleted",
+            args.paths[0].display()
+        );
+        return NOTHING;
+    };
+    delete_all(args, vec![entry]).0
+}
+
+/// `rclin plan <plan.json>`: write what `--clean` would clean to `file`, as
+/// `--output json` does, for someone to review and `rclin apply` later
+pub fn plan(args: &Args, file: &str) -> i32 {
+    let Some(mut entries) = find(args) else {
+        return FAILED;
+    };
+    skip_busy(args, &mut entries);
+    if let Some(budget) = args.max_total {
+        entries = within_budget(args, entries, budget);
+    }
+    entries.retain(|entry| match guard::refuse(entry, &args.paths) {
+        Some(reason) => {
+            println!("Leaving out {}: {reason}", entry.path.display());
+            false
+        }
+        None => true,
+    });
+    if entries.is_empty() {
+        println!("No target folders to plan cleaning!");
+        return NOTHING;
+    }
+
+    if let Err(e) = std::fs::write(file, Output::Json.format(&entries)) {
+        eprintln!("Cannot write {file}: {e}");
+        return FAILED;
+    }
+    for entry in &entries {
+        println!("{:>10}  {}", format_size(entry.size), entry.path.display());
+    }
+    let total = format_size(entries.iter().map(|e| e.size).sum());
+    println!(
+        "Planned cleaning {} target folders ({total}) in {file}",
+        entries.len()
+    );
+    OK
+}
+
+/// `rclin apply <plan.json>`: clean up the targets of a plan like `--clean`
+/// would, leaving alone the ones that aren't the same size and age anymore
+/// or aren't found at all, as those aren't what was reviewed
+pub fn apply(args: &Args, file: &str) -> i32 {
+    let planned = match std::fs::read_to_string(file)
+        .map_err(|e| e.to_string())
+        .and_then(|json| diff::parse(&json))
+    {
+        Ok(planned) => planned,
+        Err(e) => {
+            eprintln!("Cannot read {file}: {e}");
+            return FAILED;
+        }
+    };
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let options = args.scan_options(home.as_deref());
+    let mut entries = vec![];
+    let mut changed = 0;
+    for target in planned {
+        // Scanned again like the interface does, to see what's there now
+        let mut found = vec![];
+        let project = PathBuf::from(&target.project);
+        if let Err(e) = scan::scan(&[project], &options, &mut found) {
+            eprintln!("Scanning {} failed: {e}", target.project);
+        }
+        let Some(entry) = found.into_iter().find(|e| e.path == target.path) else {
+            println!("Leaving {} alone, it's not a target anymore", target.path);
+            changed += 1;
+            continue;
+        };
+        let modified = entry.modified.map(report::format_timestamp);
+        let change = if entry.size != target.size {
+            Some(format!(
+                "it took {} then and {} now",
+                format_size(target.size),
+                format_size(entry.size)
+            ))
+        } else if modified != target.modified {
+            Some("it was built again since the plan".to_string())
+        } else {
+            None
+        };
+        if let Some(change) = change {
+            println!("Leaving {} alone, {change}", target.path);
+            changed += 1;
+            continue;
+        }
+        entries.push(entry);
+    }
+
+    skip_busy(args, &mut entries);
+    if entries.is_empty() {
+        println!("Nothing in the plan to clean");
+        return if changed > 0 { FAILED } else { NOTHING };
+    }
+    if args.dry_run {
+        return dry_run(args, &entries);
+    }
+    let total = format_size(entries.iter().map(|e| e.size).sum());
+    let question = format!(
+        "{} {} planned target folders ({total})?",
+        verb(args),
+        entries.len()
+    );
+    if !args.yes && !confirm(&question) {
+        println!("Nothing deleted");
+        return NOTHING;
+    }
+
+    let code = delete_all(args, entries).0;
+    if changed > 0 {
+        eprintln!("{changed} target folders of the plan were left alone");
+        return FAILED;
+    }
+    code
+}
+
+/// `rclin duplicates`: tell which dependencies are built the same in several
+/// Rust targets and what sharing one target folder would save
+pub fn duplicates(args: &Args) -> i32 {
+    let Some(entries) = find(args) else {
+        return FAILED;
+    };
+    let targets: Vec<&Path> = entries
+        .iter()
+        .filter(|e| e.kind == Kind::Rust)
+        .map(|e| e.path.as_path())
+        .collect();
+    let report = duplicates::find(&targets);
+
+    for duplicate in &report.duplicates {
+        println!(
+            "{:>10}  {} in {} targets, {} each",
+            format_size(duplicate.savings),
+            duplicate.name,
+            duplicate.targets.len(),
+            format_size(duplicate.size)
+        );
+        if args.verbose > 0 {
+            for target in &duplicate.targets {
+                println!("            {target}");
+            }
+        }
+    }
+    println!(
+        "{} dependencies are built the same in several of {} Rust targets, their deps folders take {}",
+        report.duplicates.len(),
+        report.targets,
+        format_size(report.total)
+    );
+    if report.duplicates.is_empty() {
+        return NOTHING;
+    }
+    println!(
+        "One target folder shared by these projects would save {}: set CARGO_TARGET_DIR, or build.target-dir in ~/.cargo/config.toml. \
+         sccache shares the compiling too but still copies the artifacts into every target.",
+        format_size(report.savings())
+    );
+    OK
+}
+
+// Leave out projects open in an editor if asked to, and active ones, telling
+// how many
+fn skip_busy(args: &Args, entries: &mut Vec<Entry>) {
+    if args.open_projects == OpenProjects::Skip {
+        let count = entries.len();
+        entries.retain(|e| !e.open);
+        if entries.len() < count {
+            println!(
+                "Skipping {} projects open in an editor",
+                count - entries.len()
+            );
+        }
+    }
+    let count = entries.len();
+    entries.retain(|e| !e.active);
+    if entries.len() < count {
+        println!(
+            "Skipping {} projects with uncommitted changes or recent commits",
+            count - entries.len()
+        );
+    }
+}
+
+// Delete `entries` one at a time, journaled, then verify and report as asked.
+// Returns the exit code and what was done to each, as printed.
+fn delete_all(args: &Args, entries: Vec<Entry>) -> (i32, Vec<String>) {
+    let paths: Vec<PathBuf> = entries.iter().map(|e| e.path.clone()).collect();
+    let mut journal = Journal::start(
+        args.history.then(journal::path).flatten().as_deref(),
+        &paths,
+    );
+    let mut lines = vec![];
+    let mut deleted = vec![];
+    let mut remaining = vec![];
+    let mut freed = 0;
+    let mut failed = 0;
+    for entry in entries {
+        journal.begin(&entry.path);
+        let job = Job {
+            entry,
+            permanent: args.permanent,
+            cleanup: args.cleanup(),
+            salvage: args.salvage,
+        };
+        let (installed, result) = deleter::run(&job, &args.paths, &args.cargo_clean_args);
+        if !installed.is_empty() {
+            println!("Installed {}", installed.join(", "));
+        }
+        let entry = job.entry;
+        match result {
+            Ok(removal) => {
+                let done = said(removal);
+                journal.done(&entry.path);
+                let (path, size) = (entry.path.display(), entry.size);
+                tracing::info!(target: "rclin::delete", %path, size, "{done}");
+                let line = format!("{done} {}", entry.path.display());
+                println!("{line}");
+                lines.push(line);
+                // Cargo clean may leave some of it
+                let left = size::dir_stats_except(&entry.path, &|dir| entry.is_kept(dir));
+                freed += entry.size.saturating_sub(left.size);
+                deleted.push(entry);
+            }
+            Err(e) => {
+                journal.failed(&entry.path);
+                tracing::warn!(target: "rclin::delete", path = %entry.path.display(), "{e}");
+                eprintln!("{e}");
+                lines.push(e.to_string());
+                failed += 1;
+                remaining.push(entry);
+            }
+        }
+    }
+    journal.finish();
+
+    let summary = format!(
+        "{} {} target folders ({})",
+        done(args),
+        deleted.len(),
+        format_size(freed)
+    );
+    println!("{summary}");
+    lines.push(summary);
+    let broken = if args.verify {
+        verify(args, &deleted)
+    } else {
+        0
+    };
+
+    if let Some(dest) = &args.report {
+        let report = Report {
+            groups: report::group_by_root(&args.paths, remaining),
+            deleted,
+        };
+        if let Err(e) = report.write(args.report_format, dest) {
+            eprintln!("Writing report to {dest} failed: {e}");
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("Failed to delete {failed} target folders");
+    }
+    if broken > 0 {
+        eprintln!("{broken} projects don't build anymore");
+        lines.push(format!("{broken} projects don't build anymore"));
+    }
+    let code = if failed > 0 || broken > 0 { FAILED } else { OK };
+    (code, lines)
+}
+
+/// `rclin --emergency`: offer the biggest targets one at a time for deleting
+/// permanently right away, for when the disk is full and even trashing
+/// fails. Nothing else is written.
+pub fn emergency(args: &Args) -> i32 {
+    let root = &args.paths[0];
+    let free = |label: &str| {
+        if let Some(free) = disk::free_space(root) {
+            println!("{label} {} free", format_size(free));
+        }
+    };
+    free("Disk has");
+    let Some(entries) = find(args) else {
+        return FAILED;
+    };
+    if entries.is_empty() {
+        println!("No target folders found!");
+        return NOTHING;
+    }
+
+    let mut deleted = 0;
+    for entry in &entries {
+        if let Some(reason) = guard::refuse(entry, &args.paths) {
+            println!("Skipping {}: {reason}", entry.path.display());
+            continue;
+        }
+        let open = if entry.open {
+            ", open in an editor"
+        } else {
+            ""
+        };
+        let active = if entry.active { ", active in git" } else { "" };
+        let question = format!(
+            "Delete {} ({}{open}{active}) permanently? [y/N/q]",
+            entry.path.display(),
+            format_size(entry.size)
+        );
+        match ask(&question).as_str() {
+            "y" | "Y" | "yes" => (),
+            "q" | "Q" => break,
+            _ => continue,
+        }
+        match trashcan::remove(entry, true) {
+            Ok(_) => {
+                let (path, size) = (entry.path.display(), entry.size);
+                tracing::info!(target: "rclin::delete", %path, size, "Deleted");
+                deleted += 1;
+                free("Deleted, disk has");
+            }
+            Err(e) => {
+                tracing::warn!(target: "rclin::delete", path = %entry.path.display(), "{e}");
+                eprintln!("{e}");
+            }
+        }
+    }
+
+    if deleted > 0 {
+        OK
+    } else {
+        NOTHING
+    }
+}
+
+/// Tell on stderr how far a bulk delete that never finished got
+pub fn tell_interrupted(interrupted: &Interrupted) {
+    eprintln!(
+        "Bulk delete started {} didn't finish:",
+        report::format_time(interrupted.started)
+    );
+    for line in interrupted.lines() {
+        eprintln!("  {line}");
+    }
+}
+
+// Check that the projects of deleted Rust targets still build. Returns how
+// many don't.
+fn verify(args: &Args, deleted: &[Entry]) -> usize {
+    let mut failed = 0;
+    for entry in deleted.iter().filter(|e| e.kind == Kind::Rust) {
+        println!("Checking {}", entry.project.display());
+        if let Err(source) = cargo::check(&entry.project, &args.verify_command) {
+            let path = entry.project.clone();
+            eprintln!("{}", Error::Check { path, source });
+            failed += 1;
+        }
+    }
+    failed
+}
+
+/// Scan without the interface, biggest targets first
+pub fn find(args: &Args) -> Option<Vec<Entry>> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let options = args.scan_options(home.as_deref());
+
+    let mut entries = vec![];
+    if let Err(e) = scan::scan(&args.paths, &options, &mut entries) {
+        eprintln!("Scanning failed: {e}");
+        return None;
+    }
+
+    if let Some(age) = args.older_than {
+        entries.retain(|e| e.is_older(age));
+    }
+    if let Some(min) = args.min_size {
+        entries.retain(|e| e.size >= min);
+    }
+
+    if args.open_projects != OpenProjects::Off {
+        let open = editors::open_folders();
+        for entry in &mut entries {
+            entry.open = editors::is_open(&entry.project, &open);
+        }
+    }
+
+    // Threads find things in any order
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+    Some(entries)
+}
+
+// Targets to clean for all of them to fit in `budget`, telling which and why
+fn within_budget(args: &Args, entries: Vec<Entry>, budget: u64) -> Vec<Entry> {
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    if total <= budget {
+        println!(
+            "Targets take {}, within the budget of {}",
+            format_size(total),
+            format_size(budget)
+        );
+        return vec![];
+    }
+
+    println!(
+        "Targets take {}, {} over the budget of {}. Least recently built first:",
+        format_size(total),
+        format_size(total - budget),
+        format_size(budget)
+    );
+    let chosen: Vec<PathBuf> = scan::over_budget(&entries, budget, |e| {
+        guard::refuse(e, &args.paths).is_none()
+    })
+    .into_iter()
+    .map(|e| {
+        let built = match e.age().map(|a| a.as_secs() / (24 * 60 * 60)) {
+            None => "at an unknown time".to_string(),
+            Some(0) => "today".to_string(),
+            Some(days) => format!("{days} days ago"),
+        };
+        println!(
+            "  {}  {:>10}  last built {built}",
+            e.path.display(),
+            format_size(e.size)
+        );
+        e.path.clone()
+    })
+    .collect();
+
+    let chosen_size: u64 = entries
+        .iter()
+        .filter(|e| chosen.contains(&e.path))
+        .map(|e| e.size)
+        .sum();
+    if total - chosen_size > budget {
+        println!(
+            "Cleaning all of them still leaves {}, the rest can't be deleted",
+            format_size(total - chosen_size)
+        );
+    }
+    entries
+        .into_iter()
+        .filter(|e| chosen.contains(&e.path))
+        .collect()
+}
+
+// Tell what cleaning would delete and how much it would free, touching
+// nothing
+fn dry_run(args: &Args, entries: &[Entry]) -> i32 {
+    let verb = if cleans(args) {
+        "Would clean"
+    } else if args.permanent {
+        "Would delete"
+    } else {
+        "Would trash"
+    };
+
+    let mut count = 0;
+    let mut total = 0;
+    for entry in entries {
+        match guard::refuse(entry, &args.paths) {
+            Some(reason) => println!("Would refuse {}: {reason}", entry.path.display()),
+            None => {
+                println!(
+                    "{verb} {} ({})",
+                    entry.path.display(),
+                    format_size(entry.size)
+                );
+                count += 1;
+                total += entry.size;
+            }
+        }
+    }
+    println!(
+        "{verb} {count} target folders, freeing {} ({total} bytes)",
+        format_size(total)
+    );
+    if count > 0 {
+        OK
+    } else {
+        NOTHING
+    }
+}
+
+// Returns what was done, for printing
+// What `rclin remove` prints before the path, for the other end of --ssh
+fn said(removal: Removal) -> &'static str {
+    match removal {
+        Removal::Trashed => "Trashed",
+        Removal::Recycled => "Moved to the recycle bin",
+        Removal::Quarantined => "Moved to the quarantine",
+        Removal::Deleted => "Deleted",
+        Removal::Cleaned => "Cleaned",
+    }
+}
+
+// Whether Rust targets are cleaned instead of removed
+fn cleans(args: &Args) -> bool {
+    args.cleanup() != Cleanup::Remove
+}
+
+// What is about to be done, for asking
+fn verb(args: &Args) -> &'static str {
+    if cleans(args) {
+        "Clean"
+    } else if args.permanent {
+        "Permanently delete"
+    } else {
+        "Trash"
+    }
+}
+
+fn done(args: &Args) -> &'static str {
+    if cleans(args) {
+        "Cleaned"
+    } else if args.permanent {
+        "Deleted"
+    } else {
+        "Trashed"
+    }
+}
+
+// Ask a yes or no question on the terminal, no is the default
+fn confirm(question: &str) -> bool {
+    matches!(
+        ask(&format!("{question} [y/N]")).as_str(),
+        "y" | "Y" | "yes"
+    )
+}
+
+// Answer to a question on the terminal, `q` without a terminal to answer
+// from, e.g. in cron
+fn ask(question: &str) -> String {
+    print!("{question} ");
+    let _ = std::io::stdout().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).unwrap_or(0) == 0 {
+        println!();
+        return "q".to_string();
+    }
+    answer.trim().to_string()
+}
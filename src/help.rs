@@ -0,0 +1,250 @@
+use crate::args::{Type, OPTIONS};
+
+/// Commands given before the paths, with what follows them and what they do
+pub const COMMANDS: &[(&str, &str, &str)] = &[
+    (
+        "scan",
+        "[path...]",
+        "print the targets found without the interface, like --list",
+    ),
+    (
+        "clean",
+        "[path...]",
+        "clean up the targets found without the interface, like --clean",
+    ),
+    (
+        "auto",
+        "[path...]",
+        "clean by the auto rules of the config without asking",
+    ),
+    (
+        "diff",
+        "<old.json> [path...]",
+        "compare a scan with an earlier --output json",
+    ),
+    (
+        "plan",
+        "<plan.json> [path...]",
+        "write what --clean would clean to a file to review",
+    ),
+    (
+        "apply",
+        "<plan.json>",
+        "clean what a plan lists, if it's still the same",
+    ),
+    ("explain", "<path>", "tell why a folder is or isn't listed"),
+    (
+        "duplicates",
+        "[path...]",
+        "tell what dependencies built the same in several Rust targets take",
+    ),
+    (
+        "watch",
+        "[path...]",
+        "scan again and again, showing how fast each target grows",
+    ),
+    (
+        "purge-quarantine",
+        "<age>",
+        "delete what has been in the quarantine this long",
+    ),
+    (
+        "config",
+        "<init|path|edit|check>",
+        "write, find, edit or check the config file",
+    ),
+    (
+        "completions",
+        "<bash|zsh|fish>",
+        "print a shell completion script",
+    ),
+    (
+        "remove",
+        "<project> <target>",
+        "clean up a target of a project if a scan of it still finds it, run over SSH by --ssh",
+    ),
+    ("self-update", "", "update to the latest release"),
+];
+
+// Flags that aren't options of the config file
+const FLAGS: &[(&str, &str)] = &[
+    ("--bfs", "scan breadth first"),
+    ("--dfs", "scan depth first, the default"),
+    ("-h, --help", "print this help"),
+    ("-V, --version", "print the version"),
+];
+
+/// `rclin --version`
+pub fn version() -> String {
+    format!("rclin {}", env!("CARGO_PKG_VERSION"))
+}
+
+/// `rclin --help`: commands and every option, from the same table the config
+/// file and environment use
+pub fn help() -> String {
+    let mut out = format!(
+        "{}\nFind build artifacts of projects and clean them up\n\n\
+         Usage: rclin [command] [path...] [options]\n\n\
+         Without a command the interface opens, scanning the paths or the current folder.\n\nCommands:\n",
+        version()
+    );
+    let commands: Vec<(String, &str)> = COMMANDS
+        .iter()
+        .map(|(name, rest, help)| (format!("{name} {rest}").trim_end().to_string(), *help))
+        .collect();
+    let options: Vec<(String, &str)> = OPTIONS
+        .iter()
+        .map(|opt| (format!("--{}{}", opt.name, placeholder(opt.ty)), opt.help))
+        .chain(FLAGS.iter().map(|(flag, help)| (flag.to_string(), *help)))
+        .collect();
+    let width = commands
+        .iter()
+        .chain(&options)
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+
+    for (name, help) in &commands {
+        out.push_str(&format!("  {name:<width$}  {help}\n"));
+    }
+    out.push_str("\nOptions:\n");
+    for (name, help) in &options {
+        out.push_str(&format!("  {name:<width$}  {help}\n"));
+    }
+    out.push_str(
+        "\nBoolean options are turned off with --no-<option>. Every option can also be set \
+         in the config file as option = value or in the environment as RCLIN_OPTION.\n",
+    );
+    out
+}
+
+// What follows an option on the command line
+fn placeholder(ty: Type) -> &'static str {
+    match ty {
+        Type::Bool => "",
+        Type::Number => " <N>",
+        Type::String => " <value>",
+        Type::List => " <a,b,...>",
+    }
+}
+
+/// `rclin completions <shell>`: script completing commands and options, or
+/// None for a shell there's no script for
+pub fn completions(shell: &str) -> Option<String> {
+    match shell {
+        "bash" => Some(bash()),
+        "zsh" => Some(zsh()),
+        "fish" => Some(fish()),
+        _ => None,
+    }
+}
+
+// Every flag the command line takes, `--no-` ones included
+fn flags() -> Vec<String> {
+    let mut flags = vec![];
+    for opt in OPTIONS {
+        flags.push(format!("--{}", opt.name));
+        if opt.ty == Type::Bool {
+            flags.push(format!("--no-{}", opt.name));
+        }
+    }
+    flags.extend(["--bfs", "--dfs", "-v", "-vv", "--help", "--version"].map(String::from));
+    flags
+}
+
+fn bash() -> String {
+    let commands: Vec<&str> = COMMANDS.iter().map(|(name, ..)| *name).collect();
+    format!(
+        r#"_rclin() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}}
+    if [[ $cur == -* ]]; then
+        COMPREPLY=($(compgen -W "{}" -- "$cur"))
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "{}" -- "$cur"))
+    fi
+}}
+complete -o default -F _rclin rclin
+"#,
+        flags().join(" "),
+        commands.join(" ")
+    )
+}
+
+fn zsh() -> String {
+    let mut out = String::from("#compdef rclin\n\n_arguments \\\n");
+    for opt in OPTIONS {
+        let help = zsh_quote(opt.help);
+        match opt.ty {
+            Type::Bool => {
+                out.push_str(&format!("  '--{}[{help}]' \\\n", opt.name));
+                out.push_str(&format!("  '--no-{}[{help}, off]' \\\n", opt.name));
+            }
+            _ => out.push_str(&format!("  '--{}[{help}]:value:' \\\n", opt.name)),
+        }
+    }
+    for (flag, help) in FLAGS {
+        for flag in flag.split(", ") {
+            out.push_str(&format!("  '{flag}[{}]' \\\n", zsh_quote(help)));
+        }
+    }
+    let commands: Vec<&str> = COMMANDS.iter().map(|(name, ..)| *name).collect();
+    out.push_str(&format!(
+        "  '1: :_alternative \"commands:command:({})\" \"paths:path:_files\"' \\\n  '*:path:_files'\n",
+        commands.join(" ")
+    ));
+    out
+}
+
+// Text safe inside a single quoted `_arguments` spec
+fn zsh_quote(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn fish() -> String {
+    let mut out = String::new();
+    for (name, _, help) in COMMANDS {
+        out.push_str(&format!(
+            "complete -c rclin -n __fish_use_subcommand -a {name} -d '{}'\n",
+            fish_quote(help)
+        ));
+    }
+    for opt in OPTIONS {
+        let value = if opt.ty == Type::Bool { "" } else { " -r" };
+        let help = fish_quote(opt.help);
+        out.push_str(&format!(
+            "complete -c rclin -l {}{value} -d '{help}'\n",
+            opt.name
+        ));
+        if opt.ty == Type::Bool {
+            out.push_str(&format!(
+                "complete -c rclin -l no-{} -d '{help}, off'\n",
+                opt.name
+            ));
+        }
+    }
+    out
+}
+
+fn fish_quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_option() {
+        let help = help();
+        assert!(OPTIONS
+            .iter()
+            .all(|opt| help.contains(&format!("  --{} ", opt.name))));
+        for shell in ["bash", "zsh", "fish"] {
+            let script = completions(shell).unwrap();
+            assert!(script.contains("--no-gitignore") || script.contains("-l no-gitignore"));
+        }
+        assert!(completions("tcsh").is_none());
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    id,
+    scan::{Entry, Kind},
+    size::{self, Metric},
+};
+
+// Totals kept per key, older ones are forgotten
+const TOTALS: usize = 1000;
+
+/// File scans are remembered in
+pub fn path() -> Option<PathBuf> {
+    let dir = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?)
+    } else if let Some(dir) = std::env::var_os("XDG_DATA_HOME") {
+        PathBuf::from(dir)
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share")
+    };
+
+    Some(dir.join("rclin").join("history"))
+}
+
+/// What a scan looked for. Only scans of the same folders for the same
+/// project types are compared, folders are told apart by their IDs. Scans
+/// of apparent sizes are kept apart from the ones of disk usage.
+pub fn key(roots: &[PathBuf], kinds: &[Kind]) -> String {
+    let roots: Vec<String> = roots.iter().map(|r| id::of(r)).collect();
+    let kinds: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+    let key = format!("{} {}", kinds.join(","), roots.join(","));
+    match size::metric() {
+        Metric::Disk => key,
+        Metric::Apparent => format!("{key} apparent"),
+    }
+}
+
+/// Targets found by the last scan of each key, the total size of every scan,
+/// targets flagged for review later and notes on targets
+#[derive(Debug, Default)]
+pub struct History {
+    scans: Vec<Scan>,
+    totals: Vec<Total>,
+    later: Vec<Later>,
+    notes: Vec<Note>,
+}
+
+#[derive(Debug)]
+struct Scan {
+    key: String,
+    time: SystemTime,
+    targets: Vec<Target>,
+}
+
+#[derive(Debug)]
+struct Target {
+    size: u64,
+    id: String,
+    path: String,
+}
+
+// Path is only there for reading the file
+#[derive(Debug)]
+struct Later {
+    id: String,
+    path: String,
+}
+
+#[derive(Debug)]
+struct Note {
+    id: String,
+    text: String,
+}
+
+#[derive(Debug)]
+struct Total {
+    key: String,
+    time: SystemTime,
+    size: u64,
+}
+
+/// Difference between a remembered scan and the current one
+#[derive(Debug, Clone, Copy)]
+pub struct Changes {
+    // Targets that weren't there
+    pub new: usize,
+    // Growth of total size in bytes, negative if it shrank
+    pub growth: i64,
+    // Time since the remembered scan
+    pub since: Duration,
+}
+
+impl History {
+    // Lines are `scan <seconds since epoch> <key>` followed by the targets of
+    // that scan as `<size> <id> <path>`, and `total <seconds since epoch> <size> <key>`
+    // for every scan, `later <id> <path>` for every target flagged for review
+    // later and `note <id> <text>` for every note, separated by tabs
+    pub fn load(path: &Path) -> History {
+        let mut history = History::default();
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        let time =
+            |secs: &str| SystemTime::UNIX_EPOCH + Duration::from_secs(secs.parse().unwrap_or(0));
+
+        for line in content.lines() {
+            let mut fields = line.splitn(4, '\t');
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some("scan"), Some(secs), Some(key), None) => history.scans.push(Scan {
+                    key: key.to_string(),
+                    time: time(secs),
+                    targets: vec![],
+                }),
+                (Some("total"), Some(secs), Some(size), Some(key)) => {
+                    if let Ok(size) = size.parse() {
+                        history.totals.push(Total {
+                            key: key.to_string(),
+                            time: time(secs),
+                            size,
+                        });
+                    }
+                }
+                (Some("later"), Some(id), Some(path), None) => history.later.push(Later {
+                    id: id.to_string(),
+                    path: path.to_string(),
+                }),
+                (Some("note"), Some(id), Some(text), None) => history.notes.push(Note {
+                    id: id.to_string(),
+                    text: text.to_string(),
+                }),
+                (Some(size), Some(id), Some(path), None) => {
+                    if let (Some(scan), Ok(size)) = (history.scans.last_mut(), size.parse()) {
+                        scan.targets.push(Target {
+                            size,
+                            id: id.to_string(),
+                            path: path.to_string(),
+                        });
+                    }
+                }
+                // Written before targets had IDs
+                (Some(size), Some(path), None, None) => {
+                    if let (Some(scan), Ok(size)) = (history.scans.last_mut(), size.parse()) {
+                        scan.targets.push(Target {
+                            size,
+                            id: id::of(Path::new(path)),
+                            path: path.to_string(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        history
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = String::new();
+        for scan in &self.scans {
+            let _ = writeln!(out, "scan\t{}\t{}", secs(scan.time), scan.key);
+            for target in &scan.targets {
+                let _ = writeln!(out, "{}\t{}\t{}", target.size, target.id, target.path);
+            }
+        }
+        for later in &self.later {
+            let _ = writeln!(out, "later\t{}\t{}", later.id, later.path);
+        }
+        for note in &self.notes {
+            let _ = writeln!(out, "note\t{}\t{}", note.id, note.text);
+        }
+        for total in &self.totals {
+            let _ = writeln!(
+                out,
+                "total\t{}\t{}\t{}",
+                secs(total.time),
+                total.size,
+                total.key
+            );
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, out)
+    }
+
+    /// Changes since the last scan of `key`, if there was one
+    pub fn changes(&self, key: &str, entries: &[Entry]) -> Option<Changes> {
+        let last = self.scans.iter().find(|s| s.key == key)?;
+        // By ID, so the same folder reached through another path isn't new
+        let before: HashSet<&str> = last.targets.iter().map(|t| t.id.as_str()).collect();
+        let now: u64 = entries.iter().map(|e| e.size).sum();
+        let then: u64 = last.targets.iter().map(|t| t.size).sum();
+
+        Some(Changes {
+            new: entries
+                .iter()
+                .filter(|e| !before.contains(e.id.as_str()))
+                .count(),
+            growth: now as i64 - then as i64,
+            since: last.time.elapsed().unwrap_or_default(),
+        })
+    }
+
+    /// Total size of every remembered scan of `key`, oldest first
+    pub fn totals(&self, key: &str) -> Vec<(SystemTime, u64)> {
+        self.totals
+            .iter()
+            .filter(|t| t.key == key)
+            .map(|t| (t.time, t.size))
+            .collect()
+    }
+
+    /// IDs of targets flagged for review later
+    pub fn later(&self) -> HashSet<String> {
+        self.later.iter().map(|l| l.id.clone()).collect()
+    }
+
+    /// Flag `entry` for review later, or unflag it
+    pub fn set_later(&mut self, entry: &Entry, later: bool) {
+        self.later.retain(|l| l.id != entry.id);
+        if let Some(path) = entry.path.to_str().filter(|_| later) {
+            if !path.contains(['\t', '\n']) {
+                self.later.push(Later {
+                    id: entry.id.clone(),
+                    path: path.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Notes on targets by their IDs
+    pub fn notes(&self) -> HashMap<String, String> {
+        self.notes
+            .iter()
+            .map(|n| (n.id.clone(), n.text.clone()))
+            .collect()
+    }
+
+    /// Attach `text` to `entry`, replacing the note it had. Empty text
+    /// removes the note.
+    pub fn set_note(&mut self, entry: &Entry, text: &str) {
+        self.notes.retain(|n| n.id != entry.id);
+        if !text.is_empty() {
+            self.notes.push(Note {
+                id: entry.id.clone(),
+                // Would end the line or the field
+                text: text.replace(['\t', '\n'], " "),
+            });
+        }
+    }
+
+    /// Remember `entries` as the last scan of `key`
+    pub fn record(&mut self, key: &str, entries: &[Entry]) {
+        let now = SystemTime::now();
+        self.totals.push(Total {
+            key: key.to_string(),
+            time: now,
+            size: entries.iter().map(|e| e.size).sum(),
+        });
+        let count = self.totals.iter().filter(|t| t.key == key).count();
+        let mut forget = count.saturating_sub(TOTALS);
+        self.totals.retain(|t| {
+            let old = forget > 0 && t.key == key;
+            forget -= usize::from(old);
+            !old
+        });
+
+        self.scans.retain(|s| s.key != key);
+        self.scans.push(Scan {
+            key: key.to_string(),
+            time: now,
+            targets: entries
+                .iter()
+                // Can't be told apart from the separators, or written as text
+                .filter_map(|e| Some((e, e.path.to_str()?)))
+                .filter(|(_, path)| !path.contains(['\t', '\n']))
+                .map(|(e, path)| Target {
+                    size: e.size,
+                    id: e.id.clone(),
+                    path: path.to_string(),
+                })
+                .collect(),
+        });
+    }
+}
+
+fn secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{size::Stats, testing::Fixture};
+
+    fn entry(path: &str, size: u64) -> Entry {
+        let stats = Stats {
+            size,
+            modified: None,
+            files: 1,
+            shared: 0,
+            skipped: vec![],
+        };
+        let project = Path::new(path).parent().unwrap().to_path_buf();
+        Entry::with_stats(path.into(), project, Kind::Rust, stats)
+    }
+
+    #[test]
+    fn loads_what_was_saved() {
+        let fx = Fixture::new("history");
+        let path = fx.path("history");
+        let (a, b) = (entry("/p/a/target", 10), entry("/p/b/target", 20));
+        let mut history = History::default();
+        history.record("k", &[a.clone(), entry("/p/t\tab/target", 5)]);
+        history.set_later(&a, true);
+        history.set_note(&a, "keep\tfor now");
+        history.save(&path).unwrap();
+
+        let history = History::load(&path);
+        let changes = history.changes("k", &[a.clone(), b]).unwrap();
+        assert_eq!((changes.new, changes.growth), (1, 20));
+        assert_eq!(
+            history.totals("k").iter().map(|t| t.1).collect::<Vec<_>>(),
+            [15]
+        );
+        assert!(history.changes("other", &[]).is_none());
+        assert_eq!(history.later(), HashSet::from([a.id.clone()]));
+        assert_eq!(history.notes()[&a.id], "keep for now");
+    }
+}
@@ -0,0 +1,528 @@
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
+
+/// Language of the interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Fi,
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(from_locale()),
+            "en" => Ok(Lang::En),
+            "fi" => Ok(Lang::Fi),
+            _ => Err(format!("unknown language '{s}', expected auto, en or fi")),
+        }
+    }
+}
+
+/// Language from the locale environment, English if there's no translation
+pub fn from_locale() -> Lang {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    if locale.starts_with("fi") {
+        Lang::Fi
+    } else {
+        Lang::En
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Pick the language, once before drawing anything
+pub fn set(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// Translate `text`. English text is the key, text without a translation is
+/// shown as is.
+pub fn t(text: &'static str) -> &'static str {
+    let table = match LANG.get() {
+        Some(Lang::Fi) => FI,
+        Some(Lang::En) | None => return text,
+    };
+
+    table
+        .iter()
+        .find(|(en, _)| *en == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// Translate `template` and fill its `{}` placeholders with `args` in order
+pub fn tf(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = t(template).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+// Placeholders must stay in the same order as in English
+const FI: &[(&str, &str)] = &[
+    // List
+    ("Actions", "Toiminnot"),
+    ("Restored {}", "Palautettiin {}"),
+    ("Restoring {} failed: {}", "Kohteen {} palautus epäonnistui: {}"),
+    ("it is not in the trash anymore", "se ei ole enää roskakorissa"),
+    ("it is not in the quarantine anymore", "se ei ole enää karanteenissa"),
+    ("Details", "Tiedot"),
+    ("Select previous", "Valitse edellinen"),
+    ("Select next", "Valitse seuraava"),
+    ("Select a screenful up", "Valitse näytöllinen ylempää"),
+    ("Select a screenful down", "Valitse näytöllinen alempaa"),
+    ("Select first", "Valitse ensimmäinen"),
+    ("Select last", "Valitse viimeinen"),
+    ("Mark or unmark selected", "Merkitse valittu tai poista merkintä"),
+    ("Mark or unmark all", "Merkitse kaikki tai poista merkinnät"),
+    ("Trash selected or marked", "Roskakoriin valittu tai merkityt"),
+    ("Delete selected or marked permanently", "Poista valittu tai merkityt pysyvästi"),
+    ("Pin or unpin selected", "Kiinnitä valittu tai irrota se"),
+    ("Flag selected for review later", "Merkitse valittu katsottavaksi myöhemmin"),
+    ("Add or edit the note of selected", "Lisää tai muokkaa valitun muistiinpanoa"),
+    (
+        "Exclude project of selected from scans",
+        "Sulje valitun projekti pois skannauksista",
+    ),
+    ("Review excluded projects", "Katso poissuljettuja projekteja"),
+    ("Sort by size", "Järjestä koon mukaan"),
+    ("Sort by path", "Järjestä polun mukaan"),
+    ("Sort by age", "Järjestä iän mukaan"),
+    ("Show names or paths", "Näytä nimet tai polut"),
+    ("Show or hide details", "Näytä tai piilota tiedot"),
+    ("Empty trashed targets from trash", "Tyhjennä roskakoriin siirretyt"),
+    ("Help", "Ohje"),
+    ("Git branch: {}", "Git-haara: {}"),
+    ("Newest file: {}", "Uusin tiedosto: {}"),
+    ("Open project in the file manager", "Avaa projekti tiedostonhallinnassa"),
+    ("Open project in the editor", "Avaa projekti editorissa"),
+    ("Opening {} failed: {}", "Kohteen {} avaaminen epäonnistui: {}"),
+    ("Editor {} failed: {}", "Editori {} epäonnistui: {}"),
+    ("Editor {} quit with {}", "Editori {} lopetti: {}"),
+    ("Keys", "Näppäimet"),
+    ("Mark row on screen", "Merkitse rivi näytöltä"),
+    ("Scanning", "Skannataan"),
+    ("Deleting", "Poisto"),
+    ("to trash", "roskakoriin"),
+    ("permanently", "pysyvästi"),
+    ("with cargo clean {}", "cargo cleanilla {}"),
+    ("lightly cleaning Rust targets", "siivoten Rust-kansiot kevyesti"),
+    (
+        "only cleaning {} of Rust targets",
+        "siivoten Rust-kansioista vain {}",
+    ),
+    ("Windows drives to the recycle bin", "Windows-asemilta roskakoriin"),
+    ("Filter", "Suodatin"),
+    ("none", "ei"),
+    ("Older than", "Vanhempi kuin"),
+    ("Smaller than", "Pienempi kuin"),
+    ("{} (hidden)", "{} (piilotettu)"),
+    ("{} (shown)", "{} (näytetään)"),
+    ("Show or hide small targets", "Näytä tai piilota pienet kohteet"),
+    ("Hiding targets under {}", "Piilotetaan alle {} kohteet"),
+    (", {} under {} hidden", ", {} alle {} piilotettu"),
+    ("Showing targets under {} too", "Näytetään myös alle {} kohteet"),
+    (
+        "Give a size with --min-size to hide small targets",
+        "Anna koko valinnalla --min-size pienten kohteiden piilottamiseksi",
+    ),
+    ("{} days", "{} päivää"),
+    ("Sorted by", "Järjestys"),
+    ("Open projects", "Avoimet projektit"),
+    ("off", "ei käytössä"),
+    ("flagged", "merkitään"),
+    ("skipped by bulk actions", "jätetään pois joukkotoiminnoista"),
+    ("Active projects", "Aktiiviset projektit"),
+    (
+        "changed or committed within {} days, skipped by bulk actions",
+        "muutoksia tai committeja {} päivän sisällä, jätetään pois joukkotoiminnoista",
+    ),
+    ("Check builds with", "Käännöksen tarkistus"),
+    ("Quit", "Lopeta"),
+    ("Trash all", "Roskakoriin kaikki"),
+    ("Mark older than", "Merkitse vanhemmat"),
+    ("Group by filesystem", "Ryhmittele tiedostojärjestelmittäin"),
+    ("Look inside", "Katso sisään"),
+    ("Install binaries", "Asenna ohjelmat"),
+    ("Cargo clean", "Cargo clean"),
+    ("Light clean", "Kevyt siivous"),
+    ("Pick profiles to clean", "Valitse siivottavat profiilit"),
+    (
+        "Dependencies built in several targets",
+        "Useaan kansioon käännetyt riippuvuudet",
+    ),
+    ("Undo trash", "Palauta roskakorista"),
+    ("Trend", "Kehitys"),
+    ("Group under shared folders", "Ryhmittele yhteisten kansioiden alle"),
+    ("Scan again", "Skannaa uudelleen"),
+    ("Already scanning", "Skannaus on jo käynnissä"),
+    ("Cancelling the scan, Esc again quits", "Skannaus perutaan, Esc uudelleen lopettaa"),
+    (
+        "Scan cancelled after {} folders, {} targets found",
+        "Skannaus peruttiin {} kansion jälkeen, {} kohdetta löytyi",
+    ),
+    (
+        "Deletes are still going, scan again once they finish",
+        "Poistot ovat vielä kesken, skannaa uudelleen kun ne valmistuvat",
+    ),
+    ("Pinned {}", "Kiinnitetty {}"),
+    (
+        "{} doesn't build after cleaning: {}",
+        "{} ei käänny siivouksen jälkeen: {}",
+    ),
+    ("{} still builds", "{} kääntyy yhä"),
+    ("  |  Checking {} projects", "  |  Tarkistetaan {} projektia"),
+    ("Unpinned {}", "Irrotettu {}"),
+    (
+        "Last bulk delete didn't finish: {} done, {} interrupted, {} not started, those are marked when found",
+        "Edellinen poisto jäi kesken: {} valmiina, {} keskeytyi, {} aloittamatta, ne merkitään kun löytyvät",
+    ),
+    ("Flagged {} for review later", "{} merkitty katsottavaksi myöhemmin"),
+    ("{} no longer flagged for review later", "{} ei ole enää merkitty katsottavaksi myöhemmin"),
+    ("  (review later)", "  (katso myöhemmin)"),
+    ("  (note)", "  (muistiinpano)"),
+    ("Excluded, left in place: {}", "Rajattu pois, jätetään paikalleen: {}"),
+    ("Note: {}", "Muistiinpano: {}"),
+    ("Note: {}_  Save (Enter) Cancel (Esc)", "Muistiinpano: {}_  Tallenna (Enter) Peru (Esc)"),
+    ("Saved note on {}", "Muistiinpano tallennettu: {}"),
+    ("Removed note from {}", "Muistiinpano poistettu: {}"),
+    (
+        "Notes are kept in scan history, which is off",
+        "Muistiinpanot tallennetaan skannaushistoriaan, joka ei ole käytössä",
+    ),
+    (
+        "Review later flags are kept in scan history, which is off",
+        "Myöhemmin katsottavat merkitään skannaushistoriaan, joka ei ole käytössä",
+    ),
+    (
+        "Select (Up/Down) Page (PageUp/PageDown) First (Home) Last (End)  Mark (Space) Mark row (1-9) Mark all (v) Trash all (a) Trash selected/marked (Del) Delete permanently (D) Mark older than (O) Pin (P) Review later (r) Note (N) Exclude project (x) Excluded (X) Filter (/) Hide small (h) Sort by size (s) path (p) age (m) Group by filesystem (g) Names (n) Look inside (Enter) File manager (o) Editor (e) Details (Tab) Install binaries (i) Cargo clean (c) Light clean (l) Profiles (C) Shared dependencies (A) Undo trash (u) Trend (t) Tree (T) Rescan (R) Help (?) Quit (Esc)",
+        "Valitse (Ylös/Alas) Sivu (PageUp/PageDown) Ensimmäinen (Home) Viimeinen (End)  Merkitse (Space) Merkitse rivi (1-9) Merkitse kaikki (v) Roskakoriin kaikki (a) Roskakoriin valittu/merkityt (Del) Poista pysyvästi (D) Merkitse vanhemmat (O) Kiinnitä (P) Katso myöhemmin (r) Muistiinpano (N) Sulje projekti pois (x) Poissuljetut (X) Suodata (/) Piilota pienet (h) Järjestä koon (s) polun (p) iän (m) mukaan Ryhmittele tiedostojärjestelmittäin (g) Nimet (n) Katso sisään (Enter) Tiedostonhallinta (o) Editori (e) Tiedot (Tab) Asenna ohjelmat (i) Cargo clean (c) Kevyt siivous (l) Profiilit (C) Yhteiset riippuvuudet (A) Palauta roskakorista (u) Kehitys (t) Puu (T) Skannaa uudelleen (R) Ohje (?) Lopeta (Esc)",
+    ),
+    (
+        "Select (Up/Down)  Open folder (Enter) Back (Backspace)",
+        "Valitse (Ylös/Alas)  Avaa kansio (Enter) Takaisin (Backspace)",
+    ),
+    ("Mark entries older than (days): {}_", "Merkitse vanhemmat kuin (päivää): {}_"),
+    (
+        "Permanently delete {} trashed folders ({}, {}) from trash?",
+        "Poistetaanko {} kansiota ({}, {}) roskakorista pysyvästi?",
+    ),
+    (
+        "{} folders hold {} files, trashing takes {}. Deleting them without trash is faster.",
+        "{} kansiossa on {} tiedostoa, roskakoriin siirto kestää {}. Pysyvä poisto ohi roskakorin on nopeampi.",
+    ),
+    (
+        "Permanently delete {} folders ({}) without trash? This can't be undone.",
+        "Poistetaanko {} kansiota ({}) pysyvästi ohi roskakorin? Tätä ei voi perua.",
+    ),
+    ("Trash {} folders ({})?", "Siirretäänkö {} kansiota ({}) roskakoriin?"),
+    ("Trash {} ({})?", "Siirretäänkö {} ({}) roskakoriin?"),
+    ("Confirm", "Vahvista"),
+    ("Inside:", "Sisällä:"),
+    ("\n- and {} more", "\n- ja {} muuta"),
+    ("Biggest files:", "Suurimmat tiedostot:"),
+    ("Yes (y) No (n)", "Kyllä (y) Ei (n)"),
+    (
+        "Trash (y) Delete permanently without trash (p) Cancel (n)",
+        "Roskakoriin (y) Poista pysyvästi (p) Peru (n)",
+    ),
+    ("{} target folders, {}", "{} target-kansiota, {}"),
+    ("Scanning {} found {} in {} folders ({}s)", "Haetaan {} löytyi {} {} kansiosta ({}s)"),
+    ("Scanning {} {} found {} ({}s)", "Haetaan {} {} löytyi {} ({}s)"),
+    (" on {}", " koneella {}"),
+    ("Found {} ({}s)", "Löytyi {} ({}s)"),
+    (", {} folders unreadable", ", {} kansiota ei voitu lukea"),
+    (", scan cancelled", ", skannaus peruttu"),
+    ("  (workspace {})", "  (työtila {})"),
+    (
+        "  (in workspace {}, not used by cargo)",
+        "  (työtilassa {}, cargo ei käytä tätä)",
+    ),
+    ("  (open in editor)", "  (auki editorissa)"),
+    ("  (active in git)", "  (aktiivinen gitissä)"),
+    ("  (shared target-dir)", "  (jaettu target-dir)"),
+    ("  (stale, cargo builds elsewhere)", "  (vanha, cargo kääntää muualle)"),
+    ("  deleting... {}", "  poistetaan... {}"),
+    ("  cleaning... {}", "  siivotaan... {}"),
+    ("  (waiting to be deleted)", "  (odottaa poistoa)"),
+    ("  (from last run)", "  (edelliseltä kerralta)"),
+    ("Saving scan cache failed: {}", "Skannauksen välimuistin tallentaminen epäonnistui: {}"),
+    (
+        "{} targets are from the last run, they can be deleted once the scan finds them again",
+        "{} kohdetta on edelliseltä kerralta, ne voi poistaa kun skannaus löytää ne uudelleen",
+    ),
+    (
+        "Waiting for {} deletes to finish before quitting",
+        "Odotetaan {} poiston valmistumista ennen lopetusta",
+    ),
+    ("  (frees only {})", "  (vapauttaa vain {})"),
+    (
+        ", filter \"{}\" lists {}, bulk actions only use those",
+        ", suodatin \"{}\" näyttää {}, joukkotoiminnot koskevat vain niitä",
+    ),
+    ("  |  Filter: {}", "  |  Suodatin: {}"),
+    ("  |  Sorted by {}", "  |  Järjestys: {}"),
+    ("as found", "löytöjärjestys"),
+    ("size, biggest first", "koko, suurimmat ensin"),
+    ("size, smallest first", "koko, pienimmät ensin"),
+    ("path, A to Z", "polku, A–Ö"),
+    ("path, Z to A", "polku, Ö–A"),
+    ("age, oldest first", "ikä, vanhimmat ensin"),
+    ("age, newest first", "ikä, uusimmat ensin"),
+    (
+        "Type to filter by path, crate or type, letters in order match too  Keep filter (Enter) Cancel (Esc)",
+        "Kirjoita suodattaaksesi polun, craten tai tyypin mukaan, myös järjestyksessä olevat kirjaimet käyvät  Pidä suodatin (Enter) Peru (Esc)",
+    ),
+    ("Filter", "Suodatin"),
+    ("today", "tänään"),
+    ("  (on {})", "  ({})"),
+    (
+        "{} free ({}%), {} with them deleted",
+        "{} vapaana ({}%), {} kun ne on poistettu",
+    ),
+    ("free space unknown", "vapaa tila tuntematon"),
+    ("  |  {}: {} in targets, {}", "  |  {}: {} kohteissa, {}"),
+    (
+        "  |  Free: {}, {} with all listed deleted",
+        "  |  Vapaana: {}, {} kun kaikki listatut on poistettu",
+    ),
+    (
+        "  |  Free on {} filesystems: {}, {} with all listed deleted",
+        "  |  Vapaana {} tiedostojärjestelmässä: {}, {} kun kaikki listatut on poistettu",
+    ),
+    (
+        ", {} new targets, {}{} since {}",
+        ", {} uutta kohdetta, {}{} edellisestä skannauksesta ({})",
+    ),
+    ("earlier today", "aiemmin tänään"),
+    ("Back (any key)", "Takaisin (mikä tahansa näppäin)"),
+    ("Total size of targets over time", "Kohteiden koko ajan mittaan"),
+    (
+        "No scans of these folders remembered yet",
+        "Näiden kansioiden skannauksia ei ole vielä muistissa",
+    ),
+    (
+        "{} scans since {}, now {}, smallest {}, biggest {}",
+        "{} skannausta {} alkaen, nyt {}, pienin {}, suurin {}",
+    ),
+    ("yesterday", "eilen"),
+    ("{} days ago", "{} päivää sitten"),
+    ("Excluded projects ({})", "Poissuljetut projektit ({})"),
+    (
+        "Excluded projects are kept next to scan history, which needs a home folder",
+        "Poissuljetut projektit tallennetaan skannaushistorian viereen, mikä vaatii kotikansion",
+    ),
+    (
+        "Excluded {} from scans, review excluded projects with X",
+        "{} suljettu pois skannauksista, katso poissuljetut projektit X:llä",
+    ),
+    (
+        "Nothing excluded, x on a target excludes its project from scans",
+        "Ei poissuljettuja, x kohteen päällä sulkee sen projektin pois skannauksista",
+    ),
+    ("Saving excluded projects failed: {}", "Poissuljettujen projektien tallennus epäonnistui: {}"),
+    (
+        "Select (Up/Down)  Include again (Del) Back (Esc)",
+        "Valitse (Ylös/Alas)  Ota takaisin (Del) Takaisin (Esc)",
+    ),
+    ("Tree of {} target folders", "{} kohdekansion puu"),
+    ("  ({} targets)", "  ({} kohdetta)"),
+    (
+        "Select (Up/Down)  Collapse/expand (Enter) Trash all in selected (Del) Back (Esc)",
+        "Valitse (Ylös/Alas)  Supista/laajenna (Enter) Roskakoriin kaikki valitussa (Del) Takaisin (Esc)",
+    ),
+    (
+        "{} is scanned again, rescan with R to list it",
+        "{} skannataan taas, skannaa uudelleen R:llä listataksesi sen",
+    ),
+    ("Saving scan history failed: {}", "Skannaushistorian tallennus epäonnistui: {}"),
+    ("{}d ago", "{} pv"),
+    ("  |  Marked: {} ({})", "  |  Merkitty: {} ({})"),
+    ("  |  In trash: {}, empty with (E)", "  |  Roskakorissa: {}, tyhjennä (E)"),
+    ("Total on disk: {}", "Levyllä yhteensä: {}"),
+    ("Total apparent size: {}", "Näennäinen koko yhteensä: {}"),
+    // Details
+    ("Project: {}", "Projekti: {}"),
+    ("Files: {}, deleting takes {}", "Tiedostoja: {}, poistaminen kestää {}"),
+    ("Hard linked elsewhere: {}", "Kovalinkitetty muualle: {}"),
+    ("Reflinked or in snapshots: {}", "Jaettu reflinkeillä tai tilannevedoksissa: {}"),
+    ("Deleting frees about {}", "Poistaminen vapauttaa noin {}"),
+    ("Workspace members ({})", "Työtilan jäsenet ({})"),
+    ("Not a workspace", "Ei työtila"),
+    // Messages
+    ("Scanning failed: {}", "Haku epäonnistui: {}"),
+    ("Scanning failed", "Haku epäonnistui"),
+    ("Trashing {} failed: {}", "Kansion {} siirto roskakoriin epäonnistui: {}"),
+    (
+        "Moving {} to the recycle bin failed: {}",
+        "Kansion {} siirto Windowsin roskakoriin epäonnistui: {}",
+    ),
+    ("Refusing to delete {}: {}", "Ei poisteta {}: {}"),
+    ("Cannot read {}: {}", "Kansiota {} ei voi lukea: {}"),
+    ("it is not in the list", "se ei ole listalla"),
+    (
+        "it has been replaced by a symbolic link",
+        "se on korvattu symbolisella linkillä",
+    ),
+    ("it is not a folder anymore", "se ei ole enää kansio"),
+    ("it cannot be found anymore", "sitä ei enää löydy"),
+    (
+        "its project has no manifest anymore",
+        "sen projektista puuttuu nyt manifesti",
+    ),
+    (
+        "it is no longer an artifact folder of its project",
+        "se ei ole enää projektinsa käännöskansio",
+    ),
+    (
+        "it is or contains the current folder",
+        "se on nykyinen kansio tai sisältää sen",
+    ),
+    (
+        "it is or contains a scanned folder",
+        "se on skannattu kansio tai sisältää sellaisen",
+    ),
+    (
+        "it is outside its project and not tagged as a cache by cargo",
+        "se on projektinsa ulkopuolella eikä cargo ole merkinnyt sitä välimuistiksi",
+    ),
+    ("Deleting {} failed: {}", "Kansion {} poistaminen epäonnistui: {}"),
+    ("Trashed {}/{}", "Roskakorissa {}/{}"),
+    ("Deleted {}/{}", "Poistettu {}/{}"),
+    ("Trashed {} folders", "Siirrettiin {} kansiota roskakoriin"),
+    ("Deleted {} folders", "Poistettiin {} kansiota"),
+    ("Trashed {}", "Siirrettiin {} roskakoriin"),
+    (", freed {} this session", ", vapautettu {} tämän istunnon aikana"),
+    ("Moved {} to the recycle bin", "Siirrettiin {} Windowsin roskakoriin"),
+    ("Moved {} to the quarantine", "Siirrettiin {} karanteeniin"),
+    (
+        "Moving {} to the quarantine failed: {}",
+        "Kansion {} siirto karanteeniin epäonnistui: {}",
+    ),
+    ("{} over SSH failed: {}", "{} SSH:n yli epäonnistui: {}"),
+    (
+        "Not possible with targets over SSH",
+        "Ei onnistu SSH:n yli löytyneille kansioille",
+    ),
+    (
+        "Without PowerShell, trashing on Windows drives under WSL deletes permanently",
+        "Ilman PowerShelliä roskakoriin siirto poistaa pysyvästi WSL:n Windows-asemilla",
+    ),
+    (
+        "Network shares have no recycle bin, trashing there deletes permanently",
+        "Verkkojaoilla ei ole roskakoria, roskakoriin siirto poistaa niillä pysyvästi",
+    ),
+    ("Deleted {}", "Poistettiin {}"),
+    ("Cleaned {}", "Siivottiin {}"),
+    ("Cleaned {}/{}", "Siivottu {}/{}"),
+    ("Cleaned {} folders", "Siivottiin {} kansiota"),
+    ("Only Rust targets can be cleaned with cargo", "Vain Rust-kansiot voi siivota cargolla"),
+    ("Only Rust targets can be cleaned lightly", "Vain Rust-kansiot voi siivota kevyesti"),
+    (
+        "Remove incremental caches, docs and old artifacts of {} targets ({}), keeping binaries?",
+        "Poistetaanko {} kansion ({}) inkrementaaliset välimuistit, dokumentaatio ja vanhat käännökset, ohjelmat säilyttäen?",
+    ),
+    (
+        " Rust targets are only cleaned lightly, keeping binaries.",
+        " Rust-kansiot siivotaan vain kevyesti, ohjelmat säilyttäen.",
+    ),
+    (
+        " Only {} of Rust targets are cleaned.",
+        " Rust-kansioista siivotaan vain {}.",
+    ),
+    (
+        "Only Rust targets have profiles to clean",
+        "Vain Rust-kansioissa on siivottavia profiileja",
+    ),
+    (
+        "{} has no profile folders to clean",
+        "Kansiossa {} ei ole siivottavia profiileja",
+    ),
+    ("Profiles to clean in {}", "Siivottavat profiilit kansiossa {}"),
+    (
+        "Select (Up/Down)  Clean or keep (Space) Clean the picked (Enter) Back (Esc)",
+        "Valitse (Ylös/Alas)  Siivoa tai säilytä (Space) Siivoa valitut (Enter) Takaisin (Esc)",
+    ),
+    (
+        "Comparing the dependencies of the Rust targets...",
+        "Verrataan Rust-kansioiden riippuvuuksia...",
+    ),
+    (
+        "No dependency is built the same in several of {} Rust targets",
+        "Mitään riippuvuutta ei ole käännetty samoin useaan {} Rust-kansiosta",
+    ),
+    (
+        "{} dependencies are built the same in several of {} Rust targets. One target folder shared by these projects would save {} of the {} their deps folders take: set CARGO_TARGET_DIR, or build.target-dir in ~/.cargo/config.toml.",
+        "{} riippuvuutta on käännetty samoin useaan {} Rust-kansiosta. Projektien yhteinen target-kansio säästäisi {} niiden deps-kansioiden viemästä {}:sta: aseta CARGO_TARGET_DIR tai build.target-dir tiedostossa ~/.cargo/config.toml.",
+    ),
+    ("{}  {} in {} targets, {} each", "{}  {} {} kansiossa, {} kussakin"),
+    ("Select (Up/Down)  Back (Esc)", "Valitse (Ylös/Alas)  Takaisin (Esc)"),
+    (
+        "Nothing picked to clean, Space picks a profile",
+        "Mitään ei ole valittu siivottavaksi, Space valitsee profiilin",
+    ),
+    (
+        "Run cargo clean {}in the projects of {} targets ({})?",
+        "Ajetaanko cargo clean {}{} kohteen projekteissa ({})?",
+    ),
+    ("Cargo clean in {} failed: {}", "Cargo clean kansiossa {} epäonnistui: {}"),
+    (
+        " Rust targets are cleaned with cargo clean {}instead.",
+        " Rust-kansiot siivotaan sen sijaan: cargo clean {}",
+    ),
+    (", freed {}", ", vapautettu {}"),
+    (
+        "Trashed {} folders but free space did not grow, press E to empty them from trash",
+        "{} kansiota siirrettiin roskakoriin mutta vapaa tila ei kasvanut, tyhjennä ne roskakorista painamalla E",
+    ),
+    ("Installed {}", "Asennettu {}"),
+    ("Failed to salvage binaries from {}: {}", "Ohjelmien pelastus kansiosta {} epäonnistui: {}"),
+    ("Marked {} entries older than {} days", "Merkitty {} yli {} päivää vanhaa kansiota"),
+    (
+        "Targets take {}, within the budget of {}",
+        "Kansiot vievät {}, mikä mahtuu {} rajaan",
+    ),
+    (
+        "Targets take {}, over the budget of {}. Marked the {} least recently built ({})",
+        "Kansiot vievät {}, yli {} rajan. Merkitty {} pisimpään kääntämättä ollutta ({})",
+    ),
+    ("Emptied {} folders ({}) from trash", "Tyhjennetty {} kansiota ({}) roskakorista"),
+    ("Emptying trash failed: {}", "Roskakorin tyhjennys epäonnistui: {}"),
+    ("under a minute", "alle minuutin"),
+    ("about {} min", "noin {} min"),
+    ("about {} h {} min", "noin {} h {} min"),
+    // Session summary
+    ("No target folders found!", "Target-kansioita ei löytynyt!"),
+    ("Found {} target folders", "Löytyi {} target-kansiota"),
+    ("Deleted {} ({})", "Poistettu {} ({})"),
+    ("Still in trash {} ({})", "Vielä roskakorissa {} ({})"),
+    ("Freed {}", "Vapautettu {}"),
+    ("Failed {}:", "Epäonnistui {}:"),
+    ("Unreadable {}:", "Ei voitu lukea {}:"),
+    ("Cannot scan {}: {}", "Ei voitu skannata {}: {}"),
+    ("  (failed: {})", "  (epäonnistui: {})"),
+    ("Scanning...", "Skannataan..."),
+    (
+        "{} target folders taking {}, scanned {} times every {}s, last {}s ago",
+        "{} target-kansiota vie {}, skannattu {} kertaa {} s välein, viimeksi {} s sitten",
+    ),
+    (
+        "Size, growth since first found and growth a minute  Quit (q)",
+        "Koko, kasvu löytymisestä ja kasvu minuutissa  Lopeta (q)",
+    ),
+    ("gone", "poissa"),
+    ("{}/min", "{}/min"),
+];
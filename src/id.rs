@@ -0,0 +1,34 @@
+use std::path::{Path, PathBuf};
+
+/// Stable ID of the folder at `path`, the same however the path is written.
+/// The path is canonicalized, and inside the home folder only the part below
+/// it counts, so IDs also match between machines with different homes.
+pub fn of(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| trimmed(path));
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .and_then(|home| home.canonicalize().ok());
+
+    let name = match home
+        .as_deref()
+        .and_then(|home| path.strip_prefix(home).ok())
+    {
+        Some(relative) => Path::new("~").join(relative),
+        None => path,
+    };
+    format!("{:016x}", fnv1a(name.to_string_lossy().as_bytes()))
+}
+
+// Without trailing separators and `.` components, for paths that don't exist
+// anymore
+fn trimmed(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+// Same result on every platform and Rust version, unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
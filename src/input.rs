@@ -0,0 +1,117 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Condvar, Mutex,
+    },
+    time::Duration,
+};
+
+use crossterm::event::{self, Event};
+
+/// What the interface wakes up to
+pub enum Message {
+    // A key, mouse or resize event from the terminal
+    Event(Event),
+    // Nothing came for a tick, time to look at the background work
+    Tick,
+}
+
+/// Reads the terminal on a thread of its own, sending each event and a tick
+/// whenever nothing came for a while
+pub struct Input {
+    messages: Receiver<Message>,
+    pause: Arc<Pause>,
+}
+
+// Asked to stop reading, and whether the thread has
+#[derive(Default)]
+struct Pause {
+    state: Mutex<(bool, bool)>,
+    changed: Condvar,
+}
+
+impl Input {
+    /// Start reading, with a tick every `tick` nothing comes
+    pub fn spawn(tick: Duration) -> Input {
+        let (sender, messages) = mpsc::channel();
+        let pause = Arc::new(Pause::default());
+        let shared = pause.clone();
+        std::thread::spawn(move || loop {
+            shared.wait();
+            let message = match event::poll(tick) {
+                Ok(true) => match event::read() {
+                    Ok(event) => Message::Event(event),
+                    Err(_) => Message::Tick,
+                },
+                Ok(false) => Message::Tick,
+                // Only the terminal fails, it's tried again the next tick
+                Err(_) => {
+                    std::thread::sleep(tick);
+                    Message::Tick
+                }
+            };
+            if sender.send(message).is_err() {
+                return;
+            }
+        });
+        Input { messages, pause }
+    }
+
+    /// The next event, or a tick if none came in time
+    pub fn next(&self) -> Message {
+        self.messages
+            .recv()
+            .expect("the input thread reads until the input is dropped")
+    }
+
+    /// Stop reading until [`Input::resume`], returning once the thread has,
+    /// so that the terminal can be handed over to something else
+    pub fn pause(&self) {
+        let mut state = self.pause.state.lock().unwrap();
+        state.0 = true;
+        while !state.1 {
+            state = self.pause.changed.wait(state).unwrap();
+        }
+    }
+
+    /// Read again after [`Input::pause`]. What was read before pausing is
+    /// dropped, it was meant for whatever had the terminal.
+    pub fn resume(&self) {
+        while self.messages.try_recv().is_ok() {}
+        let mut state = self.pause.state.lock().unwrap();
+        *state = (false, false);
+        self.pause.changed.notify_all();
+    }
+}
+
+impl Pause {
+    // Block while paused, telling it to whoever waits for that
+    fn wait(&self) {
+        let mut state = self.state.lock().unwrap();
+        while state.0 {
+            state.1 = true;
+            self.changed.notify_all();
+            state = self.changed.wait(state).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn ticks_until_paused_and_again_once_resumed() {
+        let input = Input::spawn(Duration::from_millis(100));
+        assert!(matches!(input.next(), Message::Tick));
+        input.pause();
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(50));
+        input.resume();
+        // Ticks from before pausing are gone
+        assert!(input.messages.try_recv().is_err());
+        assert!(matches!(input.next(), Message::Tick));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
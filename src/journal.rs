@@ -0,0 +1,223 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::history;
+
+/// File a bulk delete keeps its progress in while it runs, next to the
+/// history
+pub fn path() -> Option<PathBuf> {
+    Some(history::path()?.with_file_name("journal"))
+}
+
+/// Progress of a bulk delete, written down as it goes so it's known after a
+/// crash how far it got. Deleting goes on without it when it can't be
+/// written, or without a place for it.
+pub struct Journal {
+    file: Option<(PathBuf, File)>,
+}
+
+impl Journal {
+    // Lines are `start <seconds since epoch>`, then `todo <path>` for every
+    // folder to delete, and `begin <path>` and `done <path>` or
+    // `failed <path>` as each is deleted, separated by tabs
+    pub fn start(path: Option<&Path>, folders: &[PathBuf]) -> Journal {
+        let file = path.and_then(|path| {
+            std::fs::create_dir_all(path.parent()?).ok()?;
+            Some((path.to_path_buf(), File::create(path).ok()?))
+        });
+        let mut journal = Journal { file };
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut lines = format!("start\t{now}\n").into_bytes();
+        for folder in folders {
+            lines.extend(line("todo", folder));
+        }
+        journal.write(&lines);
+        journal
+    }
+
+    /// Another folder to delete, added while it runs
+    pub fn add(&mut self, folder: &Path) {
+        self.write(&line("todo", folder));
+    }
+
+    pub fn begin(&mut self, folder: &Path) {
+        self.write(&line("begin", folder));
+    }
+
+    pub fn done(&mut self, folder: &Path) {
+        self.write(&line("done", folder));
+    }
+
+    pub fn failed(&mut self, folder: &Path) {
+        self.write(&line("failed", folder));
+    }
+
+    /// The whole bulk delete went through, nothing to tell next time
+    pub fn finish(self) {
+        if let Some((path, _)) = self.file {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    // On disk before going on, or it wouldn't survive the machine dying
+    fn write(&mut self, lines: &[u8]) {
+        if let Some((_, file)) = &mut self.file {
+            let _ = file.write_all(lines).and_then(|()| file.sync_data());
+        }
+    }
+}
+
+// `what` about `folder` as a line. On unix the path is written as it is, so
+// names that aren't UTF-8 come back the same.
+fn line(what: &str, folder: &Path) -> Vec<u8> {
+    let mut line = format!("{what}\t").into_bytes();
+    #[cfg(unix)]
+    line.extend(std::os::unix::ffi::OsStrExt::as_bytes(folder.as_os_str()));
+    #[cfg(not(unix))]
+    line.extend(folder.to_string_lossy().as_bytes());
+    line.push(b'\n');
+    line
+}
+
+fn folder(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    return PathBuf::from(<std::ffi::OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(bytes));
+    #[cfg(not(unix))]
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Bulk delete that never finished
+#[derive(Debug)]
+pub struct Interrupted {
+    pub started: SystemTime,
+    pub done: Vec<PathBuf>,
+    pub failed: Vec<PathBuf>,
+    // Being deleted when it stopped, may be partly gone
+    pub in_flight: Vec<PathBuf>,
+    // Never started
+    pub remaining: Vec<PathBuf>,
+}
+
+/// The bulk delete the journal at `path` tells about, if one was interrupted.
+/// It's told once, the journal is removed.
+pub fn interrupted(path: &Path) -> Option<Interrupted> {
+    let content = std::fs::read(path).ok()?;
+    let _ = std::fs::remove_file(path);
+
+    let mut interrupted = Interrupted {
+        started: SystemTime::UNIX_EPOCH,
+        done: vec![],
+        failed: vec![],
+        in_flight: vec![],
+        remaining: vec![],
+    };
+    let mut todo = vec![];
+    let mut begun = vec![];
+    // Only whole lines, the last one may be cut short by the crash
+    for line in content.split_inclusive(|b| *b == b'\n') {
+        let Some(line) = line.strip_suffix(b"\n") else {
+            continue;
+        };
+        let Some(tab) = line.iter().position(|b| *b == b'\t') else {
+            continue;
+        };
+        let (what, path) = (&line[..tab], folder(&line[tab + 1..]));
+        match what {
+            b"start" => {
+                let secs = String::from_utf8_lossy(&line[tab + 1..])
+                    .parse()
+                    .unwrap_or(0);
+                interrupted.started = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            }
+            b"todo" => todo.push(path),
+            b"begin" => begun.push(path),
+            b"done" => interrupted.done.push(path),
+            b"failed" => interrupted.failed.push(path),
+            _ => (),
+        }
+    }
+
+    let finished =
+        |folder: &PathBuf| interrupted.done.contains(folder) || interrupted.failed.contains(folder);
+    let in_flight: Vec<PathBuf> = begun.into_iter().filter(|f| !finished(f)).collect();
+    let remaining: Vec<PathBuf> = todo
+        .into_iter()
+        .filter(|f| !finished(f) && !in_flight.contains(f))
+        .collect();
+    interrupted.in_flight = in_flight;
+    interrupted.remaining = remaining;
+    Some(interrupted)
+}
+
+impl Interrupted {
+    /// Every folder and how far it got, for printing
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        for folder in &self.done {
+            lines.push(format!("done         {}", folder.display()));
+        }
+        for folder in &self.failed {
+            lines.push(format!("failed       {}", folder.display()));
+        }
+        for folder in &self.in_flight {
+            let state = if folder.exists() {
+                "partly gone"
+            } else {
+                "gone"
+            };
+            lines.push(format!("interrupted  {} ({state})", folder.display()));
+        }
+        for folder in &self.remaining {
+            lines.push(format!("not started  {}", folder.display()));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn tells_how_far_it_got() {
+        let fx = Fixture::new("journal");
+        let path = fx.path("journal");
+        let folders = ["/p/a", "/p/b", "/p/c"].map(PathBuf::from);
+        let mut journal = Journal::start(Some(&path), &folders);
+        journal.begin(Path::new("/p/a"));
+        journal.done(Path::new("/p/a"));
+        journal.begin(Path::new("/p/b"));
+        journal.add(Path::new("/p/d"));
+        // Dropped without finishing, like a crash
+
+        drop(journal);
+        let interrupted = interrupted(&path).unwrap();
+        assert_eq!(interrupted.done, [Path::new("/p/a")]);
+        assert!(interrupted.failed.is_empty());
+        assert_eq!(interrupted.in_flight, [Path::new("/p/b")]);
+        assert_eq!(
+            interrupted.remaining,
+            [Path::new("/p/c"), Path::new("/p/d")]
+        );
+        // Told once
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn finished_leaves_nothing() {
+        let fx = Fixture::new("finished");
+        let path = fx.path("journal");
+        let mut journal = Journal::start(Some(&path), &[PathBuf::from("/p/a")]);
+        journal.begin(Path::new("/p/a"));
+        journal.failed(Path::new("/p/a"));
+        journal.finish();
+        assert!(interrupted(&path).is_none());
+    }
+}
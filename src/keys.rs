@@ -0,0 +1,287 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+/// Actions of the list that can be bound to other keys, with their default
+/// keys and what they do. Handlers match the first key.
+pub const ACTIONS: &[(&str, &[&str], &str)] = &[
+    ("up", &["Up", "k"], "Select previous"),
+    ("down", &["Down", "j"], "Select next"),
+    ("page-up", &["PageUp"], "Select a screenful up"),
+    ("page-down", &["PageDown"], "Select a screenful down"),
+    ("first", &["Home"], "Select first"),
+    ("last", &["End", "G"], "Select last"),
+    ("mark", &["Space"], "Mark or unmark selected"),
+    ("mark-all", &["v"], "Mark or unmark all"),
+    ("trash-all", &["a"], "Trash all"),
+    ("trash", &["Del", "dd"], "Trash selected or marked"),
+    (
+        "delete-permanently",
+        &["D"],
+        "Delete selected or marked permanently",
+    ),
+    ("mark-older", &["O"], "Mark older than"),
+    ("pin", &["P"], "Pin or unpin selected"),
+    ("review-later", &["r"], "Flag selected for review later"),
+    ("note", &["N"], "Add or edit the note of selected"),
+    ("exclude", &["x"], "Exclude project of selected from scans"),
+    ("excluded", &["X"], "Review excluded projects"),
+    ("filter", &["/"], "Filter"),
+    ("hide-small", &["h"], "Show or hide small targets"),
+    ("sort-size", &["s"], "Sort by size"),
+    ("sort-path", &["p"], "Sort by path"),
+    ("sort-age", &["m"], "Sort by age"),
+    ("group-filesystems", &["g"], "Group by filesystem"),
+    ("names", &["n"], "Show names or paths"),
+    ("look-inside", &["Enter"], "Look inside"),
+    ("open-folder", &["o"], "Open project in the file manager"),
+    ("edit", &["e"], "Open project in the editor"),
+    ("details", &["Tab"], "Show or hide details"),
+    ("install", &["i"], "Install binaries"),
+    ("cargo-clean", &["c"], "Cargo clean"),
+    ("light-clean", &["l"], "Light clean"),
+    ("profiles", &["C"], "Pick profiles to clean"),
+    (
+        "duplicates",
+        &["A"],
+        "Dependencies built in several targets",
+    ),
+    ("empty-trash", &["E"], "Empty trashed targets from trash"),
+    ("undo", &["u"], "Undo trash"),
+    ("trend", &["t"], "Trend"),
+    ("tree", &["T"], "Group under shared folders"),
+    ("rescan", &["R", "F5"], "Scan again"),
+    ("help", &["?"], "Help"),
+    ("quit", &["Esc"], "Quit"),
+];
+
+// Longest time between the presses of a key pressed twice, like `dd`
+const TWICE: Duration = Duration::from_secs(1);
+
+/// Keys of list actions, the defaults unless bound to others
+#[derive(Debug, Clone)]
+pub struct Keys {
+    bindings: Vec<Binding>,
+    // Key pressed once of the keys bound as pressed twice, and when
+    pending: Option<(KeyCode, Instant)>,
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    action: &'static str,
+    // First default key of the action
+    default: Key,
+    key: Key,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    // Pressed twice in a row, like `dd`
+    twice: bool,
+    // As written in the config and help text
+    name: String,
+}
+
+impl Default for Keys {
+    fn default() -> Keys {
+        let mut bindings = vec![];
+        for (action, keys, _) in ACTIONS {
+            let default = Key::parse(keys[0]).expect("default keys parse");
+            for key in *keys {
+                bindings.push(Binding {
+                    action,
+                    default: default.clone(),
+                    key: Key::parse(key).expect("default keys parse"),
+                });
+            }
+        }
+        Keys {
+            bindings,
+            pending: None,
+        }
+    }
+}
+
+impl FromStr for Keys {
+    type Err = String;
+
+    // Comma separated `action=key` pairs, e.g. `trash-all=x,quit=q`. An
+    // action given several times gets all those keys.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Keys::default();
+        let mut rebound: Vec<&str> = vec![];
+
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (action, key) = pair
+                .split_once('=')
+                .ok_or(format!("expected action=key, found '{pair}'"))?;
+            let (action, defaults, _) = ACTIONS
+                .iter()
+                .find(|(name, ..)| *name == action.trim())
+                .ok_or(format!("unknown action '{}'", action.trim()))?;
+
+            // Given keys replace the defaults
+            if !rebound.contains(action) {
+                rebound.push(action);
+                keys.bindings.retain(|b| b.action != *action);
+            }
+            let key = Key::parse(key.trim())?;
+            keys.bindings.push(Binding {
+                action,
+                default: Key::parse(defaults[0])?,
+                key,
+            });
+        }
+
+        for (i, binding) in keys.bindings.iter().enumerate() {
+            let key = &binding.key;
+            if let Some(other) = keys.bindings[..i].iter().find(|b| b.key == *key) {
+                return Err(format!(
+                    "key '{}' is bound to both {} and {}",
+                    key.name, other.action, binding.action
+                ));
+            }
+            // Pressing it once would do something already
+            let once = keys.bindings.iter().find(|b| {
+                key.twice
+                    && !b.key.twice
+                    && b.key.code == key.code
+                    && b.key.modifiers == key.modifiers
+            });
+            if let Some(once) = once {
+                return Err(format!(
+                    "key '{}' can't be used when '{}' is bound to {}",
+                    key.name, once.key.name, once.action
+                ));
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+impl Keys {
+    /// Turn a pressed key into the first default key of the action bound to
+    /// it, so handlers only need to know the defaults. Default keys of
+    /// rebound actions do nothing, like the first press of a key bound as
+    /// pressed twice.
+    pub fn remap(&mut self, event: &Event) -> Option<Event> {
+        let Event::Key(pressed) = event else {
+            return Some(event.clone());
+        };
+        let is = |key: &Key| key.code == pressed.code && key.modifiers == pressed.modifiers;
+        let again = self
+            .pending
+            .take()
+            .is_some_and(|(code, at)| code == pressed.code && at.elapsed() < TWICE);
+
+        let binding = self
+            .bindings
+            .iter()
+            .find(|b| is(&b.key) && b.key.twice == again);
+        if let Some(binding) = binding {
+            let default = &binding.default;
+            return Some(Event::Key(KeyEvent::new(default.code, default.modifiers)));
+        }
+        if self.bindings.iter().any(|b| is(&b.key) && b.key.twice) {
+            self.pending = Some((pressed.code, Instant::now()));
+            return None;
+        }
+        if ACTIONS
+            .iter()
+            .any(|(_, keys, _)| Key::parse(keys[0]).is_ok_and(|k| is(&k)))
+        {
+            return None;
+        }
+        Some(event.clone())
+    }
+
+    /// Names of the keys bound to `action`
+    pub fn bound(&self, action: &str) -> Vec<&str> {
+        self.bindings
+            .iter()
+            .filter(|b| b.action == action)
+            .map(|b| b.key.name.as_str())
+            .collect()
+    }
+
+    /// Help text with bound keys in place of the defaults, which are written
+    /// in parentheses like `Trash all (a)`
+    pub fn label(&self, text: &str) -> String {
+        let mut label = String::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find('(') {
+            let Some(len) = rest[start..].find(')') else {
+                break;
+            };
+            let name = &rest[start + 1..start + len];
+            let binding = self.bindings.iter().find(|b| b.default.name == name);
+
+            label.push_str(&rest[..=start]);
+            label.push_str(binding.map_or(name, |b| &b.key.name));
+            rest = &rest[start + len..];
+        }
+        label.push_str(rest);
+        label
+    }
+}
+
+/// Press of a key named like in the help text, e.g. `a`, `D` or `Del`
+pub fn event(name: &str) -> Option<Event> {
+    let key = Key::parse(name).ok()?;
+    Some(Event::Key(KeyEvent::new(key.code, key.modifiers)))
+}
+
+impl Key {
+    // Key names like `x`, `X` (with shift), `Space`, `Del`, `Enter` or `F5`,
+    // and a character twice like `dd` for pressing it twice
+    fn parse(name: &str) -> Result<Key, String> {
+        let mut chars = name.chars();
+        let (c, twice) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, None) => (Some(c), false),
+            (Some(c), Some(again), None) if c == again => (Some(c), true),
+            _ => (None, false),
+        };
+        let (code, modifiers) = match c {
+            Some(c) if c.is_uppercase() => (KeyCode::Char(c), KeyModifiers::SHIFT),
+            Some(c) => (KeyCode::Char(c), KeyModifiers::NONE),
+            None => (named(name)?, KeyModifiers::NONE),
+        };
+
+        Ok(Key {
+            code,
+            modifiers,
+            twice,
+            name: name.to_string(),
+        })
+    }
+}
+
+fn named(name: &str) -> Result<KeyCode, String> {
+    let code = match name {
+        "Space" => KeyCode::Char(' '),
+        "Del" | "Delete" => KeyCode::Delete,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Insert" => KeyCode::Insert,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        _ => match name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => return Err(format!("unknown key '{name}'")),
+        },
+    };
+    Ok(code)
+}
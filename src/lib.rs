@@ -0,0 +1,57 @@
+//! Finding and cleaning up build artifacts, the part of rclin other tools
+//! can use too. [`scan::scan`] walks folders for projects and their targets,
+//! sized as it goes, and [`deleter::Deleter`] trashes, deletes or cargo
+//! cleans them like the interface does, checked by [`guard`] first. The
+//! interface itself is [`app::run`], drawn by [`ui`], and [`headless`] has
+//! the commands that print instead.
+
+pub mod app;
+pub mod args;
+pub mod auto;
+pub mod browser;
+pub mod cache;
+pub mod caps;
+pub mod cargo;
+pub mod command;
+pub mod config;
+pub mod deleter;
+pub mod details;
+pub mod detectors;
+pub mod diff;
+pub mod disk;
+pub mod duplicates;
+pub mod editors;
+pub mod error;
+pub mod excluded;
+pub mod explain;
+pub mod git;
+pub mod gitignore;
+pub mod guard;
+pub mod headless;
+pub mod help;
+pub mod history;
+pub mod i18n;
+pub mod id;
+pub mod input;
+pub mod journal;
+pub mod keys;
+pub mod output;
+pub mod quarantine;
+pub mod quiet;
+pub mod remote;
+pub mod report;
+pub mod scan;
+pub mod size;
+pub mod state;
+#[cfg(test)]
+mod testing;
+pub mod theme;
+pub mod trace;
+pub mod trashcan;
+pub mod tree;
+pub mod ui;
+#[cfg(feature = "self-update")]
+pub mod update;
+pub mod users;
+pub mod watch;
+pub mod wsl;
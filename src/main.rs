@@ -1,278 +1,211 @@
-mod state;
-
-use state::{Events, State};
-
 use std::{
-    io::{stdout, Error, Stdout},
-    path::{Path, PathBuf},
-    time::Duration,
-    vec,
+    fs::File,
+    io::{stderr, stdin, stdout, IsTerminal},
+    path::PathBuf,
 };
 
-use crossterm::{
-    event::{
-        poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent,
-        KeyEventKind, KeyEventState, KeyModifiers,
-    },
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-
-use tui::{
-    backend::CrosstermBackend,
-    layout::Rect,
-    style::Style,
-    text::Span,
-    widgets::{Block, Borders, List, ListItem, Paragraph},
-    Frame, Terminal,
+use rclin::{
+    app,
+    args::Args,
+    caps::Caps,
+    command::{self, Command, Info},
+    config, diff,
+    error::Error,
+    explain, headless, help, i18n, journal, remote, size,
+    trace::{self, exit},
+    trashcan,
 };
 
 fn main() -> Result<(), Error> {
-    // Read path arg or default to current dir. Panic is ok.
-    let p = std::env::args()
-        .nth(1)
-        .map_or_else(|| std::env::current_dir().unwrap(), PathBuf::from);
-
-    // Create state
-    let mut state = State {
-        results: vec![],
-        time: 0.0,
-    };
-
-    // Scan
-    let start = std::time::Instant::now();
-    println!("Scanning...");
-    if let Err(e) = scan(&p, &mut state.results) {
-        println!("Scanning failed: {e}");
-        std::process::exit(1);
-    }
-    state.time = start.elapsed().as_secs_f32();
-
-    // Quit if not results
-    if state.results.is_empty() {
-        println!("No target folders found!");
-        std::process::exit(0);
-    }
-
-    // Create stateful widget state
-    let mut events = Events::new(state.results.clone());
-    events.next();
-
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    // Draw initial screen
-    terminal.draw(|f| draw(f, &mut state, &mut events))?;
-
-    // Poll for events every 100 millis. If got one, handle it, and draw again
-    loop {
-        if let Ok(true) = poll(Duration::from_millis(100)) {
-            if let Ok(event) = read() {
-                if let Err(e) = handle_event(&event, &mut terminal, &mut state, &mut events) {
-                    println!("Error: {e}");
-                    std::process::exit(2);
-                }
-                // Update on event
-                terminal.draw(|f| draw(f, &mut state, &mut events))?;
-            }
+    let cli: Vec<String> = std::env::args().skip(1).collect();
+    // Before anything else can complain about the rest
+    match command::info(&cli) {
+        Some(Info::Help) => {
+            print!("{}", help::help());
+            std::process::exit(0);
+        }
+        Some(Info::Version) => {
+            println!("{}", help::version());
+            std::process::exit(0);
         }
+        None => (),
     }
-}
-
-fn scan(path: &Path, results: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
-    // println!("Scanning: {path:?}");
-    match std::fs::read_dir(path) {
-        Ok(dir) => {
-            let mut found_target = false;
-            let mut found_cargo_toml = false;
-
-            // Loop through every file in folder
-            for entry in dir.flatten() {
-                // Skip hidden files
-                if entry.file_name().to_str().unwrap().starts_with('.') {
-                    continue;
-                }
-
-                // Skip symlinks
-                if let Ok(meta) = &entry.metadata() {
-                    if meta.is_symlink() {
-                        continue;
-                    }
-
-                    // Check if folder named target
-                    if entry.file_name() == "target" && meta.is_dir() {
-                        found_target = true;
-                        continue;
-                    }
-
-                    // Check cargo toml
-                    if entry.file_name() == "Cargo.toml" && meta.is_file() {
-                        found_cargo_toml = true;
-                    }
-                }
+    let (command, cli) = command::parse(cli).unwrap_or_else(|e| {
+        println!("Invalid arguments: {e}");
+        std::process::exit(1);
+    });
+    match &command {
+        Command::Completions(shell) => match help::completions(shell) {
+            Some(script) => {
+                print!("{script}");
+                std::process::exit(0);
             }
-
-            if found_target && found_cargo_toml {
-                let p = path.to_path_buf().join("target");
-                results.push(p.to_str().unwrap().to_string());
+            None => {
+                println!("Usage: rclin completions <bash|zsh|fish>");
+                std::process::exit(1);
             }
-
-            // Aight bet, loop again
-            let dir = std::fs::read_dir(path)?;
-
-            for entry in dir.flatten() {
-                if entry.file_type().unwrap().is_dir() {
-                    scan(&entry.path(), results).unwrap();
-                }
+        },
+        Command::Config(command) => std::process::exit(config::run(command.as_deref())),
+        Command::SelfUpdate => {
+            #[cfg(feature = "self-update")]
+            std::process::exit(rclin::update::self_update());
+            #[cfg(not(feature = "self-update"))]
+            {
+                println!("This rclin was built without self-update, update it the way it was installed, e.g. with cargo install rclin");
+                std::process::exit(1);
             }
         }
-        Err(e) => {
-            println!("Cannot scan {path:?}: {}", e.kind());
-        }
+        _ => (),
     }
 
-    Ok(())
-}
-
-fn trash_selected(state: &mut State, events: &mut Events) {
-    if let Some(idx) = events.state.selected() {
-        if let Some(path) = &state.results.get(idx) {
-            if trash::delete(path).is_ok() {
-                state.results.remove(idx);
-                events.items.remove(idx);
-            }
+    // Config file first, then environment, arguments override both
+    let mut args = Args::default();
+    if let Err(errors) = config::load(&mut args) {
+        config::print_errors(&config::path().unwrap_or_default(), &errors);
+        std::process::exit(1);
+    }
+    if let Err(errors) = config::load_env(&mut args) {
+        println!("Invalid environment:");
+        for e in errors {
+            println!("  {e}");
         }
+        std::process::exit(1);
     }
-}
 
-fn trash_all(state: &mut State, events: &mut Events) {
-    for path in &state.results {
-        trash::delete(path).unwrap();
+    // Read path arg or default to current dir
+    if let Err(e) = args.parse(cli) {
+        println!("Invalid arguments: {e}");
+        std::process::exit(1);
     }
-    state.results.clear();
-    events.clear();
-}
 
-fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, state: &mut State, events: &mut Events) {
-    let size = f.size();
-    let block = Block::default()
-        .title(format!(
-            "Found {} target folders ({:.2}s)",
-            state.results.len(),
-            state.time
-        ))
-        .borders(Borders::ALL);
-
-    let items: Vec<ListItem> = events
-        .items
-        .iter()
-        .map(|s| ListItem::new(s.as_ref()))
-        .collect();
-
-    let list = List::new(items)
-        .block(block)
-        .style(Style::default())
-        .highlight_style(Style::default())
-        .highlight_symbol(">>");
+    // Over SSH the host scans and cleans, only the interface runs here
+    if let Some(remote) = &mut args.ssh {
+        let headless = command != Command::Interface
+            || args.list
+            || args.clean
+            || args.output.is_some()
+            || args.all_users
+            || args.emergency
+            || !(stdin().is_terminal() && stdout().is_terminal());
+        if headless {
+            println!(
+                "--ssh only works in the interface, run rclin on {} for the rest",
+                remote.host
+            );
+            std::process::exit(1);
+        }
+        remote.rclin = args.ssh_rclin.clone();
+        args.paths = vec![PathBuf::from(&remote.path)];
+        // Scans there aren't scans of these folders here
+        args.history = false;
+        args.cache = false;
+        remote::set(remote.clone());
+    }
 
-    let actions_block = Block::default().title("Actions").borders(Borders::ALL);
+    match command {
+        Command::Scan => args.list = true,
+        Command::Clean => args.clean = true,
+        _ => (),
+    }
 
-    let actions = Span::raw(
-        "Select (Up/Down)  Trash all (a) Trash selected (Del) Quit (Esc)",
-    );
-    let paragraph = Paragraph::new(actions);
+    if command == Command::Explain {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let options = args.scan_options(home.as_deref());
+        std::process::exit(explain::explain(&args.paths[0], &options));
+    }
 
-    // Rect
-    let list_rect = Rect::new(0, 0, size.width, size.height - 3);
-    let actions_rect = Rect::new(0, list_rect.height, size.width, 3);
-    let paragraph_rect = actions_block.inner(actions_rect);
+    let trace = args.trace_output.as_ref().map(|path| {
+        File::create(path).unwrap_or_else(|e| {
+            println!("Cannot write trace to {path}: {e}");
+            std::process::exit(1);
+        })
+    });
+    let log_file = args.log_file.as_ref().map(|path| {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path);
+        file.unwrap_or_else(|e| {
+            println!("Cannot write log to {path}: {e}");
+            std::process::exit(1);
+        })
+    });
+    trace::start(trace::Logging {
+        trace,
+        verbose: args.verbose,
+        log_file,
+        stderr_free: || !app::has_terminal() || !stderr().is_terminal(),
+    });
+
+    size::set_metric(args.metric);
+    if let Some(dir) = &args.quarantine {
+        trashcan::set_quarantine(dir.clone());
+    }
 
-    f.render_stateful_widget(list, list_rect, &mut events.state);
-    f.render_widget(actions_block, actions_rect);
-    f.render_widget(paragraph, paragraph_rect);
-}
+    if let Command::Diff(old) = &command {
+        std::process::exit(diff::diff(&args, old));
+    }
 
-fn handle_event(
-    event: &Event,
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    state: &mut State,
-    events: &mut Events,
-) -> Result<(), Box<dyn std::error::Error>> {
-    match event {
-        // Select previous
-        Event::Key(KeyEvent {
-            code: KeyCode::Up,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::NONE,
-        }) => {
-            events.previous();
-        }
+    i18n::set(args.language);
 
-        // Select next
-        Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::NONE,
-        }) => {
-            events.next();
-        }
+    // Tell how far a bulk delete that was cut short got, once
+    let journal = args.history.then(journal::path).flatten();
+    let interrupted = journal.as_deref().and_then(journal::interrupted);
+    if let Some(interrupted) = &interrupted {
+        headless::tell_interrupted(interrupted);
+    }
 
-        // Trash one
-        Event::Key(KeyEvent {
-            code: KeyCode::Delete,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::NONE,
-        }) => {
-            trash_selected(state, events);
-            events.next();
+    match command {
+        Command::Auto => exit(headless::auto(&args)),
+        Command::PurgeQuarantine(age) => exit(headless::purge_quarantine(&args, age)),
+        Command::Plan(file) => exit(headless::plan(&args, &file)),
+        Command::Apply(file) => exit(headless::apply(&args, &file)),
+        Command::Remove(project, target) => {
+            // The other end of --ssh, where sshd tells about the connection.
+            // Cleaning by hand is what clean is for.
+            if std::env::var_os("SSH_CONNECTION").is_none() {
+                eprintln!("rclin remove only runs over SSH for --ssh, use rclin clean here");
+                exit(1);
+            }
+            args.paths = vec![PathBuf::from(project)];
+            exit(headless::remove(&args, &target));
         }
+        Command::Duplicates => exit(headless::duplicates(&args)),
+        _ => (),
+    }
+    if args.all_users {
+        exit(headless::users(&args));
+    }
+    if args.emergency {
+        exit(headless::emergency(&args));
+    }
+    // Without a terminal, like in a container or a pipe, there's no
+    // interface to show
+    if !args.clean && args.output.is_none() && !(stdin().is_terminal() && stdout().is_terminal()) {
+        args.list = true;
+    }
+    if args.list {
+        exit(headless::list(&args));
+    }
+    if let Some(output) = args.output {
+        exit(headless::output(&args, output));
+    }
+    if args.clean {
+        exit(headless::clean(&args));
+    }
 
-        // Trash all
-        Event::Key(KeyEvent {
-            code: KeyCode::Char('a'),
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::NONE,
-        }) => {
-            trash_all(state, events);
-            events.next();
-        }
+    let mut caps = Caps::detect();
+    caps.unicode &= !args.ascii;
+    caps.colors &= args.color;
 
-        // Exit
-        Event::Key(
-            KeyEvent {
-                code: KeyCode::Esc,
-                modifiers: KeyModifiers::NONE,
-                kind: KeyEventKind::Press,
-                state: KeyEventState::NONE,
-            }
-            | KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                state: KeyEventState::NONE,
-            },
-        ) => {
-            // restore terminal
-            disable_raw_mode()?;
-            execute!(
-                terminal.backend_mut(),
-                LeaveAlternateScreen,
-                DisableMouseCapture
-            )?;
-            terminal.show_cursor()?;
-            // Quit
-            std::process::exit(0);
+    if command == Command::Watch {
+        if !(stdin().is_terminal() && stdout().is_terminal()) {
+            println!("rclin watch needs a terminal to show what grows");
+            exit(1);
         }
-
-        _ => (),
+        app::watch(&args, caps)?;
+        exit(0);
     }
-    Ok(())
+
+    app::run(&args, caps, journal, interrupted)
 }
@@ -0,0 +1,92 @@
+use std::{fmt::Write as _, str::FromStr};
+
+use crate::{report::format_timestamp, scan::Entry};
+
+/// Version of the JSON objects. Fields may be added without changing it,
+/// renaming, removing or changing the meaning of one bumps it.
+pub const SCHEMA: u64 = 1;
+
+/// Machine readable format for found targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    Json,
+    Csv,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Output::Json),
+            "csv" => Ok(Output::Csv),
+            _ => Err(format!("unknown output format '{s}', expected json or csv")),
+        }
+    }
+}
+
+impl Output {
+    /// `entries` with their ID, path, project, type, size in bytes and last
+    /// modification time
+    pub fn format(self, entries: &[Entry]) -> String {
+        match self {
+            Output::Json => json(entries),
+            Output::Csv => csv(entries),
+        }
+    }
+}
+
+// Array of objects, one line each, each telling its schema version
+fn json(entries: &[Entry]) -> String {
+    let mut out = String::from("[");
+    for (i, e) in entries.iter().enumerate() {
+        let modified = e
+            .modified
+            .map_or("null".to_string(), |m| json_string(&format_timestamp(m)));
+        let _ = write!(
+            out,
+            "{}\n  {{\"schema\": {SCHEMA}, \"id\": {}, \"path\": {}, \"project\": {}, \"type\": {}, \"size\": {}, \"modified\": {}}}",
+            if i == 0 { "" } else { "," },
+            json_string(&e.id),
+            json_string(&e.path.to_string_lossy()),
+            json_string(&e.project.to_string_lossy()),
+            json_string(&e.kind.name().to_lowercase()),
+            e.size,
+            modified,
+        );
+    }
+    out.push_str(if entries.is_empty() { "]\n" } else { "\n]\n" });
+    out
+}
+
+// Quoted and escaped by serde_json, which reads it back too
+fn json_string(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+// With a header row, modification time empty when unknown
+fn csv(entries: &[Entry]) -> String {
+    let mut out = String::from("id,path,project,type,size,modified\n");
+    for e in entries {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            e.id,
+            csv_field(&e.path.to_string_lossy()),
+            csv_field(&e.project.to_string_lossy()),
+            e.kind.name().to_lowercase(),
+            e.size,
+            e.modified.map(format_timestamp).unwrap_or_default(),
+        );
+    }
+    out
+}
+
+// Quote fields that contain separators or quotes
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Each folder moved into the quarantine gets a folder of its own named
+// `<seconds since epoch>-<n>`, holding it under its own name and the path it
+// came from in this file
+const ORIGIN: &str = "origin";
+
+/// Folder moved into the quarantine
+#[derive(Debug, Clone)]
+pub struct Item {
+    // Folder of its own in the quarantine
+    pub dir: PathBuf,
+    // Where it was moved from
+    pub origin: PathBuf,
+    pub time: SystemTime,
+}
+
+impl Item {
+    /// Whether it was moved in at least `age` ago
+    pub fn is_older(&self, age: Duration) -> bool {
+        self.time.elapsed().is_ok_and(|elapsed| elapsed >= age)
+    }
+}
+
+/// Move `path` into the quarantine folder `dir`. It must be on the same
+/// filesystem, nothing is copied.
+pub fn put(dir: &Path, path: &Path) -> io::Result<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "it has no name"))?;
+    std::fs::create_dir_all(dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let item = (0..)
+        .map(|n| dir.join(format!("{secs}-{n}")))
+        .find_map(|item| match std::fs::create_dir(&item) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => None,
+            created => Some(created.map(|()| item)),
+        })
+        .expect("some number is free")?;
+
+    let moved = std::fs::write(item.join(ORIGIN), path.to_string_lossy().as_bytes())
+        .and_then(|()| std::fs::rename(path, item.join(name)));
+    if moved.is_err() {
+        let _ = std::fs::remove_dir_all(&item);
+    }
+    moved
+}
+
+/// Folders in the quarantine `dir`, oldest first. Folders in it rclin didn't
+/// put there are left out.
+pub fn items(dir: &Path) -> Vec<Item> {
+    let mut items: Vec<(u64, Item)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let (secs, n) = name.to_str()?.split_once('-')?;
+            let time = UNIX_EPOCH + Duration::from_secs(secs.parse().ok()?);
+            let origin = std::fs::read_to_string(entry.path().join(ORIGIN)).ok()?;
+            let item = Item {
+                dir: entry.path(),
+                origin: PathBuf::from(origin),
+                time,
+            };
+            Some((n.parse().ok()?, item))
+        })
+        .collect();
+    items.sort_by_key(|(n, item)| (item.time, *n));
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Move `item` back where it came from, unless something is there already
+pub fn restore(item: &Item) -> io::Result<()> {
+    let name = item
+        .origin
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "its origin has no name"))?;
+    if item.origin.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "something took its place",
+        ));
+    }
+    if let Some(parent) = item.origin.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(item.dir.join(name), &item.origin)?;
+    std::fs::remove_dir_all(&item.dir)
+}
+
+/// Delete `item` for good
+pub fn purge(item: &Item) -> io::Result<()> {
+    std::fs::remove_dir_all(&item.dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn moves_in_and_back() {
+        let fx = Fixture::new("quarantine");
+        let target = fx.project("app");
+        fx.file("app/target/debug/app", "binary");
+        let dir = fx.path("quarantine");
+
+        put(&dir, &target).unwrap();
+        assert!(!target.exists());
+        let moved = items(&dir);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].origin, target);
+        assert!(!moved[0].is_older(Duration::from_secs(60)));
+
+        restore(&moved[0]).unwrap();
+        assert!(target.join("debug").join("app").exists());
+        assert!(items(&dir).is_empty());
+
+        put(&dir, &target).unwrap();
+        purge(&items(&dir)[0]).unwrap();
+        assert!(!target.exists() && items(&dir).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+/// Time of day during which unattended cleaning doesn't run, e.g. work hours
+/// when builds are active. May wrap past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    // Minutes since midnight
+    start: u32,
+    end: u32,
+}
+
+impl QuietHours {
+    /// Whether `minute` (since midnight) is within quiet hours
+    pub fn contains(self, minute: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// When quiet hours end, as `HH:MM`
+    pub fn end(self) -> String {
+        format!("{:02}:{:02}", self.end / 60, self.end % 60)
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    // `HH:MM-HH:MM`, e.g. `09:00-17:00` or `22:00-06:00`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("expected quiet hours like 09:00-17:00, found '{s}'");
+        let (start, end) = s.split_once('-').ok_or_else(error)?;
+        let minutes = |time: &str| -> Option<u32> {
+            let (hours, minutes) = time.trim().split_once(':')?;
+            let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+            (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+        };
+
+        Ok(QuietHours {
+            start: minutes(start).ok_or_else(error)?,
+            end: minutes(end).ok_or_else(error)?,
+        })
+    }
+}
+
+/// Minutes since midnight in local time
+#[cfg(unix)]
+pub fn now() -> u32 {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs()) as libc::time_t;
+    let mut local: libc::tm = unsafe { std::mem::zeroed() };
+
+    // SAFETY: both pointers are valid for the duration of the call
+    if unsafe { libc::localtime_r(&time, &mut local) }.is_null() {
+        return utc(time as u64);
+    }
+    (local.tm_hour * 60 + local.tm_min) as u32
+}
+
+// Without a way to read the time zone, quiet hours are in UTC
+#[cfg(not(unix))]
+pub fn now() -> u32 {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    utc(time)
+}
+
+fn utc(seconds: u64) -> u32 {
+    (seconds % 86400 / 60) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_past_midnight() {
+        let night: QuietHours = "22:00-06:30".parse().unwrap();
+        assert!(night.contains(22 * 60));
+        assert!(night.contains(23 * 60 + 59));
+        assert!(night.contains(0));
+        assert!(night.contains(6 * 60 + 29));
+        assert!(!night.contains(6 * 60 + 30));
+        assert!(!night.contains(12 * 60));
+        assert_eq!(night.end(), "06:30");
+
+        let day: QuietHours = "09:00-17:00".parse().unwrap();
+        assert!(day.contains(9 * 60) && !day.contains(17 * 60) && !day.contains(0));
+        assert!("24:00-06:00".parse::<QuietHours>().is_err());
+        assert!("22:00".parse::<QuietHours>().is_err());
+    }
+}
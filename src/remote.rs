@@ -0,0 +1,302 @@
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::{mpsc, Arc, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    deleter::{Cleanup, Job, Removal},
+    error::{self, Error},
+    scan::{Background, Entry, Found, Kind, Progress},
+};
+
+/// Folder on another machine, scanned and cleaned up by the rclin installed
+/// there over SSH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    // Destination for ssh, like `user@host`
+    pub host: String,
+    pub path: String,
+    // Command running rclin on the host
+    pub rclin: String,
+}
+
+impl FromStr for Remote {
+    type Err = String;
+
+    /// `user@host:/path`, the path may also start with `~/`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((host, path)) if !host.is_empty() && !path.is_empty() => Ok(Remote {
+                host: host.to_string(),
+                path: path.to_string(),
+                rclin: "rclin".to_string(),
+            }),
+            _ => Err(format!("expected user@host:/path, got '{s}'")),
+        }
+    }
+}
+
+impl fmt::Display for Remote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.path)
+    }
+}
+
+static REMOTE: OnceLock<Remote> = OnceLock::new();
+
+/// Work on `remote` instead of this machine. Can only be set once.
+pub fn set(remote: Remote) {
+    let _ = REMOTE.set(remote);
+}
+
+/// Where targets are when working over SSH
+pub fn get() -> Option<&'static Remote> {
+    REMOTE.get()
+}
+
+impl Remote {
+    // ssh running rclin with `args` on the host. The remote shell splits
+    // the command line again, so every argument is quoted.
+    fn command(&self, args: &[String]) -> Command {
+        let line: Vec<String> = std::iter::once(self.rclin.clone())
+            .chain(args.iter().map(|arg| quote(arg)))
+            .collect();
+        let mut command = Command::new("ssh");
+        command
+            .args(["-o", "BatchMode=yes", "--", &self.host])
+            .arg(line.join(" "))
+            .stdin(Stdio::null());
+        command
+    }
+}
+
+/// Scan the folder on the host in the background, like [`crate::scan::spawn`]
+/// does here. Only `kinds` are looked for, the rest is up to the config on the
+/// host.
+pub fn spawn(remote: Remote, kinds: &[Kind]) -> Background {
+    let mut args = vec!["--output".to_string(), "csv".to_string()];
+    if !kinds.is_empty() {
+        let names: Vec<String> = kinds.iter().map(|k| k.name().to_lowercase()).collect();
+        args.extend(["--types".to_string(), names.join(",")]);
+    }
+    args.push(remote.path.clone());
+
+    let (sender, receiver) = mpsc::channel();
+    let progress = Arc::new(Progress::default());
+    let shared = Arc::clone(&progress);
+    let handle = std::thread::spawn(move || {
+        let mut child = remote
+            .command(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| failed(Path::new(&remote.path), source))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        for line in BufReader::new(stdout).lines().skip(1) {
+            if shared.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(());
+            }
+            let line = line.map_err(|source| failed(Path::new(&remote.path), source))?;
+            if let Some(entry) = parse_entry(&line) {
+                // Nobody listens anymore when quitting
+                let _ = sender.send(Found::Entry(Box::new(entry)));
+            }
+        }
+        finish(child, Path::new(&remote.path)).map(drop)
+    });
+    (receiver, handle, progress)
+}
+
+/// Clean up the target of `job` on the host, where rclin scans its project
+/// again and checks that it's still safe to
+pub fn remove(remote: &Remote, job: &Job, cargo_clean_args: &[String]) -> error::Result<Removal> {
+    let entry = &job.entry;
+    // The host printed the paths as text, they go back the same way
+    let path = entry.path.to_string_lossy();
+    let mut args = vec![
+        "remove".to_string(),
+        entry.project.to_string_lossy().into_owned(),
+        path.to_string(),
+    ];
+    if job.permanent {
+        args.push("--permanent".to_string());
+    }
+    if job.salvage {
+        args.push("--salvage".to_string());
+    }
+    match &job.cleanup {
+        Cleanup::Remove => (),
+        Cleanup::Cargo => {
+            args.extend([
+                "--cargo-clean".to_string(),
+                "--cargo-clean-args".to_string(),
+            ]);
+            args.push(cargo_clean_args.join(" "));
+        }
+        Cleanup::Light => args.push("--light-clean".to_string()),
+        Cleanup::Profiles(profiles) => {
+            args.extend(["--profiles".to_string(), profiles.join(",")]);
+        }
+    }
+
+    let child = remote
+        .command(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|source| failed(&entry.path, source))?;
+    let output = finish(child, &entry.path)?;
+    // What was done comes before the path, like `Trashed <path>`
+    let done = output
+        .lines()
+        .find(|line| line.ends_with(&*path))
+        .unwrap_or_default();
+    Ok(if done.starts_with("Deleted") {
+        Removal::Deleted
+    } else if done.starts_with("Cleaned") {
+        Removal::Cleaned
+    } else if done.starts_with("Moved to the recycle bin") {
+        Removal::Recycled
+    } else if done.starts_with("Moved to the quarantine") {
+        Removal::Quarantined
+    } else {
+        Removal::Trashed
+    })
+}
+
+// Wait for rclin on the host, returning what it printed or what it said went
+// wrong
+fn finish(child: std::process::Child, path: &Path) -> error::Result<String> {
+    let output = child
+        .wait_with_output()
+        .map_err(|source| failed(path, source))?;
+    let out = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        return Ok(out);
+    }
+    let err = String::from_utf8_lossy(&output.stderr);
+    // The first error is what went wrong, the rest follows from it
+    let message = err
+        .lines()
+        .chain(out.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map_or_else(|| output.status.to_string(), String::from);
+    Err(failed(path, io::Error::other(message)))
+}
+
+fn failed(path: &Path, source: io::Error) -> Error {
+    Error::Remote {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+// Target from a row of `--output csv`: id, path, project, type, size and
+// modification time
+fn parse_entry(line: &str) -> Option<Entry> {
+    let [id, path, project, kind, size, modified] = <[String; 6]>::try_from(fields(line)).ok()?;
+    // Only what the host told, this machine's folders of the same name don't
+    // count
+    Some(Entry {
+        path: PathBuf::from(path),
+        id,
+        project: PathBuf::from(project),
+        kind: kind.parse().ok()?,
+        size: size.parse().ok()?,
+        modified: parse_timestamp(&modified),
+        open: false,
+        shared_dir: false,
+        stale: false,
+        active: false,
+        shared: 0,
+        files: 0,
+        names: vec![],
+        workspace: None,
+        device: None,
+        kept: vec![],
+    })
+}
+
+// Fields of a CSV row, quoted ones unquoted
+fn fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+// Time like `2024-01-31T23:59:59Z`
+fn parse_timestamp(s: &str) -> Option<SystemTime> {
+    let number = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let secs = number(11..13)? * 3600 + number(14..16)? * 60 + number(17..19)?;
+
+    // Civil date to days, from Howard Hinnant's date algorithms
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let since = u64::try_from(days * 86400 + secs).ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(since))
+}
+
+// Argument safe from the remote shell. A leading `~/` is left for it to
+// expand to the home folder.
+fn quote(arg: &str) -> String {
+    let (home, rest) = match arg.strip_prefix("~/") {
+        Some(rest) => ("~/", rest),
+        None => ("", arg),
+    };
+    format!("{home}'{}'", rest.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_what_rclin_prints_on_the_host() {
+        let remote: Remote = "me@build:~/code".parse().unwrap();
+        assert_eq!(
+            (remote.host.as_str(), remote.path.as_str()),
+            ("me@build", "~/code")
+        );
+        assert!("build".parse::<Remote>().is_err());
+        assert_eq!(quote("~/it's"), r"~/'it'\''s'");
+
+        let entry =
+            parse_entry(r#"0123abcd,"/srv/a,b/target",/srv/a,b,rust,4096,2024-02-29T12:30:00Z"#);
+        assert!(entry.is_none(), "a comma in an unquoted field splits it");
+        let entry =
+            parse_entry(r#"0123abcd,"/srv/a,b/target","/srv/a,b",rust,4096,2024-02-29T12:30:00Z"#)
+                .unwrap();
+        assert_eq!(entry.path, Path::new("/srv/a,b/target"));
+        assert_eq!((entry.kind, entry.size), (Kind::Rust, 4096));
+        let secs = entry
+            .modified
+            .unwrap()
+            .duration_since(SystemTime::UNIX_EPOCH);
+        assert_eq!(secs.unwrap().as_secs(), 1709209800);
+    }
+}
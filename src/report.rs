@@ -0,0 +1,244 @@
+use std::{fmt::Write as _, io::Write as _, path::PathBuf, str::FromStr, time::SystemTime};
+
+use crate::{scan::Entry, size::format_size, users::User};
+
+/// Reclaimable space per user, biggest targets first
+pub fn print_users(results: &mut [(User, Vec<Entry>)]) {
+    results.sort_by_key(|(_, entries)| std::cmp::Reverse(total(entries)));
+
+    println!("{:<20} {:>8} {:>10}", "User", "Targets", "Size");
+    for (user, entries) in results.iter() {
+        println!(
+            "{:<20} {:>8} {:>10}",
+            user.name,
+            entries.len(),
+            format_size(total(entries))
+        );
+    }
+
+    let all: u64 = results.iter().map(|(_, entries)| total(entries)).sum();
+    let count: usize = results.iter().map(|(_, entries)| entries.len()).sum();
+    println!("{:<20} {:>8} {:>10}", "Total", count, format_size(all));
+
+    for (user, entries) in results.iter_mut().filter(|(_, e)| !e.is_empty()) {
+        entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+        println!();
+        println!("{} ({})", user.name, user.home.display());
+        for entry in entries.iter() {
+            println!("{:>10}  {}", format_size(entry.size), entry.path.display());
+        }
+    }
+}
+
+/// Report groups of `entries` by the root they were found under
+pub fn group_by_root(roots: &[PathBuf], entries: Vec<Entry>) -> Vec<(String, Vec<Entry>)> {
+    let mut groups: Vec<(String, Vec<Entry>)> = roots
+        .iter()
+        .map(|root| (root.display().to_string(), vec![]))
+        .collect();
+
+    for entry in entries {
+        let root = roots
+            .iter()
+            .position(|root| entry.path.starts_with(root))
+            .unwrap_or(0);
+        groups[root].1.push(entry);
+    }
+    groups
+}
+
+fn total(entries: &[Entry]) -> u64 {
+    entries.iter().map(|e| e.size).sum()
+}
+
+/// Output format of a written report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!(
+                "unknown report format '{s}', expected text or html"
+            )),
+        }
+    }
+}
+
+/// Summary of a run, for mailing from cron jobs and such
+pub struct Report {
+    // Remaining targets, grouped by root or user
+    pub groups: Vec<(String, Vec<Entry>)>,
+    pub deleted: Vec<Entry>,
+}
+
+impl Report {
+    /// Write to file, or stdout when `dest` is `-`
+    pub fn write(&self, format: ReportFormat, dest: &str) -> std::io::Result<()> {
+        let content = match format {
+            ReportFormat::Text => self.text(),
+            ReportFormat::Html => self.html(),
+        }
+        .map_err(std::io::Error::other)?;
+
+        if dest == "-" {
+            std::io::stdout().write_all(content.as_bytes())
+        } else {
+            std::fs::write(dest, content)
+        }
+    }
+
+    fn text(&self) -> Result<String, std::fmt::Error> {
+        let mut out = String::new();
+        let count: usize = self.groups.iter().map(|(_, e)| e.len()).sum();
+        let size: u64 = self.groups.iter().map(|(_, e)| total(e)).sum();
+
+        writeln!(out, "rclin report, {}", format_time(SystemTime::now()))?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "Deleted {} targets, freed {}",
+            self.deleted.len(),
+            format_size(total(&self.deleted))
+        )?;
+        writeln!(out, "Found {count} targets, {} total", format_size(size))?;
+
+        if !self.deleted.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "Deleted:")?;
+            for entry in &self.deleted {
+                writeln!(
+                    out,
+                    "{:>10}  {}",
+                    format_size(entry.size),
+                    entry.path.display()
+                )?;
+            }
+        }
+
+        for (group, entries) in self.groups.iter().filter(|(_, e)| !e.is_empty()) {
+            writeln!(out)?;
+            writeln!(out, "{group}: {}", format_size(total(entries)))?;
+            for entry in sorted(entries) {
+                writeln!(
+                    out,
+                    "{:>10}  {}",
+                    format_size(entry.size),
+                    entry.path.display()
+                )?;
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn html(&self) -> Result<String, std::fmt::Error> {
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(
+            out,
+            "<html><head><meta charset=\"utf-8\"><title>rclin report</title></head><body>"
+        )?;
+        writeln!(
+            out,
+            "<h1>rclin report</h1><p>{}</p>",
+            format_time(SystemTime::now())
+        )?;
+        writeln!(
+            out,
+            "<p>Deleted {} targets, freed {}</p>",
+            self.deleted.len(),
+            format_size(total(&self.deleted))
+        )?;
+
+        let mut table = |title: &str, entries: &[Entry]| -> std::fmt::Result {
+            writeln!(
+                out,
+                "<h2>{} ({})</h2><table><tr><th>Size</th><th>Path</th></tr>",
+                escape(title),
+                format_size(total(entries))
+            )?;
+            for entry in sorted(entries) {
+                writeln!(
+                    out,
+                    "<tr><td align=\"right\">{}</td><td>{}</td></tr>",
+                    format_size(entry.size),
+                    escape(&entry.path.to_string_lossy())
+                )?;
+            }
+            writeln!(out, "</table>")
+        };
+
+        if !self.deleted.is_empty() {
+            table("Deleted", &self.deleted)?;
+        }
+        for (group, entries) in self.groups.iter().filter(|(_, e)| !e.is_empty()) {
+            table(group, entries)?;
+        }
+
+        writeln!(out, "</body></html>")?;
+        Ok(out)
+    }
+}
+
+fn sorted(entries: &[Entry]) -> Vec<&Entry> {
+    let mut sorted: Vec<&Entry> = entries.iter().collect();
+    sorted.sort_by_key(|e| std::cmp::Reverse(e.size));
+    sorted
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Format time as `YYYY-MM-DD HH:MM UTC`
+pub fn format_time(time: SystemTime) -> String {
+    let (year, month, day, secs) = civil(time);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// Format time as RFC 3339 in UTC, e.g. `2024-10-14T06:30:00Z`
+pub fn format_timestamp(time: SystemTime) -> String {
+    let (year, month, day, secs) = civil(time);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+// Year, month, day and seconds into the day in UTC
+fn civil(time: SystemTime) -> (i64, i64, i64, u64) {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, rest) = (secs / 86400, secs % 86400);
+
+    // Days to civil date, from Howard Hinnant's date algorithms
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day, rest)
+}
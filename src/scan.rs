@@ -0,0 +1,832 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Condvar, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    cargo, detectors, disk,
+    error::{self, Error},
+    git,
+    gitignore::Rules,
+    id,
+    size::{self, Stats},
+    trashcan,
+};
+
+/// Type of project the artifact folder belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    Rust,
+    Node,
+    Python,
+    CMake,
+    Gradle,
+    // Any folder tagged with CACHEDIR.TAG
+    Cache,
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Rust => "Rust",
+            Kind::Node => "Node",
+            Kind::Python => "Python",
+            Kind::CMake => "CMake",
+            Kind::Gradle => "Gradle",
+            Kind::Cache => "Cache",
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rust" => Ok(Kind::Rust),
+            "node" => Ok(Kind::Node),
+            "python" => Ok(Kind::Python),
+            "cmake" => Ok(Kind::CMake),
+            "gradle" => Ok(Kind::Gradle),
+            "cachedir" | "cache" => Ok(Kind::Cache),
+            _ => Err(format!(
+                "unknown project type '{s}', expected rust, node, python, cmake, gradle or cachedir"
+            )),
+        }
+    }
+}
+
+/// Found target folder
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    // Stable ID of the path, see `id::of`
+    pub id: String,
+    // Folder with the manifest
+    pub project: PathBuf,
+    pub kind: Kind,
+    pub size: u64,
+    // Newest file or folder inside, or the project's manifest if it was
+    // edited later
+    pub modified: Option<SystemTime>,
+    // Project looks like it's open in an editor
+    pub open: bool,
+    // Rust target outside its project, set by CARGO_TARGET_DIR or
+    // build.target-dir. Other projects may build into it too.
+    pub shared_dir: bool,
+    // Rust target cargo doesn't build into anymore, since it was told to
+    // build elsewhere
+    pub stale: bool,
+    // Project has uncommitted changes or recent commits, see `git::is_active`
+    pub active: bool,
+    // Hard linked from outside, won't be freed by deleting this entry
+    pub shared: u64,
+    pub files: u64,
+    // Crate name, or the member names of a workspace
+    pub names: Vec<String>,
+    // Cargo workspace the project is the root or a member of
+    pub workspace: Option<Workspace>,
+    // Filesystem the entry is on
+    pub device: Option<u64>,
+    // Excluded folders inside, left out of the size and left in place when
+    // deleting, like a bind mount
+    pub kept: Vec<PathBuf>,
+}
+
+/// Cargo workspace of a Rust project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub name: String,
+    // Folder with the workspace manifest
+    pub root: PathBuf,
+    // The project is a member, so this isn't the target cargo builds into
+    pub member: bool,
+}
+
+impl Workspace {
+    fn of(project: &Path) -> Option<Workspace> {
+        let (root, member) = match cargo::workspace_root(project) {
+            Some(root) => (root, true),
+            None if cargo::workspace_members(project).is_some() => (project.to_path_buf(), false),
+            None => return None,
+        };
+        Some(Workspace {
+            name: cargo::workspace_name(&root),
+            root,
+            member,
+        })
+    }
+}
+
+// Whether the characters of `query` appear in `text` in the same order, so a
+// substring matches and so does `wnm` for `web/node_modules`. Spaces in the
+// query are ignored.
+fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut text = text.chars();
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text.any(|c| c == q))
+}
+
+impl Entry {
+    /// Measure the target at `path`, leaving out folders inside it that
+    /// `excluded` is true for
+    pub fn new(
+        path: PathBuf,
+        project: PathBuf,
+        kind: Kind,
+        excluded: &dyn Fn(&Path) -> bool,
+    ) -> Entry {
+        let stats = size::dir_stats_except(&path, excluded);
+        Entry::with_stats(path, project, kind, stats)
+    }
+
+    /// Target at `path` measured already, like when cached
+    pub fn with_stats(path: PathBuf, project: PathBuf, kind: Kind, stats: Stats) -> Entry {
+        let (names, workspace) = match kind {
+            Kind::Rust => (cargo::crate_names(&project), Workspace::of(&project)),
+            _ => (vec![], None),
+        };
+        let (shared_dir, stale) = match kind {
+            Kind::Rust => {
+                let target = cargo::target_dir(&project);
+                (
+                    path == target && !target.starts_with(&project),
+                    path != target,
+                )
+            }
+            _ => (false, false),
+        };
+        let manifest = detectors::manifest_modified(&project, kind);
+        let device = disk::device(&path);
+        Entry {
+            id: id::of(&path),
+            path,
+            project,
+            kind,
+            size: stats.size,
+            modified: stats.modified.max(manifest),
+            open: false,
+            shared_dir,
+            stale,
+            active: false,
+            shared: stats.shared,
+            files: stats.files,
+            names,
+            workspace,
+            device,
+            kept: stats.skipped,
+        }
+    }
+
+    /// Whether `dir` is an excluded folder left in place inside the target
+    pub fn is_kept(&self, dir: &Path) -> bool {
+        self.kept.iter().any(|k| k == dir)
+    }
+
+    /// Whether the path, crate or workspace names or project type contain the letters of
+    /// `query` in order, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let path = self.path.to_string_lossy();
+        std::iter::once(path.as_ref())
+            .chain(self.names.iter().map(String::as_str))
+            .chain(self.workspace.iter().map(|w| w.name.as_str()))
+            .chain(std::iter::once(self.kind.name()))
+            .any(|text| fuzzy_match(&query, &text.to_lowercase()))
+    }
+
+    /// Name to show the project by: its workspace or crate name, or else
+    /// its folder
+    pub fn name(&self) -> String {
+        match (&self.workspace, self.names.as_slice()) {
+            (Some(w), _) if !w.member => w.name.clone(),
+            (_, [name, ..]) => name.clone(),
+            _ => self.project.file_name().map_or_else(
+                || self.project.display().to_string(),
+                |n| n.to_string_lossy().to_string(),
+            ),
+        }
+    }
+
+    /// Space that deleting this entry frees, as far as hard links tell
+    pub fn reclaimable(&self) -> u64 {
+        self.size - self.shared
+    }
+
+    // Time since anything inside was modified
+    pub fn age(&self) -> Option<Duration> {
+        SystemTime::now().duration_since(self.modified?).ok()
+    }
+
+    /// Whether nothing has been modified within `age`. Unknown ages are not
+    /// older than anything.
+    pub fn is_older(&self, age: Duration) -> bool {
+        self.age().is_some_and(|a| a > age)
+    }
+}
+
+/// Least recently built of `entries` that need to go for the total size to
+/// fall within `budget` bytes, oldest first, with entries of unknown age
+/// before them. Only entries `deletable` says can be deleted are chosen, the
+/// others still count towards the total.
+pub fn over_budget(
+    entries: &[Entry],
+    budget: u64,
+    deletable: impl Fn(&Entry) -> bool,
+) -> Vec<&Entry> {
+    let mut candidates: Vec<&Entry> = entries.iter().filter(|e| deletable(e)).collect();
+    candidates.sort_by_key(|e| e.modified);
+
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+    candidates
+        .into_iter()
+        .take_while(|e| {
+            let over = total > budget;
+            total = total.saturating_sub(e.size);
+            over
+        })
+        .collect()
+}
+
+/// Order in which directories are visited while scanning
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Traversal {
+    /// Visit shallow directories first. Finds top level projects early.
+    BreadthFirst,
+    /// Walk each subtree to the bottom before moving on
+    DepthFirst,
+}
+
+impl FromStr for Traversal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bfs" | "breadth-first" => Ok(Traversal::BreadthFirst),
+            "dfs" | "depth-first" => Ok(Traversal::DepthFirst),
+            _ => Err(format!("unknown traversal '{s}', expected bfs or dfs")),
+        }
+    }
+}
+
+/// How to walk the scanned folder
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub traversal: Traversal,
+    // Folders scanned in parallel. With more than one the traversal order is
+    // only roughly followed.
+    pub threads: usize,
+    // Folder levels below the roots to scan, 0 for no limit
+    pub max_depth: usize,
+    // Folder levels below the roots to look for projects in, 0 for no limit.
+    // Folders inside projects are scanned deeper, up to `max_depth`.
+    pub project_depth: usize,
+    // Folders that are never entered
+    pub excludes: Vec<PathBuf>,
+    // Globs of folders that are never entered, gitignore style
+    pub globs: Vec<String>,
+    // Skip folders ignored by `.gitignore` and `.ignore` files
+    pub gitignore: bool,
+    // Project types to look for
+    pub kinds: Vec<Kind>,
+    // Leave out projects that were never built
+    pub skip_unbuilt: bool,
+    // Enter symlinked folders too
+    pub follow_symlinks: bool,
+    // Don't enter folders on other filesystems than their root
+    pub same_filesystem: bool,
+    // Flag projects with uncommitted changes or commits this recent
+    pub active_within: Option<Duration>,
+}
+
+/// Defaults of the command line: Rust projects found depth first on all
+/// cores, ignored folders skipped. Nothing is excluded, see
+/// `default_excludes` for the folders rclin leaves alone.
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            traversal: Traversal::DepthFirst,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            max_depth: 0,
+            project_depth: 0,
+            excludes: vec![],
+            globs: vec![],
+            gitignore: true,
+            kinds: vec![Kind::Rust],
+            skip_unbuilt: false,
+            follow_symlinks: false,
+            same_filesystem: false,
+            active_within: None,
+        }
+    }
+}
+
+/// Folders managed by package managers and toolchains. Their contents are
+/// not meant to be deleted by hand even when they look like projects.
+pub fn default_excludes(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut excludes: Vec<PathBuf> = [
+        "/nix/store",
+        "/snap",
+        "/var/lib/snapd",
+        "/var/lib/flatpak",
+        "/usr/local/cargo/registry",
+        "/usr/local/cargo/git",
+        "/usr/local/rustup",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+
+    let cargo_home = std::env::var_os("CARGO_HOME").map(PathBuf::from);
+    let rustup_home = std::env::var_os("RUSTUP_HOME").map(PathBuf::from);
+    for cargo in cargo_home.into_iter().chain(home.map(|h| h.join(".cargo"))) {
+        excludes.push(cargo.join("registry"));
+        excludes.push(cargo.join("git"));
+    }
+    excludes.extend(rustup_home);
+
+    if let Some(home) = home {
+        excludes.push(home.join(".rustup"));
+        excludes.push(home.join("snap"));
+        excludes.push(home.join(".local/share/flatpak"));
+        excludes.push(home.join(".var/app"));
+    }
+
+    excludes
+}
+
+// Folders waiting to be scanned, shared by the scanner threads
+struct Queue {
+    dirs: VecDeque<Dir>,
+    // Threads scanning a folder right now, they may queue more
+    busy: usize,
+}
+
+// Folder waiting to be scanned
+struct Dir {
+    path: PathBuf,
+    // Levels below the root
+    depth: usize,
+    // Ignore rules of the parent
+    rules: Option<Arc<Rules>>,
+    // Filesystem of the root
+    device: Option<u64>,
+    // Inside a project, which project depth doesn't limit
+    in_project: bool,
+}
+
+/// Something the scanner came across
+pub enum Found {
+    Entry(Box<Entry>),
+    // Folder that could not be read
+    Unreadable(PathBuf, std::io::ErrorKind),
+}
+
+/// How far a running scan is
+#[derive(Debug, Default)]
+pub struct Progress {
+    dirs: AtomicUsize,
+    current: Mutex<PathBuf>,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    /// Folders scanned so far
+    pub fn dirs(&self) -> usize {
+        self.dirs.load(Ordering::Relaxed)
+    }
+
+    /// Folder one of the threads is scanning
+    pub fn current(&self) -> PathBuf {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Stop the scan once the folders being scanned right now are done,
+    /// keeping what was found
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the scan was told to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn visit(&self, path: &Path) {
+        self.dirs.fetch_add(1, Ordering::Relaxed);
+        // Any thread's folder will do, no need to wait for the others
+        if let Ok(mut current) = self.current.try_lock() {
+            *current = path.to_path_buf();
+        }
+    }
+}
+
+// How often the progress line on the terminal is updated
+const TICK: Duration = Duration::from_millis(100);
+
+/// Scan `roots` and collect what is found into `results`. Tells how it's
+/// going on stderr when that's a terminal, so a long scan doesn't look stuck.
+pub fn scan(roots: &[PathBuf], options: &Options, results: &mut Vec<Entry>) -> error::Result<()> {
+    let entries = Mutex::new(vec![]);
+    let progress = Progress::default();
+    let done = AtomicBool::new(false);
+    let terminal = std::io::stderr().is_terminal();
+    // Over the progress line
+    let clear = if terminal { "\r\x1b[K" } else { "" };
+
+    std::thread::scope(|s| {
+        if terminal {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    let found = entries.lock().unwrap().len();
+                    tick(&progress, found);
+                    std::thread::sleep(TICK);
+                }
+                // Leave the line for what comes next
+                eprint!("{clear}");
+            });
+        }
+        let result = run(roots, options, &progress, &|found| match found {
+            Found::Entry(entry) => entries.lock().unwrap().push(*entry),
+            Found::Unreadable(path, kind) => eprintln!("{clear}Cannot scan {path:?}: {kind}"),
+        });
+        done.store(true, Ordering::Relaxed);
+        result
+    })?;
+
+    results.extend(entries.into_inner().unwrap());
+    Ok(())
+}
+
+// Rewrite the progress line, cut to the terminal width so it doesn't wrap
+fn tick(progress: &Progress, found: usize) {
+    let width = crossterm::terminal::size().map_or(80, |(w, _)| usize::from(w));
+    let line = format!(
+        "{} folders scanned, {found} targets found, {}",
+        progress.dirs(),
+        progress.current().display()
+    );
+    let line: String = line.chars().take(width.saturating_sub(1)).collect();
+    eprint!("\r\x1b[K{line}");
+    let _ = std::io::stderr().flush();
+}
+
+/// Scan running on a background thread, and how far it is
+pub type Background = (
+    Receiver<Found>,
+    JoinHandle<error::Result<()>>,
+    Arc<Progress>,
+);
+
+/// Scan `roots` on a background thread, sending everything as soon as it's
+/// found. The channel closes when the scan is done and the thread returns
+/// the error that stopped it, if any.
+pub fn spawn(roots: Vec<PathBuf>, options: Options) -> Background {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Arc::new(Progress::default());
+    let shared = Arc::clone(&progress);
+    let handle = std::thread::spawn(move || {
+        run(&roots, &options, &shared, &|found| {
+            // Nobody listens anymore when quitting
+            let _ = sender.send(found);
+        })
+    });
+    (receiver, handle, progress)
+}
+
+#[tracing::instrument(skip_all, fields(roots = roots.len(), threads = options.threads))]
+fn run(
+    roots: &[PathBuf],
+    options: &Options,
+    progress: &Progress,
+    report: &(dyn Fn(Found) + Sync),
+) -> error::Result<()> {
+    // Asking git takes a moment, better on the scanner threads than in the
+    // interface
+    let report = &|mut found: Found| {
+        if let (Found::Entry(entry), Some(within)) = (&mut found, options.active_within) {
+            entry.active = git::is_active(&entry.project, &entry.path, within);
+        }
+        report(found)
+    };
+
+    // Scanning inside an excluded folder on purpose is fine
+    let excludes: Vec<PathBuf> = options
+        .excludes
+        .iter()
+        .filter(|e| !roots.iter().any(|root| root.starts_with(e)))
+        .cloned()
+        .collect();
+
+    // Globs given with a slash are matched against the whole path
+    let globs = Rules::new(Path::new("/"), options.globs.iter().map(String::as_str));
+    let dirs = roots.iter().map(|root| Dir {
+        path: root.clone(),
+        depth: 0,
+        rules: options.gitignore.then(|| parent_rules(root)).flatten(),
+        device: disk::device(root),
+        in_project: false,
+    });
+
+    let queue = Mutex::new(Queue {
+        dirs: dirs.collect(),
+        busy: 0,
+    });
+    let wake = Condvar::new();
+    // Target folders already found, several projects can share one
+    let seen = Mutex::new(HashSet::new());
+    // Real paths of folders scanned, symlinks can lead to them again
+    let visited = Mutex::new(roots.iter().flat_map(|r| r.canonicalize()).collect());
+    let error = Mutex::new(None);
+
+    std::thread::scope(|s| {
+        for _ in 0..options.threads.max(1) {
+            s.spawn(|| {
+                let walker = Walker {
+                    traversal: options.traversal,
+                    kinds: &options.kinds,
+                    max_depth: options.max_depth,
+                    project_depth: options.project_depth,
+                    excludes: &excludes,
+                    globs: &globs,
+                    gitignore: options.gitignore,
+                    skip_unbuilt: options.skip_unbuilt,
+                    follow_symlinks: options.follow_symlinks,
+                    same_filesystem: options.same_filesystem,
+                    visited: &visited,
+                    queue: &queue,
+                    wake: &wake,
+                    seen: &seen,
+                    progress,
+                    report,
+                    error: &error,
+                };
+                walker.work();
+            });
+        }
+    });
+
+    match error.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+// Rules of folders above `root`, they apply too like in git
+fn parent_rules(root: &Path) -> Option<Arc<Rules>> {
+    let parents: Vec<&Path> = root
+        .ancestors()
+        .skip(1)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .collect();
+    parents
+        .into_iter()
+        .rev()
+        .fold(None, |parent, dir| Rules::load(dir, parent))
+}
+
+// State shared by the scanner threads
+struct Walker<'a> {
+    traversal: Traversal,
+    kinds: &'a [Kind],
+    max_depth: usize,
+    project_depth: usize,
+    excludes: &'a [PathBuf],
+    globs: &'a Rules,
+    gitignore: bool,
+    skip_unbuilt: bool,
+    follow_symlinks: bool,
+    same_filesystem: bool,
+    visited: &'a Mutex<HashSet<PathBuf>>,
+    queue: &'a Mutex<Queue>,
+    wake: &'a Condvar,
+    seen: &'a Mutex<HashSet<PathBuf>>,
+    progress: &'a Progress,
+    report: &'a (dyn Fn(Found) + Sync),
+    error: &'a Mutex<Option<Error>>,
+}
+
+impl Walker<'_> {
+    fn is_excluded(&self, dir: &Path) -> bool {
+        is_excluded(dir, self.excludes, self.globs)
+    }
+
+    // Why `dir` below a scanned folder isn't scanned, if it isn't
+    fn skip(&self, dir: &Path, rules: Option<&Rules>, device: Option<u64>) -> Option<&str> {
+        if self.is_excluded(dir) {
+            Some("excluded")
+        } else if rules.is_some_and(|r| r.is_ignored(dir)) {
+            Some("ignored")
+        } else if self.same_filesystem && disk::device(dir) != device {
+            Some("on another filesystem")
+        } else if self.follow_symlinks
+            // Each folder once, whichever way it was reached
+            && !dir
+                .canonicalize()
+                .is_ok_and(|real| self.visited.lock().unwrap().insert(real))
+        {
+            Some("scanned already")
+        } else {
+            None
+        }
+    }
+
+    // Take folders from the queue until it's empty and no other thread can add more
+    fn work(&self) {
+        loop {
+            let Dir {
+                path,
+                depth,
+                rules,
+                device,
+                in_project,
+            } = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    let next = match self.traversal {
+                        Traversal::BreadthFirst => queue.dirs.pop_front(),
+                        Traversal::DepthFirst => queue.dirs.pop_back(),
+                    };
+                    if let Some(next) = next {
+                        queue.busy += 1;
+                        break next;
+                    }
+                    if queue.busy == 0 {
+                        return;
+                    }
+                    queue = self.wake.wait(queue).unwrap();
+                }
+            };
+
+            self.progress.visit(&path);
+            let subdirs = scan_dir(
+                &path,
+                self.kinds,
+                &|dir| self.is_excluded(dir),
+                self.skip_unbuilt,
+                self.follow_symlinks,
+                self.seen,
+                self.report,
+            );
+            let rules = if self.gitignore {
+                Rules::load(&path, rules)
+            } else {
+                rules
+            };
+
+            let mut queue = self.queue.lock().unwrap();
+            queue.busy -= 1;
+            match subdirs {
+                // Nothing more is scanned once cancelled
+                _ if self.progress.is_cancelled() => queue.dirs.clear(),
+                // Deep enough
+                Ok(_) if self.max_depth > 0 && depth >= self.max_depth => (),
+                // No projects any deeper, and not in one
+                Ok((_, project))
+                    if !in_project
+                        && !project
+                        && self.project_depth > 0
+                        && depth >= self.project_depth => {}
+                Ok((mut subdirs, project)) => {
+                    subdirs.retain(|dir| match self.skip(dir, rules.as_deref(), device) {
+                        Some(reason) => {
+                            tracing::debug!(path = %dir.display(), "skipped, {reason}");
+                            false
+                        }
+                        None => true,
+                    });
+
+                    // Keep the natural directory order when walking depth first
+                    if self.traversal == Traversal::DepthFirst {
+                        subdirs.reverse();
+                    }
+                    queue.dirs.extend(subdirs.into_iter().map(|path| Dir {
+                        path,
+                        depth: depth + 1,
+                        rules: rules.clone(),
+                        device,
+                        in_project: in_project || project,
+                    }));
+                }
+                // Stop everything on the first error
+                Err(e) => {
+                    self.error.lock().unwrap().get_or_insert(e);
+                    queue.dirs.clear();
+                }
+            }
+            self.wake.notify_all();
+        }
+    }
+}
+
+// Path of the exclude rule that keeps `path` from being scanned
+fn excluded_by<'a>(path: &Path, excludes: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    excludes.iter().find(|e| path.starts_with(e))
+}
+
+// Excluded by path or glob. Unlike ignore files, these apply inside targets
+// too, whose own `.gitignore` ignores everything.
+fn is_excluded(dir: &Path, excludes: &[PathBuf], globs: &Rules) -> bool {
+    excluded_by(dir, excludes).is_some() || globs.is_ignored(dir)
+}
+
+// Whether `dir` found in a scanned folder is looked into at all. Tagged
+// caches are skipped the way backup tools skip them, and targets in the
+// trash were deleted already.
+fn is_entered(dir: &Path) -> bool {
+    !detectors::is_cache_dir(dir) && !trashcan::is_trash(dir)
+}
+
+/// Why scanning a folder above `dir` with `options` doesn't look into it, if
+/// it doesn't, the way the scanner decides it
+pub fn skipped(dir: &Path, options: &Options) -> Option<&'static str> {
+    let globs = Rules::new(Path::new("/"), options.globs.iter().map(String::as_str));
+    let rules = options.gitignore.then(|| parent_rules(dir)).flatten();
+    if is_excluded(dir, &options.excludes, &globs) {
+        Some("excluded by --exclude or the default excludes")
+    } else if rules.is_some_and(|r| r.is_ignored(dir)) {
+        Some("ignored by a .gitignore or .ignore file, see --no-gitignore")
+    } else if dir.ancestors().any(|dir| !is_entered(dir)) {
+        Some("it is in the trash or a folder tagged as a cache")
+    } else {
+        None
+    }
+}
+
+// Scan a single directory and return the subdirectories to visit next, and
+// whether it holds a project
+#[tracing::instrument(level = "debug", skip(kinds, excluded, seen, report))]
+fn scan_dir(
+    path: &Path,
+    kinds: &[Kind],
+    excluded: &dyn Fn(&Path) -> bool,
+    skip_unbuilt: bool,
+    follow_symlinks: bool,
+    seen: &Mutex<HashSet<PathBuf>>,
+    report: &(dyn Fn(Found) + Sync),
+) -> error::Result<(Vec<PathBuf>, bool)> {
+    let mut subdirs = vec![];
+    let mut project = false;
+
+    match detectors::find(path, kinds) {
+        Ok(found) => {
+            project = !found.is_empty();
+            for (kind, artifact) in &found {
+                if skip_unbuilt && detectors::is_unbuilt(*kind, path, artifact) {
+                    tracing::debug!(path = %artifact.display(), "skipped, never built");
+                    continue;
+                }
+                if seen.lock().unwrap().insert(artifact.clone()) {
+                    tracing::info!(path = %artifact.display(), kind = kind.name(), "found target");
+                    let entry = Entry::new(artifact.clone(), path.to_path_buf(), *kind, excluded);
+                    report(Found::Entry(Box::new(entry)));
+                }
+            }
+
+            // Aight bet, loop again. The folder can be gone by now, like
+            // when a cargo clean runs alongside, which only leaves it out.
+            let dir = match std::fs::read_dir(path) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), "can't read: {e}");
+                    report(Found::Unreadable(path.to_path_buf(), e.kind()));
+                    return Ok((subdirs, project));
+                }
+            };
+
+            for entry in dir.flatten() {
+                // Artifacts hold no projects of their own, while node_modules
+                // is full of package.json files. Windows junctions count as
+                // symlinks, and online-only OneDrive folders would be
+                // downloaded by listing them.
+                let dir = entry.file_type().is_ok_and(|t| {
+                    t.is_dir() || (follow_symlinks && t.is_symlink() && entry.path().is_dir())
+                }) && !(cfg!(windows)
+                    && entry.metadata().is_ok_and(|m| disk::is_placeholder(&m)));
+                if dir
+                    && !found.iter().any(|(_, artifact)| *artifact == entry.path())
+                    && is_entered(&entry.path())
+                {
+                    subdirs.push(entry.path());
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!(path = %path.display(), "can't read: {e}");
+            report(Found::Unreadable(path.to_path_buf(), e.kind()));
+        }
+    }
+
+    Ok((subdirs, project))
+}
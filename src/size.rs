@@ -0,0 +1,316 @@
+use std::{
+    collections::HashMap,
+    fs::Metadata,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::SystemTime,
+};
+
+use crate::disk;
+
+/// Format bytes as human readable size, e.g. 1.4 GB
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Parse human readable size like `100MB`, `1.5G` or `2048`
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let number: f64 = number.parse().map_err(|_| format!("invalid size '{s}'"))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(format!("invalid size unit in '{s}'")),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Size and newest modification time of a folder
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    // Number of files, deleting many small files is slow
+    pub files: u64,
+    // Part of size in files that are hard linked from outside the folder too,
+    // deleting the folder won't free it
+    pub shared: u64,
+    // Folders inside that were skipped, not counted in any of the above
+    pub skipped: Vec<PathBuf>,
+}
+
+/// What sizes measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    // Space the files take on disk, what deleting them frees
+    #[default]
+    Disk,
+    // Lengths of the files, like `du --apparent-size`
+    Apparent,
+}
+
+static METRIC: OnceLock<Metric> = OnceLock::new();
+
+/// Pick what sizes measure, once before sizing anything
+pub fn set_metric(metric: Metric) {
+    let _ = METRIC.set(metric);
+}
+
+/// What sizes measure, disk usage unless picked otherwise
+pub fn metric() -> Metric {
+    METRIC.get().copied().unwrap_or_default()
+}
+
+/// Size of a file by the metric in use
+pub fn usage(meta: &Metadata) -> u64 {
+    match metric() {
+        Metric::Disk => disk_usage(meta),
+        Metric::Apparent => meta.len(),
+    }
+}
+
+/// Disk space taken by a file. On unix this is the allocated blocks like
+/// `du` reports, so sparse files and block rounding are accounted for.
+#[cfg(unix)]
+pub fn disk_usage(meta: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    // st_blocks is always in 512 byte units
+    meta.blocks() * 512
+}
+
+// Cloud placeholders take no space until they are downloaded
+#[cfg(not(unix))]
+pub fn disk_usage(meta: &Metadata) -> u64 {
+    if disk::is_placeholder(meta) {
+        0
+    } else {
+        meta.len()
+    }
+}
+
+// Device and inode, and link count, of a file with more than one hard link
+#[cfg(unix)]
+fn hard_link(meta: &Metadata) -> Option<((u64, u64), u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (meta.nlink() > 1).then(|| ((meta.dev(), meta.ino()), meta.nlink()))
+}
+
+#[cfg(not(unix))]
+fn hard_link(_meta: &Metadata) -> Option<((u64, u64), u64)> {
+    None
+}
+
+// Hard linked file met during a walk
+struct Link {
+    links: u64,
+    seen: u64,
+    usage: u64,
+}
+
+/// Size of files in `path` and its subfolders. Symlinks are not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    dir_stats(path).size
+}
+
+/// Walk `path` and its subfolders, summing sizes and tracking the most
+/// recent modification time. Symlinks are not followed and hard linked files
+/// are counted once.
+pub fn dir_stats(path: &Path) -> Stats {
+    dir_stats_except(path, &|_| false)
+}
+
+/// `dir_stats` leaving out the folders inside `path` that `skip` is true for,
+/// like excluded ones
+#[tracing::instrument(skip(skip))]
+pub fn dir_stats_except(path: &Path, skip: &dyn Fn(&Path) -> bool) -> Stats {
+    let mut stats = Stats::default();
+    let mut links = HashMap::new();
+    walk(path, skip, &mut stats, &mut links);
+
+    // Files with links we didn't see live on after the folder is deleted
+    stats.shared = links
+        .values()
+        .filter(|link| link.seen < link.links)
+        .map(|link| link.usage)
+        .sum();
+    stats
+}
+
+fn walk(
+    path: &Path,
+    skip: &dyn Fn(&Path) -> bool,
+    stats: &mut Stats,
+    links: &mut HashMap<(u64, u64), Link>,
+) {
+    let Ok(dir) = std::fs::read_dir(path) else {
+        return;
+    };
+
+    for entry in dir.flatten() {
+        if let Ok(meta) = entry.metadata() {
+            // Listing an online-only folder would download it
+            if meta.is_dir() && disk::is_placeholder(&meta) {
+                continue;
+            }
+            if meta.is_dir() && skip(&entry.path()) {
+                stats.skipped.push(entry.path());
+                continue;
+            }
+            if meta.is_dir() {
+                // Folders take blocks too
+                stats.size += usage(&meta);
+                walk(&entry.path(), skip, stats, links);
+            } else if meta.is_file() {
+                stats.files += 1;
+                match hard_link(&meta) {
+                    Some((id, count)) => {
+                        let link = links.entry(id).or_insert_with(|| {
+                            stats.size += usage(&meta);
+                            Link {
+                                links: count,
+                                seen: 0,
+                                usage: usage(&meta),
+                            }
+                        });
+                        link.seen += 1;
+                    }
+                    None => stats.size += usage(&meta),
+                }
+            }
+            stats.modified = stats.modified.max(meta.modified().ok());
+        }
+    }
+}
+
+/// Disk usage of data in `path` that is shared with other files through
+/// reflinks or filesystem snapshots (btrfs, XFS), so deleting `path` won't
+/// free it. Opens every file, so it's only done for one folder at a time.
+#[cfg(target_os = "linux")]
+#[tracing::instrument]
+pub fn reflinked(path: &Path) -> u64 {
+    let Ok(dir) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    let mut shared = 0;
+    for entry in dir.flatten() {
+        match entry.metadata() {
+            Ok(meta) if meta.is_dir() => shared += reflinked(&entry.path()),
+            Ok(meta) if meta.is_file() => shared += shared_extents(&entry.path()),
+            _ => (),
+        }
+    }
+    shared
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn reflinked(_path: &Path) -> u64 {
+    0
+}
+
+// Sum the extents of a file flagged as shared, using the FIEMAP ioctl
+#[cfg(target_os = "linux")]
+fn shared_extents(path: &Path) -> u64 {
+    use std::os::unix::io::AsRawFd;
+
+    const FS_IOC_FIEMAP: u64 = 0xC020_660B;
+    const FIEMAP_EXTENT_LAST: u32 = 0x1;
+    const FIEMAP_EXTENT_SHARED: u32 = 0x2000;
+    const EXTENTS: usize = 64;
+
+    // struct fiemap_extent and struct fiemap from linux/fiemap.h
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Fiemap {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; EXTENTS],
+    }
+
+    let Ok(file) = std::fs::File::open(path) else {
+        return 0;
+    };
+
+    let mut shared = 0;
+    let mut start = 0;
+    loop {
+        // SAFETY: all-zero is a valid Fiemap, it's plain integers
+        let mut map: Fiemap = unsafe { std::mem::zeroed() };
+        map.start = start;
+        map.length = u64::MAX;
+        map.extent_count = EXTENTS as u32;
+
+        // SAFETY: map is a fiemap with room for extent_count extents
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP as _, &mut map) };
+        if result != 0 || map.mapped_extents == 0 {
+            return shared;
+        }
+
+        let extents = &map.extents[..map.mapped_extents as usize];
+        shared += extents
+            .iter()
+            .filter(|e| e.flags & FIEMAP_EXTENT_SHARED != 0)
+            .map(|e| e.length)
+            .sum::<u64>();
+
+        let last = extents[extents.len() - 1];
+        if last.flags & FIEMAP_EXTENT_LAST != 0 {
+            return shared;
+        }
+        start = last.logical + last.length;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_and_parses_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 << 30), "3.0 GB");
+        assert_eq!(parse_size("2048"), Ok(2048));
+        assert_eq!(parse_size("100MB"), Ok(100 << 20));
+        assert_eq!(parse_size("1.5G"), Ok(3 << 29));
+        assert!(parse_size("lots").is_err());
+    }
+}
@@ -1,32 +1,270 @@
-use tui::widgets::ListState;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::mpsc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use tui::{layout::Rect, widgets::ListState};
+
+use crate::{
+    browser::{Browser, Preview},
+    caps::Caps,
+    cargo::Checker,
+    deleter::{Cleanup, Deleter, Removal},
+    details::Details,
+    disk::Filesystem,
+    duplicates::Report,
+    editors::OpenProjects,
+    history::Changes,
+    keys::Keys,
+    report::ReportFormat,
+    scan::{self, Entry},
+    theme::Theme,
+    tree::Tree,
+};
 
 pub struct Events {
     // `items` is the state managed by your application.
-    pub items: Vec<String>,
+    pub items: Vec<Entry>,
     // `state` is the state that can be modified by the UI. It stores the index of the selected
     // item as well as the offset computed during the previous draw call (used to implement
     // natural scrolling).
     pub state: ListState,
+    // Paths of entries marked for deletion
+    pub marked: HashSet<PathBuf>,
+    // Deleted entries still shown, and when they were deleted
+    pub tombstones: HashMap<PathBuf, Instant>,
+    // Entries that failed to delete, and why
+    pub failed: HashMap<PathBuf, String>,
+    // First row on screen
+    pub offset: usize,
+    // Where the list and the words of the key help were drawn, with the key
+    // of each word's action, for the mouse
+    pub list_area: Rect,
+    pub hints: Vec<(Rect, String)>,
+    // Row and time of the last click, to tell double clicks
+    last_click: Option<(usize, Instant)>,
 }
 
+// Longest time between the clicks of a double click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
 impl Events {
-    pub fn new(items: Vec<String>) -> Events {
+    pub fn new(items: Vec<Entry>) -> Events {
         Events {
             items,
             state: ListState::default(),
+            marked: HashSet::new(),
+            tombstones: HashMap::new(),
+            failed: HashMap::new(),
+            offset: 0,
+            list_area: Rect::default(),
+            hints: vec![],
+            last_click: None,
         }
     }
 
+    /// Selected entry, unless it has been deleted
+    pub fn selected(&self) -> Option<&Entry> {
+        let entry = self.items.get(self.state.selected()?)?;
+        (!self.is_tombstone(&entry.path)).then_some(entry)
+    }
+
+    pub fn is_tombstone(&self, path: &Path) -> bool {
+        self.tombstones.contains_key(path)
+    }
+
+    // Remove entries that have been tombstones for longer than `after`, keeping
+    // the selection on the same row. Returns true if anything was removed.
+    pub fn bury(&mut self, after: Duration) -> bool {
+        let before = self.items.len();
+        let selected = self.state.selected();
+        let mut above = 0;
+        let mut i = 0;
+        let tombstones = &mut self.tombstones;
+        self.items.retain(|e| {
+            let dead = tombstones
+                .get(&e.path)
+                .is_some_and(|deleted| deleted.elapsed() >= after);
+            if dead {
+                tombstones.remove(&e.path);
+                above += usize::from(selected.is_some_and(|s| i < s));
+            }
+            i += 1;
+            !dead
+        });
+
+        if let Some(s) = selected {
+            let last = self.items.len().checked_sub(1);
+            self.state.select(last.map(|last| (s - above).min(last)));
+        }
+        before != self.items.len()
+    }
+
     pub fn clear(&mut self) {
         self.items = vec![];
+        self.tombstones.clear();
+        self.failed.clear();
+        self.offset = 0;
         // We reset the state as the associated items have changed. This effectively reset
         // the selection as well as the stored offset.
         self.state = ListState::default();
     }
 
+    // Select `row` after a click on it. Returns true for the second click of
+    // a double click.
+    pub fn click(&mut self, row: usize) -> bool {
+        if row >= self.items.len() {
+            return false;
+        }
+        self.state.select(Some(row));
+        let double = self
+            .last_click
+            .is_some_and(|(last, at)| last == row && at.elapsed() < DOUBLE_CLICK);
+        self.last_click = (!double).then(|| (row, Instant::now()));
+        double
+    }
+
+    // Mark the selected entry, or unmark it if it's marked already
+    pub fn toggle_mark(&mut self) {
+        if let Some(path) = self.selected().map(|e| e.path.clone()) {
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+        }
+    }
+
+    // Order the list, keeping the same entry selected
+    pub fn sort(&mut self, by: Sort, reversed: bool) {
+        let selected = self.state.selected().and_then(|i| self.items.get(i));
+        let selected = selected.map(|e| e.path.clone());
+
+        by.apply(&mut self.items, reversed);
+
+        if let Some(path) = selected {
+            let idx = self.items.iter().position(|e| e.path == path);
+            self.state.select(idx);
+        }
+    }
+
+    // Move entries with a lower `rank` first, keeping their order otherwise
+    // and the same entry selected
+    pub fn group(&mut self, rank: impl Fn(&Entry) -> usize) {
+        let selected = self.state.selected().and_then(|i| self.items.get(i));
+        let selected = selected.map(|e| e.path.clone());
+
+        self.items.sort_by_key(|e| rank(e));
+
+        if let Some(path) = selected {
+            let idx = self.items.iter().position(|e| e.path == path);
+            self.state.select(idx);
+        }
+    }
+
+    // Move targets of workspace members right below the target of their
+    // workspace, keeping the order otherwise and the same entry selected
+    pub fn group_workspaces(&mut self) {
+        let selected = self.state.selected().and_then(|i| self.items.get(i));
+        let selected = selected.map(|e| e.path.clone());
+
+        let roots: HashSet<PathBuf> = self
+            .items
+            .iter()
+            .filter_map(|e| e.workspace.as_ref().filter(|w| !w.member))
+            .map(|w| w.root.clone())
+            .collect();
+        let member_of = |e: &Entry| {
+            e.workspace
+                .as_ref()
+                .filter(|w| w.member && roots.contains(&w.root))
+                .map(|w| w.root.clone())
+        };
+        let (members, mut rest): (Vec<Entry>, Vec<Entry>) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|e| member_of(e).is_some());
+        for member in members.into_iter().rev() {
+            let root = member_of(&member);
+            let at = rest.iter().position(|e| {
+                e.workspace
+                    .as_ref()
+                    .is_some_and(|w| !w.member && Some(&w.root) == root.as_ref())
+            });
+            rest.insert(at.map_or(rest.len(), |i| i + 1), member);
+        }
+        self.items = rest;
+
+        if let Some(path) = selected {
+            let idx = self.items.iter().position(|e| e.path == path);
+            self.state.select(idx);
+        }
+    }
+
+    // Select the `n`th row on screen, counting from 1, and toggle its mark
+    pub fn quick_select(&mut self, n: usize) {
+        let idx = self.offset + n - 1;
+        if idx < self.items.len() {
+            self.state.select(Some(idx));
+            self.toggle_mark();
+        }
+    }
+
+    // Scroll like the list widget does, which keeps the selection on screen
+    // and otherwise stays put, to know which rows are on a screen of `height` rows
+    pub fn scroll(&mut self, height: usize) {
+        let last = self.items.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0).min(last);
+        self.offset = self.offset.min(last);
+        if selected >= self.offset + height {
+            self.offset = selected + 1 - height;
+        }
+        if selected < self.offset {
+            self.offset = selected;
+        }
+    }
+
+    // Mark every listed entry, or unmark them if they all are marked already.
+    // Entries a filter hides are left alone.
+    pub fn mark_all(&mut self) {
+        let alive: Vec<PathBuf> = self
+            .items
+            .iter()
+            .filter(|e| !self.is_tombstone(&e.path))
+            .map(|e| e.path.clone())
+            .collect();
+
+        if alive.iter().all(|path| self.marked.contains(path)) {
+            for path in &alive {
+                self.marked.remove(path);
+            }
+        } else {
+            self.marked.extend(alive);
+        }
+    }
+
+    // Mark entries that have not been modified within `age`. Returns the number of
+    // newly marked entries.
+    pub fn mark_older(&mut self, age: Duration) -> usize {
+        let mut count = 0;
+        let alive = self
+            .items
+            .iter()
+            .filter(|e| !self.tombstones.contains_key(&e.path));
+        for item in alive {
+            if item.is_older(age) && self.marked.insert(item.path.clone()) {
+                count += 1;
+            }
+        }
+        count
+    }
+
     // Select the next item. This will not be reflected until the widget is drawn in the
     // `Terminal::draw` callback using `Frame::render_stateful_widget`.
     pub fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -40,9 +278,36 @@ impl Events {
         self.state.select(Some(i));
     }
 
+    // Select the item a screenful up, or the first one, without wrapping
+    pub fn page_up(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.select(i.saturating_sub(self.page()));
+    }
+
+    // Select the item a screenful down, or the last one, without wrapping
+    pub fn page_down(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.select(i + self.page());
+    }
+
+    // Select the item at `i`, or the last one if there aren't that many
+    pub fn select(&mut self, i: usize) {
+        if !self.items.is_empty() {
+            self.state.select(Some(i.min(self.items.len() - 1)));
+        }
+    }
+
+    // Rows on screen, inside the borders
+    fn page(&self) -> usize {
+        usize::from(self.list_area.height.saturating_sub(2)).max(1)
+    }
+
     // Select the previous item. This will not be reflected until the widget is drawn in the
     // `Terminal::draw` callback using `Frame::render_stateful_widget`.
     pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -57,8 +322,338 @@ impl Events {
     }
 }
 
-#[derive(Debug)]
+/// Order of the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    // As the scanner finds them
+    Found,
+    // Biggest first
+    Size,
+    Path,
+    // Oldest first
+    Age,
+}
+
+impl Sort {
+    /// Order `entries`, the other way around if `reversed`. Entries found in
+    /// the same order keep it.
+    pub fn apply(self, entries: &mut [Entry], reversed: bool) {
+        match self {
+            Sort::Found => return,
+            Sort::Size => entries.sort_by_key(|e| std::cmp::Reverse(e.size)),
+            Sort::Path => entries.sort_by(|a, b| a.path.cmp(&b.path)),
+            // Never modified ones come last
+            Sort::Age => entries.sort_by_key(|e| (e.modified.is_none(), e.modified)),
+        }
+        if reversed {
+            entries.reverse();
+        }
+    }
+
+    /// Name of the order for the footer
+    pub fn describe(self, reversed: bool) -> &'static str {
+        match (self, reversed) {
+            (Sort::Found, _) => "as found",
+            (Sort::Size, false) => "size, biggest first",
+            (Sort::Size, true) => "size, smallest first",
+            (Sort::Path, false) => "path, A to Z",
+            (Sort::Path, true) => "path, Z to A",
+            (Sort::Age, false) => "age, oldest first",
+            (Sort::Age, true) => "age, newest first",
+        }
+    }
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "found" => Ok(Sort::Found),
+            "size" => Ok(Sort::Size),
+            "path" => Ok(Sort::Path),
+            "age" => Ok(Sort::Age),
+            _ => Err(format!(
+                "unknown sort order '{s}', expected found, size, path or age"
+            )),
+        }
+    }
+}
+
+/// What the UI is currently showing
+pub enum Mode {
+    List,
+    // Looking inside a target folder
+    Browse(Browser),
+    // Asking for age in days, entries older than that get marked
+    MarkOlder(String),
+    // Typing the filter, with the filter there was before so it can be
+    // cancelled
+    Search(String),
+    // Dialog over the list
+    Confirm(Confirm),
+    // Graph of the total size of remembered scans
+    Trend,
+    // Every key and the current settings
+    Help,
+    // Typing the note of the selected entry
+    Note(String),
+    // Projects excluded from scans, to include again
+    Excluded(ListState),
+    // Results grouped under the folders they share, in `State::tree`
+    Tree,
+    // Picking the profile folders of a Rust target to clean
+    Profiles(Profiles),
+    // Dependencies built the same in several Rust targets
+    Duplicates(Duplicates),
+}
+
+/// Dependencies of Rust targets compared across them in the background
+pub struct Duplicates {
+    // Until the comparison is done
+    pub receiver: Option<mpsc::Receiver<Report>>,
+    pub report: Option<Report>,
+    pub state: ListState,
+}
+
+/// Profile folders of a Rust target, picked to clean or keep
+pub struct Profiles {
+    pub path: PathBuf,
+    // Name, size and whether it's cleaned
+    pub folders: Vec<(String, u64, bool)>,
+    pub state: ListState,
+}
+
+// How long a message stays in the status line when more are waiting, and
+// when it's the last one
+const MESSAGE_TIME: Duration = Duration::from_secs(1);
+const LAST_MESSAGE_TIME: Duration = Duration::from_secs(5);
+
+/// Line of feedback about what an action did
+pub struct Message {
+    pub text: String,
+    pub error: bool,
+    // Replaced by the next progress update instead of waiting its turn
+    progress: bool,
+}
+
+/// Messages waiting to be shown in the status line, one at a time
+#[derive(Default)]
+pub struct Messages {
+    queue: VecDeque<Message>,
+    // When the first message was first shown
+    shown: Option<Instant>,
+}
+
+impl Messages {
+    pub fn push(&mut self, text: String) {
+        self.add(text, false, false);
+    }
+
+    pub fn error(&mut self, text: String) {
+        self.add(text, true, false);
+    }
+
+    /// Show how a longer action is going, in place of its previous update
+    pub fn progress(&mut self, text: String) {
+        match self.queue.iter_mut().find(|m| m.progress) {
+            Some(message) => message.text = text,
+            None => self.add(text, false, true),
+        }
+    }
+
+    /// Replace the progress of an action with how it went
+    pub fn finish(&mut self, text: String) {
+        self.progress(text);
+        for message in &mut self.queue {
+            message.progress = false;
+        }
+    }
+
+    fn add(&mut self, text: String, error: bool, progress: bool) {
+        self.queue.push_back(Message {
+            text,
+            error,
+            progress,
+        });
+    }
+
+    /// Message to show now
+    pub fn current(&mut self) -> Option<&Message> {
+        if self.shown.is_none() && !self.queue.is_empty() {
+            self.shown = Some(Instant::now());
+        }
+        self.queue.front()
+    }
+
+    /// Let the shown message go once it has been up long enough, the last
+    /// one stays a while longer. Returns true if the status line changed.
+    pub fn tick(&mut self) -> bool {
+        let time = if self.queue.len() > 1 {
+            MESSAGE_TIME
+        } else {
+            LAST_MESSAGE_TIME
+        };
+        let expired = self.shown.is_some_and(|shown| shown.elapsed() >= time)
+            // Progress stays up until the action is done
+            && !self.queue.front().is_some_and(|m| m.progress);
+        if expired {
+            self.queue.pop_front();
+            self.shown = None;
+        }
+        expired
+    }
+}
+
+/// Question asked in a dialog before doing something that can't be taken back
+/// with a single key
+pub enum Confirm {
+    // Trash these paths
+    Trash(Vec<PathBuf>),
+    // Trashing these paths would take long, asking how to go on
+    SlowDelete(Vec<PathBuf>),
+    // Delete these paths without trash
+    PermanentDelete(Vec<PathBuf>),
+    // Run cargo clean in the projects of these paths
+    CargoClean(Vec<PathBuf>),
+    // Remove the heavy parts of these paths, keeping binaries
+    LightClean(Vec<PathBuf>),
+    // Empty trashed targets from trash
+    EmptyTrash,
+}
+
+/// Deletes going on in the background, counted for progress
+pub struct Batch {
+    pub total: usize,
+    pub finished: usize,
+    pub permanent: bool,
+    // By cargo clean or a light clean
+    pub cleaning: bool,
+    // Free space when it started
+    pub free_before: Option<u64>,
+    // Last one that went through, told by path when it's the only one
+    pub last: Option<(PathBuf, Removal)>,
+}
+
+/// Entry in trash and where it was before it went there
+pub struct Undo {
+    pub path: PathBuf,
+    // Index in the results and row in the list
+    pub result: usize,
+    pub row: Option<usize>,
+}
+
 pub struct State {
-    pub results: Vec<String>,
+    pub mode: Mode,
+    // Scanned folders
+    pub roots: Vec<PathBuf>,
+    // How the roots are scanned, kept for scanning again
+    pub scan_options: scan::Options,
+    pub results: Vec<Entry>,
+    // Background scan while it's running
+    pub scan: Option<scan::Background>,
+    pub started: Instant,
+    // Scan time once done
     pub time: f32,
+    // The last scan was cancelled before it was done
+    pub cancelled: bool,
+    // What the scan couldn't read and why, told after the interface closes
+    pub warnings: Vec<String>,
+    // Copy release binaries to cargo bin folder before trashing
+    pub salvage: bool,
+    // Remove folders instead of trashing them
+    pub permanent: bool,
+    // How Rust targets are cleaned up, cargo clean gets these arguments
+    pub cleanup: Cleanup,
+    pub cargo_clean_args: Vec<String>,
+    // Checks that projects still build once their Rust targets are gone, with
+    // --verify
+    pub checker: Option<Checker>,
+    // Ask before deleting a single entry too, not only before bulk deletes
+    pub confirm_single: bool,
+    // Show what's inside folders when asking before deleting them
+    pub preview: bool,
+    // What the folders asked about hold, if previewed
+    pub preview_of: Option<Preview>,
+    // Only entries matching this are listed
+    pub filter: String,
+    // Entries modified within this are left out altogether
+    pub older_than: Option<Duration>,
+    // Entries smaller than this are hidden while `hide_small` is on
+    pub min_size: Option<u64>,
+    pub hide_small: bool,
+    pub sort: Sort,
+    // Sort the other way around
+    pub sort_reversed: bool,
+    // List entries on the fullest filesystem first
+    pub group_by_filesystem: bool,
+    // Rows show project names and shortened paths instead of full paths
+    pub show_names: bool,
+    // Paths of entries kept at the top of the list and out of bulk actions
+    pub pinned: HashSet<PathBuf>,
+    // Paths an interrupted bulk delete didn't get to, marked when found
+    pub resume: HashSet<PathBuf>,
+    // Filesystems entries are on, fullest first
+    pub filesystems: Vec<Filesystem>,
+    // Keys bound to list actions instead of the defaults
+    pub keys: Keys,
+    // Show deleted entries struck through for a moment instead of removing them right away
+    pub tombstones: bool,
+    // Sizes from which the size column turns yellow and red
+    pub size_colors: (u64, u64),
+    pub theme: Theme,
+    // Projects excluded from scans with `x`, kept across runs
+    pub excluded: Vec<PathBuf>,
+    // Tree view while it's open, kept through its delete dialogs
+    pub tree: Option<Tree>,
+    pub open_projects: OpenProjects,
+    // Projects with uncommitted changes or commits this recent are flagged
+    // and left out of bulk deletes
+    pub active_within: Option<Duration>,
+    // Editor `e` runs, with its arguments
+    pub editor: String,
+    // Key the scan is cached by, None if it isn't
+    pub cache: Option<String>,
+    // Paths of cached targets the scan hasn't found again yet
+    pub cached: HashSet<PathBuf>,
+    // Key the scan is remembered by in history, None if it isn't
+    pub history: Option<String>,
+    // IDs of targets flagged for review later, kept in the history file.
+    // None without history.
+    pub later: Option<HashSet<String>>,
+    // Notes on targets by their IDs, kept in the history file too
+    pub notes: Option<HashMap<String, String>>,
+    // What changed since the last scan of the same folders
+    pub changes: Option<Changes>,
+    // Total size of every remembered scan of the same folders, oldest first
+    pub trend: Vec<(SystemTime, u64)>,
+    // Where to write a report on exit
+    pub report: Option<(ReportFormat, String)>,
+    // Cleans up targets one at a time in the background
+    pub deleter: Deleter,
+    // Deletes sent to the deleter that aren't all done yet
+    pub batch: Option<Batch>,
+    // Entries deleted this session
+    pub deleted: Vec<Entry>,
+    // Entries this session put in trash and that are still there
+    pub trashed: Vec<Entry>,
+    // Trashed entries that can be put back, latest last
+    pub undo: Vec<Undo>,
+    // Show details pane
+    pub show_details: bool,
+    // Details of the selected entry
+    pub details: Option<Details>,
+    // Feedback from actions, shown in the status line
+    pub messages: Messages,
+    // Free space on the filesystem of the first root when the session
+    // started, for the summary
+    pub free_at_start: Option<u64>,
+    // Budget for all targets, the ones over it get marked after scanning
+    pub max_total: Option<u64>,
+    // Bytes of targets deleted, cleaned or emptied from trash this session
+    pub freed: u64,
+    // Errors of actions that failed this session
+    pub failures: Vec<String>,
+    // What the terminal can show
+    pub caps: Caps,
 }
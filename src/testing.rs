@@ -0,0 +1,51 @@
+//! Folders built in the temp folder for unit tests, like `tests/common` does
+//! for the integration tests
+
+use std::path::{Path, PathBuf};
+
+/// Folder of its own for a test, removed again when dropped
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new(name: &str) -> Fixture {
+        let root = std::env::temp_dir().join(format!("rclin-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        // Real path, the temp folder can be behind a symlink like on macOS
+        let root = root.canonicalize().unwrap();
+        Fixture { root }
+    }
+
+    pub fn path(&self, relative: &str) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Write `content` to `relative`, creating the folders above it
+    pub fn file(&self, relative: &str, content: &str) -> PathBuf {
+        let path = self.path(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Rust project in `relative` with a manifest and a `target/debug`
+    /// folder. Returns the target.
+    pub fn project(&self, relative: &str) -> PathBuf {
+        let name = Path::new(relative).file_name().unwrap().to_str().unwrap();
+        self.file(
+            &format!("{relative}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\n"),
+        );
+        let target = self.path(&format!("{relative}/target"));
+        std::fs::create_dir_all(target.join("debug")).unwrap();
+        target
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{str::FromStr, time::Duration};
+
+use tui::style::{Color, Modifier, Style};
+
+/// Parts of the interface that can be styled, with their default styles
+pub const ELEMENTS: &[(&str, &str)] = &[
+    ("selected", "+reversed"),
+    ("marked", "cyan"),
+    ("pinned", "magenta"),
+    // Sizes by the thresholds of --size-colors
+    ("small", "green"),
+    ("medium", "yellow"),
+    ("large", "red"),
+    // Targets unused for long or not used by cargo anymore
+    ("stale", "darkgray"),
+    // Roots, paths by name and minor labels
+    ("dimmed", "darkgray"),
+    // Labels worth a look, like open in editor
+    ("warning", "yellow"),
+    // Keys, notes and review later flags
+    ("info", "cyan"),
+    ("error", "red"),
+    ("success", "green"),
+];
+
+/// Targets not used for this long are dimmed as stale
+pub const STALE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Styles of the interface, the defaults unless set otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub selected: Style,
+    pub marked: Style,
+    pub pinned: Style,
+    pub small: Style,
+    pub medium: Style,
+    pub large: Style,
+    pub stale: Style,
+    pub dimmed: Style,
+    pub warning: Style,
+    pub info: Style,
+    pub error: Style,
+    pub success: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        let mut theme = Theme {
+            selected: Style::default(),
+            marked: Style::default(),
+            pinned: Style::default(),
+            small: Style::default(),
+            medium: Style::default(),
+            large: Style::default(),
+            stale: Style::default(),
+            dimmed: Style::default(),
+            warning: Style::default(),
+            info: Style::default(),
+            error: Style::default(),
+            success: Style::default(),
+        };
+        for (element, style) in ELEMENTS {
+            *theme.element(element).expect("elements have fields") =
+                parse_style(style).expect("default styles parse");
+        }
+        theme
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    // Comma separated `element=style` pairs, e.g. `selected=black:cyan,marked=blue`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (element, style) = pair
+                .split_once('=')
+                .ok_or(format!("expected element=style, found '{pair}'"))?;
+            let element = element.trim();
+            let names: Vec<&str> = ELEMENTS.iter().map(|(name, _)| *name).collect();
+            *theme.element(element).ok_or(format!(
+                "unknown element '{element}', expected one of {}",
+                names.join(", ")
+            ))? = parse_style(style.trim())?;
+        }
+        Ok(theme)
+    }
+}
+
+impl Theme {
+    fn element(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "selected" => &mut self.selected,
+            "marked" => &mut self.marked,
+            "pinned" => &mut self.pinned,
+            "small" => &mut self.small,
+            "medium" => &mut self.medium,
+            "large" => &mut self.large,
+            "stale" => &mut self.stale,
+            "dimmed" => &mut self.dimmed,
+            "warning" => &mut self.warning,
+            "info" => &mut self.info,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            _ => return None,
+        })
+    }
+}
+
+// Style like `yellow`, `black:cyan` for a background too, `:blue` for only a
+// background, with modifiers after it like `red+bold` or `+reversed`
+fn parse_style(s: &str) -> Result<Style, String> {
+    let mut parts = s.split('+');
+    let colors = parts.next().unwrap_or_default();
+    let mut style = Style::default();
+
+    let (fg, bg) = colors.split_once(':').unwrap_or((colors, ""));
+    if !fg.is_empty() {
+        style = style.fg(parse_color(fg)?);
+    }
+    if !bg.is_empty() {
+        style = style.bg(parse_color(bg)?);
+    }
+    for modifier in parts {
+        style = style.add_modifier(match modifier {
+            "bold" => Modifier::BOLD,
+            "dim" => Modifier::DIM,
+            "italic" => Modifier::ITALIC,
+            "underlined" => Modifier::UNDERLINED,
+            "reversed" => Modifier::REVERSED,
+            _ => {
+                return Err(format!(
+                "unknown modifier '{modifier}', expected bold, dim, italic, underlined or reversed"
+            ))
+            }
+        });
+    }
+    Ok(style)
+}
+
+// Color by name, 256 color index or `#rrggbb`
+fn parse_color(s: &str) -> Result<Color, String> {
+    let color = match s.to_ascii_lowercase().as_str() {
+        "default" | "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        other => match other.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 => {
+                let rgb =
+                    u32::from_str_radix(hex, 16).map_err(|_| format!("invalid color '{s}'"))?;
+                Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+            }
+            Some(_) => return Err(format!("invalid color '{s}', expected #rrggbb")),
+            None => Color::Indexed(other.parse().map_err(|_| format!("unknown color '{s}'"))?),
+        },
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_styles() {
+        let theme: Theme = "selected=black:cyan+bold, marked=#ff8000, stale=244"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            theme.selected,
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(theme.marked, Style::default().fg(Color::Rgb(255, 128, 0)));
+        assert_eq!(theme.stale, Style::default().fg(Color::Indexed(244)));
+        assert_eq!(theme.pinned, Theme::default().pinned);
+        assert!("unknown=red".parse::<Theme>().is_err());
+        assert!("marked=purple".parse::<Theme>().is_err());
+        assert!("marked=red+blink".parse::<Theme>().is_err());
+    }
+}
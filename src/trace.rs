@@ -0,0 +1,92 @@
+use std::{
+    fs::File,
+    io::{IsTerminal, Stderr, Write},
+    sync::Mutex,
+};
+
+use tracing::{level_filters::LevelFilter, Level};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{filter::Targets, fmt, prelude::*};
+
+// Trace is written out when this is dropped
+static GUARD: Mutex<Option<FlushGuard>> = Mutex::new(None);
+
+/// Where spans and events are recorded
+pub struct Logging {
+    // Chrome trace of the scanner, sizer and deletes
+    pub trace: Option<File>,
+    // Events on stderr: 1 for found targets, warnings and deletes, 2 for
+    // every folder skipped too
+    pub verbose: usize,
+    // Deletes and cleans, appended with times
+    pub log_file: Option<File>,
+    // Whether stderr is free, it's not while the interface draws on it
+    pub stderr_free: fn() -> bool,
+}
+
+/// Start recording as `logging` says. The Chrome trace opens in
+/// chrome://tracing or <https://ui.perfetto.dev>.
+pub fn start(logging: Logging) {
+    let chrome = logging.trace.map(|file| {
+        let (layer, guard) = ChromeLayerBuilder::new()
+            .writer(file)
+            .include_args(true)
+            .build();
+        *GUARD.lock().unwrap() = Some(guard);
+        layer
+    });
+
+    let level = match logging.verbose {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::INFO,
+        _ => LevelFilter::DEBUG,
+    };
+    let free = logging.stderr_free;
+    let stderr = fmt::layer()
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_writer(move || Quiet((free)().then(std::io::stderr)))
+        .with_filter(level);
+
+    let audit = logging.log_file.map(|file| {
+        fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+            .with_filter(Targets::new().with_target("rclin::delete", Level::INFO))
+    });
+
+    tracing_subscriber::registry()
+        .with(chrome)
+        .with(stderr)
+        .with(audit)
+        .init();
+}
+
+/// Finish the trace file, if tracing. Must be called before exiting.
+pub fn finish() {
+    drop(GUARD.lock().unwrap().take());
+}
+
+/// Exit with `code` after finishing the trace
+pub fn exit(code: i32) -> ! {
+    finish();
+    std::process::exit(code);
+}
+
+// Stderr, or nowhere while it's not free
+struct Quiet(Option<Stderr>);
+
+impl Write for Quiet {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.0 {
+            Some(stderr) => stderr.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.0 {
+            Some(stderr) => stderr.flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use crate::{
+    disk,
+    error::{self, Error},
+    i18n::{t, tf},
+    quarantine,
+    scan::Entry,
+    wsl,
+};
+
+// Rough rate of deleting files. Removing a folder costs a syscall or two per
+// file, trashing across filesystems or to the recycle bin costs a copy too.
+const FILES_PER_SEC: u64 = 10_000;
+
+/// Bulk deletes estimated to take longer than this ask for confirmation
+pub const SLOW: Duration = Duration::from_secs(60);
+
+/// Rough time deleting `files` files takes
+pub fn estimate(files: u64) -> Duration {
+    Duration::from_secs(files / FILES_PER_SEC)
+}
+
+/// Estimate as text, e.g. `about 5 min`
+pub fn format_estimate(time: Duration) -> String {
+    let minutes = time.as_secs() / 60;
+    match minutes {
+        0 => t("under a minute").to_string(),
+        1..=59 => tf("about {} min", &[&minutes]),
+        _ => tf("about {} h {} min", &[&(minutes / 60), &(minutes % 60)]),
+    }
+}
+
+/// Where a trashed folder went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trashed {
+    Trash,
+    RecycleBin,
+    // Moved into the quarantine folder instead of the trash
+    Quarantine,
+    // Trash can't be used, so it was deleted for good
+    Deleted,
+}
+
+static QUARANTINE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Move trashed folders into `dir` instead of the trash, for where the trash
+/// doesn't work, like NFS homes and containers. Once before trashing anything.
+pub fn set_quarantine(dir: PathBuf) {
+    let _ = QUARANTINE.set(dir);
+}
+
+/// Folder trashed folders are moved into instead of the trash, if any
+pub fn quarantine() -> Option<&'static Path> {
+    QUARANTINE.get().map(PathBuf::as_path)
+}
+
+// Send `path` to the trash. Linux trash on a Windows drive under WSL is a
+// hidden folder Windows never empties, so there it goes to the Windows recycle
+// bin, or is deleted when that can't be reached. Network shares have no
+// recycle bin, Windows would delete it anyway. With a quarantine it goes there
+// wherever it is.
+fn trash(path: &Path) -> error::Result<Trashed> {
+    if let Some(dir) = quarantine() {
+        quarantine::put(dir, path).map_err(|source| Error::Quarantine {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        return Ok(Trashed::Quarantine);
+    }
+    if disk::is_network(path) {
+        return delete(path);
+    }
+    if !wsl::is_windows_drive(path) {
+        trash::delete(path).map_err(|source| Error::Trash {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        return Ok(Trashed::Trash);
+    }
+
+    if wsl::can_recycle() {
+        wsl::recycle(path).map_err(|source| Error::Recycle {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Trashed::RecycleBin)
+    } else {
+        delete(path)
+    }
+}
+
+// Delete `path` for good, a folder with everything in it
+fn delete(path: &Path) -> error::Result<Trashed> {
+    let mut removed = delete_once(path);
+    // On Windows read-only files can't be deleted, and files a virus scanner,
+    // the search indexer or a share still holds open only go a moment later
+    for wait in RETRIES {
+        match &removed {
+            Err(e) if cfg!(windows) && is_transient(e) => (),
+            _ => break,
+        }
+        std::thread::sleep(wait);
+        clear_readonly(path);
+        removed = match delete_once(path) {
+            // What was left went after all
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            other => other,
+        };
+    }
+    removed.map_err(|source| Error::Delete {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(Trashed::Deleted)
+}
+
+fn delete_once(path: &Path) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if !meta.is_dir() => std::fs::remove_file(path),
+        _ => std::fs::remove_dir_all(path),
+    }
+}
+
+// Waits before trying to delete again on Windows
+const RETRIES: [Duration; 3] = [
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(2),
+];
+
+// Access denied, a sharing violation or a folder not empty yet because
+// something inside is still being deleted
+fn is_transient(e: &std::io::Error) -> bool {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_DIR_NOT_EMPTY: i32 = 145;
+
+    e.kind() == ErrorKind::PermissionDenied
+        || matches!(
+            e.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_DIR_NOT_EMPTY)
+        )
+}
+
+// Clear the read-only attribute of everything in `path`, which keeps files
+// from being deleted on Windows. Sources checked out from git often have it.
+#[cfg(windows)]
+fn clear_readonly(path: &Path) {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return;
+    };
+    if meta.is_dir() && !meta.is_symlink() {
+        for entry in std::fs::read_dir(path).into_iter().flatten().flatten() {
+            clear_readonly(&entry.path());
+        }
+    }
+    let mut permissions = meta.permissions();
+    if permissions.readonly() {
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        let _ = std::fs::set_permissions(path, permissions);
+    }
+}
+
+#[cfg(not(windows))]
+fn clear_readonly(_path: &Path) {}
+
+/// Trash the folder of `entry` like `trash`, or with `permanent` delete it
+/// for good. Excluded folders inside it stay where they are with the folders
+/// leading to them, only what's around them goes, which is what its size
+/// counted.
+pub fn remove(entry: &Entry, permanent: bool) -> error::Result<Trashed> {
+    remove_around(&entry.path, &entry.kept, permanent)
+}
+
+fn remove_around(path: &Path, kept: &[PathBuf], permanent: bool) -> error::Result<Trashed> {
+    if !kept.iter().any(|k| k.starts_with(path)) {
+        return if permanent { delete(path) } else { trash(path) };
+    }
+
+    let mut done = if permanent {
+        Trashed::Deleted
+    } else {
+        Trashed::Trash
+    };
+    let dir = std::fs::read_dir(path).map_err(|source| Error::Delete {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    for child in dir.flatten() {
+        let child = child.path();
+        if !kept.contains(&child) {
+            done = remove_around(&child, kept, permanent)?;
+        }
+    }
+    Ok(done)
+}
+
+/// Why trashing folders under `roots` may delete them for good instead,
+/// which is worth a warning
+pub fn deletes_instead(roots: &[PathBuf]) -> Option<&'static str> {
+    if quarantine().is_some() {
+        None
+    } else if roots.iter().any(|root| disk::is_network(root)) {
+        Some("Network shares have no recycle bin, trashing there deletes permanently")
+    } else if roots.iter().any(|root| wsl::is_windows_drive(root)) && !wsl::can_recycle() {
+        Some("Without PowerShell, trashing on Windows drives under WSL deletes permanently")
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is a trash folder: the desktop trash in the home folder,
+/// `.Trash-<uid>` or `.Trash` at the top of other filesystems, `.Trashes` on
+/// macOS drives, the Windows recycle bin or the quarantine. Targets in there
+/// were trashed already.
+pub fn is_trash(path: &Path) -> bool {
+    if quarantine().is_some_and(|dir| dir == path) {
+        return true;
+    }
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    match name {
+        ".Trash" | ".Trashes" => true,
+        "Trash" => home_trash().is_some_and(|trash| trash == path),
+        _ if name.eq_ignore_ascii_case("$Recycle.Bin") => true,
+        _ => name
+            .strip_prefix(".Trash-")
+            .is_some_and(|uid| !uid.is_empty() && uid.bytes().all(|b| b.is_ascii_digit())),
+    }
+}
+
+// Trash of the desktop, `$XDG_DATA_HOME/Trash`
+fn home_trash() -> Option<PathBuf> {
+    let data = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(data.join("Trash"))
+}
+
+/// Trash items holding `deleted` entries, the latest one for each path
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn items(deleted: &[Entry]) -> Result<Vec<trash::TrashItem>, trash::Error> {
+    let mut items: Vec<trash::TrashItem> = vec![];
+
+    for item in trash::os_limited::list()? {
+        let path = item.original_path();
+        // What was around excluded folders was trashed piece by piece
+        if !deleted
+            .iter()
+            .any(|e| *path == e.path || (!e.kept.is_empty() && path.starts_with(&e.path)))
+        {
+            continue;
+        }
+
+        match items.iter_mut().find(|i| i.original_path() == path) {
+            Some(existing) if existing.time_deleted < item.time_deleted => *existing = item,
+            Some(_) => (),
+            None => items.push(item),
+        }
+    }
+
+    Ok(items)
+}
+
+// Quarantined folders holding `deleted` entries, the latest one for each path
+fn quarantined(dir: &Path, deleted: &[Entry]) -> Vec<quarantine::Item> {
+    let mut items: Vec<quarantine::Item> = vec![];
+    for item in quarantine::items(dir) {
+        if !deleted.iter().any(|e| {
+            item.origin == e.path || (!e.kept.is_empty() && item.origin.starts_with(&e.path))
+        }) {
+            continue;
+        }
+        // Oldest first, so later ones replace earlier ones
+        items.retain(|i| i.origin != item.origin);
+        items.push(item);
+    }
+    items
+}
+
+/// Permanently remove `deleted` entries from the trash, or the quarantine.
+/// Returns how many items were purged.
+#[tracing::instrument(skip_all, fields(count = deleted.len()))]
+pub fn empty(deleted: &[Entry]) -> Result<usize, String> {
+    let Some(dir) = quarantine() else {
+        return empty_trash(deleted);
+    };
+    let items = quarantined(dir, deleted);
+    for item in &items {
+        quarantine::purge(item).map_err(|e| e.to_string())?;
+    }
+    Ok(items.len())
+}
+
+/// Put `entry` back where it was trashed from
+#[tracing::instrument(skip_all, fields(path = %entry.path.display()))]
+pub fn restore(entry: &Entry) -> Result<(), String> {
+    let Some(dir) = quarantine() else {
+        return restore_trash(entry);
+    };
+    let items = quarantined(dir, std::slice::from_ref(entry));
+    if items.is_empty() {
+        return Err(t("it is not in the quarantine anymore").to_string());
+    }
+    for item in &items {
+        quarantine::restore(item).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn empty_trash(deleted: &[Entry]) -> Result<usize, String> {
+    let items = items(deleted).map_err(|e| e.to_string())?;
+    let count = items.len();
+    trash::os_limited::purge_all(items).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn restore_trash(entry: &Entry) -> Result<(), String> {
+    let items = items(std::slice::from_ref(entry)).map_err(|e| e.to_string())?;
+    if items.is_empty() {
+        return Err(t("it is not in the trash anymore").to_string());
+    }
+    trash::os_limited::restore_all(items).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn restore_trash(_entry: &Entry) -> Result<(), String> {
+    Err("restoring from the trash is not supported on this platform".to_string())
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn empty_trash(_deleted: &[Entry]) -> Result<usize, String> {
+    Err("emptying the trash is not supported on this platform".to_string())
+}
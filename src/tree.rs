@@ -0,0 +1,204 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use tui::widgets::ListState;
+
+use crate::scan::Entry;
+
+/// Folder holding targets of several projects, or a target
+pub struct Node {
+    pub path: PathBuf,
+    // Path from the folder above it in the tree
+    pub label: String,
+    pub depth: usize,
+    pub size: u64,
+    // Paths of the targets at or below it
+    pub targets: Vec<PathBuf>,
+    pub is_target: bool,
+}
+
+/// Results grouped under the folders they have in common, for monorepos
+/// with many nested projects. Folders can be collapsed.
+#[derive(Default)]
+pub struct Tree {
+    pub collapsed: HashSet<PathBuf>,
+    pub state: ListState,
+}
+
+impl Tree {
+    /// Nodes on screen, those inside collapsed folders left out
+    pub fn rows<'a>(&self, nodes: &'a [Node]) -> Vec<&'a Node> {
+        let mut rows = vec![];
+        let mut hidden_below: Option<usize> = None;
+        for node in nodes {
+            if hidden_below.is_some_and(|depth| node.depth > depth) {
+                continue;
+            }
+            hidden_below =
+                (!node.is_target && self.collapsed.contains(&node.path)).then_some(node.depth);
+            rows.push(node);
+        }
+        rows
+    }
+
+    /// Collapse the selected folder, or expand it if it's collapsed
+    pub fn toggle(&mut self, rows: &[&Node]) {
+        let Some(node) = self.state.selected().and_then(|i| rows.get(i)) else {
+            return;
+        };
+        if !node.is_target && !self.collapsed.remove(&node.path) {
+            self.collapsed.insert(node.path.clone());
+        }
+    }
+
+    /// Select the row at `i`, or the last one if there aren't that many
+    pub fn select(&mut self, i: usize, rows: usize) {
+        self.state
+            .select(rows.checked_sub(1).map(|last| i.min(last)));
+    }
+}
+
+/// Tree of `entries` under each of `roots`. With a single root its folder is
+/// left out, the tree starts below it.
+pub fn nodes(roots: &[PathBuf], entries: &[&Entry]) -> Vec<Node> {
+    let mut nodes = vec![];
+    for root in roots {
+        let under: Vec<&Entry> = entries
+            .iter()
+            .filter(|e| e.path.starts_with(root))
+            .copied()
+            .collect();
+        if under.is_empty() {
+            continue;
+        }
+        let depth = if roots.len() > 1 {
+            nodes.push(folder(root, root.display().to_string(), 0, &under));
+            1
+        } else {
+            0
+        };
+        build(root, &under, depth, &mut nodes);
+    }
+    nodes
+}
+
+// Nodes of `entries` below `prefix`, biggest first. Entries sharing the next
+// folder are grouped under the deepest folder they all are in.
+fn build(prefix: &Path, entries: &[&Entry], depth: usize, nodes: &mut Vec<Node>) {
+    let mut groups: BTreeMap<Option<OsString>, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        let next = entry
+            .path
+            .strip_prefix(prefix)
+            .ok()
+            .and_then(|rest| rest.iter().next())
+            .map(OsString::from);
+        groups.entry(next).or_default().push(entry);
+    }
+    let mut groups: Vec<Vec<&Entry>> = groups.into_values().collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.iter().map(|e| e.size).sum::<u64>()));
+
+    for group in groups {
+        if let [entry] = group[..] {
+            nodes.push(Node {
+                path: entry.path.clone(),
+                label: relative(&entry.path, prefix),
+                depth,
+                size: entry.size,
+                targets: vec![entry.path.clone()],
+                is_target: true,
+            });
+            continue;
+        }
+        // At least the next folder, they all are in it
+        let common = common_ancestor(group.iter().map(|e| e.path.as_path()));
+        nodes.push(folder(&common, relative(&common, prefix), depth, &group));
+        build(&common, &group, depth + 1, nodes);
+    }
+}
+
+fn folder(path: &Path, label: String, depth: usize, entries: &[&Entry]) -> Node {
+    Node {
+        path: path.to_path_buf(),
+        label,
+        depth,
+        size: entries.iter().map(|e| e.size).sum(),
+        targets: entries.iter().map(|e| e.path.clone()).collect(),
+        is_target: false,
+    }
+}
+
+// Deepest folder all of `paths` are in
+fn common_ancestor<'a>(mut paths: impl Iterator<Item = &'a Path>) -> PathBuf {
+    let Some(first) = paths.next() else {
+        return PathBuf::new();
+    };
+    let mut common = first.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&common) {
+            if !common.pop() {
+                break;
+            }
+        }
+    }
+    common
+}
+
+fn relative(path: &Path, prefix: &Path) -> String {
+    match path.strip_prefix(prefix) {
+        Ok(rest) if !rest.as_os_str().is_empty() => rest.display().to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scan::Kind, size::Stats};
+
+    fn entry(path: &str, size: u64) -> Entry {
+        let stats = Stats {
+            size,
+            modified: None,
+            files: 1,
+            shared: 0,
+            skipped: vec![],
+        };
+        Entry::with_stats(path.into(), path.into(), Kind::Rust, stats)
+    }
+
+    #[test]
+    fn groups_under_shared_folders() {
+        let entries = [
+            entry("/m/mono/crates/a/target", 10),
+            entry("/m/mono/crates/b/target", 20),
+            entry("/m/mono/tools/c/target", 5),
+            entry("/m/solo/target", 40),
+        ];
+        let entries: Vec<&Entry> = entries.iter().collect();
+        let nodes = nodes(&[PathBuf::from("/m")], &entries);
+        let shown: Vec<(&str, usize, u64)> = nodes
+            .iter()
+            .map(|n| (n.label.as_str(), n.depth, n.size))
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                ("solo/target", 0, 40),
+                ("mono", 0, 35),
+                ("crates", 1, 30),
+                ("b/target", 2, 20),
+                ("a/target", 2, 10),
+                ("tools/c/target", 1, 5),
+            ]
+        );
+
+        let mut tree = Tree::default();
+        tree.collapsed.insert(PathBuf::from("/m/mono/crates"));
+        let rows: Vec<&str> = tree.rows(&nodes).iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(rows, ["solo/target", "mono", "crates", "tools/c/target"]);
+    }
+}
@@ -0,0 +1,1321 @@
+use std::{
+    collections::BTreeMap,
+    io::Stdout,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Sparkline, Wrap},
+    Frame,
+};
+
+use crate::{
+    app::{cargo_args, files, listed, paths_of},
+    browser::{Browser, Preview},
+    caps::Degrade,
+    deleter::Cleanup,
+    details::Details,
+    disk::Filesystem,
+    editors::OpenProjects,
+    i18n::{t, tf},
+    keys, remote, report,
+    scan::{Entry, Kind},
+    size,
+    state::{Confirm, Duplicates, Events, Mode, Profiles, Sort, State},
+    theme::{self, Theme},
+    trashcan,
+    tree::{self, Tree},
+    wsl,
+};
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long deleted entries stay in the list
+pub const TOMBSTONE: Duration = Duration::from_millis(1500);
+
+/// The list and whatever the mode shows over it, with the status line
+pub fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, state: &mut State, events: &mut Events) {
+    let size = f.size();
+    let actions = actions(state);
+    let actions_block = Block::default().title(t("Actions")).borders(Borders::ALL);
+    // Wrap key help on narrow terminals instead of cutting it off
+    let actions_lines = wrapped_lines(&actions, size.width.saturating_sub(2)).min(3);
+
+    // Search bar between the list and the footer while typing the filter
+    let search_lines = if matches!(state.mode, Mode::Search(_)) {
+        3
+    } else {
+        0
+    };
+
+    // The list gets what's left, on tiny terminals that may be nothing
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(search_lines),
+            Constraint::Length(1),
+            Constraint::Length(actions_lines + 2),
+            Constraint::Length(1),
+        ])
+        .split(size);
+    let (mut list_rect, search_rect, actions_rect) = (rows[0], rows[1], rows[3]);
+    // Footer and status line start a column in, like the text in boxes
+    let indent = |rect: Rect| Rect {
+        x: rect.x + 1.min(rect.width),
+        width: rect.width.saturating_sub(1),
+        ..rect
+    };
+    let (footer_rect, status_rect) = (indent(rows[2]), indent(rows[4]));
+    let paragraph_rect = actions_block.inner(actions_rect);
+    events.hints = hints(&actions, paragraph_rect);
+
+    if state.show_details {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(3, 5), Constraint::Ratio(2, 5)])
+            .split(list_rect);
+        let details_rect = columns[1];
+        list_rect = columns[0];
+        let note = state
+            .notes
+            .as_ref()
+            .and_then(|n| n.get(&events.selected()?.id));
+        draw_details(
+            f,
+            &mut state.details,
+            note,
+            &state.theme,
+            events,
+            details_rect,
+        );
+    }
+
+    draw_list(f, state, events, list_rect);
+    if search_lines > 0 {
+        let search = Paragraph::new(format!("{}_", state.filter))
+            .block(Block::default().title(t("Filter")).borders(Borders::ALL));
+        f.render_widget(search, search_rect);
+    }
+    f.render_widget(footer(state, events), footer_rect);
+    f.render_widget(actions_block, actions_rect);
+    let paragraph = Paragraph::new(actions.as_str()).wrap(Wrap { trim: true });
+    f.render_widget(paragraph, paragraph_rect);
+    if let Some(status) = status(state) {
+        f.render_widget(status, status_rect);
+    }
+    draw_over(f, state, list_rect);
+    f.render_widget(Degrade(state.caps), size);
+}
+
+// The list of targets in its box, or the pane of the mode in its place
+fn draw_list(
+    f: &mut Frame<CrosstermBackend<Stdout>>,
+    state: &mut State,
+    events: &mut Events,
+    rect: Rect,
+) {
+    // Keep the selected row in view even when no rows fit
+    events.scroll(usize::from(rect.height.saturating_sub(2)).max(1));
+    events.list_area = rect;
+
+    if let Mode::Browse(browser) = &mut state.mode {
+        draw_browser(f, browser, rect);
+    } else if let Mode::Excluded(list) = &mut state.mode {
+        draw_excluded(f, &state.excluded, list, rect);
+    } else if let Mode::Profiles(profiles) = &mut state.mode {
+        draw_profiles(f, profiles, rect);
+    } else if let Mode::Duplicates(duplicates) = &mut state.mode {
+        draw_duplicates(f, duplicates, rect);
+    } else if let Some(mut tree) = state.tree.take() {
+        draw_tree(f, state, &mut tree, rect);
+        state.tree = Some(tree);
+    } else {
+        let items: Vec<ListItem> = events
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, e)| row(state, events, i, e))
+            .collect();
+        let block = Block::default()
+            .title(title(state, events))
+            .borders(Borders::ALL);
+        let list = List::new(items)
+            .block(block)
+            .style(Style::default())
+            .highlight_style(state.theme.selected)
+            .highlight_symbol(">>");
+        f.render_stateful_widget(list, rect, &mut events.state);
+    }
+}
+
+// What was found and how the list is narrowed, for the box around it
+fn title(state: &State, events: &Events) -> String {
+    let found = tf(
+        "{} target folders, {}",
+        &[
+            &state.results.len(),
+            &size::format_size(state.results.iter().map(|e| e.size).sum()),
+        ],
+    );
+    let mut title = match &state.scan {
+        Some((_, _, progress)) => {
+            let elapsed = state.started.elapsed();
+            let spinner = SPINNER[elapsed.as_millis() as usize / 100 % SPINNER.len()];
+            let seconds = format!("{:.0}", elapsed.as_secs_f32());
+            match remote::get() {
+                Some(remote) => tf(
+                    "Scanning {} {} found {} ({}s)",
+                    &[&spinner, remote, &found, &seconds],
+                ),
+                None => tf(
+                    "Scanning {} found {} in {} folders ({}s)",
+                    &[&spinner, &found, &progress.dirs(), &seconds],
+                ),
+            }
+        }
+        None => tf("Found {} ({}s)", &[&found, &format!("{:.2}", state.time)]),
+    };
+    if let Some(remote) = remote::get().filter(|_| state.scan.is_none()) {
+        title.push_str(&tf(" on {}", &[&remote.host]));
+    }
+    if state.cancelled {
+        title.push_str(t(", scan cancelled"));
+    }
+    if !state.warnings.is_empty() {
+        title.push_str(&tf(", {} folders unreadable", &[&state.warnings.len()]));
+    }
+    if let Some(min) = state.min_size.filter(|_| state.hide_small) {
+        let hidden = state.results.iter().filter(|e| e.size < min).count();
+        if hidden > 0 {
+            title.push_str(&tf(
+                ", {} under {} hidden",
+                &[&hidden, &size::format_size(min)],
+            ));
+        }
+    }
+    if let Some(changes) = state.changes {
+        let growth = size::format_size(changes.growth.unsigned_abs());
+        let sign = if changes.growth < 0 { "-" } else { "+" };
+        let since = match changes.since.as_secs() / (24 * 60 * 60) {
+            0 => t("earlier today").to_string(),
+            1 => t("yesterday").to_string(),
+            days => tf("{} days ago", &[&days]),
+        };
+        title.push_str(&tf(
+            ", {} new targets, {}{} since {}",
+            &[&changes.new, &sign, &growth, &since],
+        ));
+    }
+    if !state.filter.is_empty() {
+        title.push_str(&tf(
+            ", filter \"{}\" lists {}, bulk actions only use those",
+            &[&state.filter, &events.items.len()],
+        ));
+    }
+    if state.freed > 0 {
+        title.push_str(&tf(
+            ", freed {} this session",
+            &[&size::format_size(state.freed)],
+        ));
+    }
+    title
+}
+
+// Row `i` of the list, showing `e`
+fn row(state: &State, events: &Events, i: usize, e: &Entry) -> ListItem<'static> {
+    // Rows tell their filesystem when there are several, and shade the root
+    // they were found in
+    let filesystems = state.filesystems.len() > 1;
+    let roots = state.roots.len() > 1;
+    let theme = state.theme;
+    // Rows on screen can be picked with number keys
+    let number = match i.checked_sub(events.offset) {
+        Some(row @ 0..=8) => format!("{} ", row + 1),
+        _ => "  ".to_string(),
+    };
+    if let Some(deleted) = events.tombstones.get(&e.path) {
+        return tombstone(e, deleted.elapsed());
+    }
+    let size = Span::styled(
+        format!("{:>9}", size::format_size(e.size)),
+        size_style(e.size, state.size_colors, &theme),
+    );
+    // By name the whole path is dimmed, shortened with `~` instead of the root
+    let shown = e.path.display().to_string();
+    let root = roots
+        .then(|| root_of(&state.roots, e))
+        .flatten()
+        .filter(|_| !state.show_names)
+        .map(|i| state.roots[i].display().to_string())
+        .filter(|root| shown.starts_with(root.as_str()));
+    let (rest, dimmed) = if state.show_names {
+        let dash = if state.caps.unicode { " — " } else { " - " };
+        (e.name(), format!("{dash}{}", tilde(&e.path)))
+    } else {
+        let rest = root.as_ref().map_or(shown.as_str(), |r| &shown[r.len()..]);
+        (rest.to_string(), String::new())
+    };
+    // Not used for long or not by cargo anymore
+    let stale = e.stale || e.age().is_some_and(|age| age > theme::STALE);
+    let age = format!("  {:>8}", last_used(e));
+    let (mark, path, age) = if state.pinned.contains(&e.path) {
+        ("[P] ", Span::styled(rest, theme.pinned), Span::raw(age))
+    } else if events.marked.contains(&e.path) {
+        ("[x] ", Span::styled(rest, theme.marked), Span::raw(age))
+    } else if stale {
+        (
+            "[ ] ",
+            Span::styled(rest, theme.stale),
+            Span::styled(age, theme.stale),
+        )
+    } else {
+        ("[ ] ", Span::raw(rest), Span::raw(age))
+    };
+    let mut spans = vec![
+        Span::raw(number),
+        Span::raw(mark),
+        size,
+        age,
+        Span::raw(format!("  {:<7}", e.kind.name())),
+        Span::styled(root.unwrap_or_default(), theme.dimmed),
+        path,
+        Span::styled(dimmed, theme.dimmed),
+    ];
+    if state.deleter.is_current(&e.path) {
+        let spinner = SPINNER[state.started.elapsed().as_millis() as usize / 100 % SPINNER.len()];
+        let working = if state.batch.as_ref().is_some_and(|b| b.cleaning) {
+            "  cleaning... {}"
+        } else {
+            "  deleting... {}"
+        };
+        let mut working = tf(working, &[&spinner]);
+        if state.caps.unicode {
+            working = working.replace("...", "…");
+        }
+        spans.push(Span::styled(working, theme.error));
+    } else if state.deleter.is_queued(&e.path) {
+        spans.push(Span::styled(t("  (waiting to be deleted)"), theme.dimmed));
+    } else if state.cached.contains(&e.path) {
+        spans.push(Span::styled(t("  (from last run)"), theme.dimmed));
+    } else if let Some(reason) = events.failed.get(&e.path) {
+        spans.push(Span::styled(tf("  (failed: {})", &[reason]), theme.error));
+    }
+    match &e.workspace {
+        Some(w) if w.member => spans.push(Span::styled(
+            tf("  (in workspace {}, not used by cargo)", &[&w.name]),
+            theme.warning,
+        )),
+        Some(w) => spans.push(Span::styled(
+            tf("  (workspace {})", &[&w.name]),
+            theme.dimmed,
+        )),
+        None => (),
+    }
+    if e.shared_dir {
+        spans.push(Span::styled(t("  (shared target-dir)"), theme.dimmed));
+    }
+    if e.stale {
+        spans.push(Span::styled(
+            t("  (stale, cargo builds elsewhere)"),
+            theme.warning,
+        ));
+    }
+    if let Some(fs) = filesystems.then(|| filesystem(state, e)).flatten() {
+        spans.push(Span::styled(
+            tf("  (on {})", &[&fs.mount.display()]),
+            theme.dimmed,
+        ));
+    }
+    if state.later.as_ref().is_some_and(|l| l.contains(&e.id)) {
+        spans.push(Span::styled(t("  (review later)"), theme.info));
+    }
+    if state.notes.as_ref().is_some_and(|n| n.contains_key(&e.id)) {
+        spans.push(Span::styled(t("  (note)"), theme.dimmed));
+    }
+    if e.open {
+        spans.push(Span::styled(t("  (open in editor)"), theme.warning));
+    }
+    if e.active {
+        spans.push(Span::styled(t("  (active in git)"), theme.warning));
+    }
+    if e.shared > 0 {
+        spans.push(Span::styled(
+            tf("  (frees only {})", &[&size::format_size(e.reclaimable())]),
+            theme.warning,
+        ));
+    }
+    ListItem::new(Spans::from(spans))
+}
+
+// Totals of what's listed and marked, below the list
+fn footer(state: &State, events: &Events) -> Paragraph<'static> {
+    let mut footer = totals(
+        events
+            .items
+            .iter()
+            .filter(|e| !events.is_tombstone(&e.path)),
+    );
+    if !state.filter.is_empty() {
+        footer.push_str(&tf("  |  Filter: {}", &[&state.filter]));
+    }
+    if state.roots.len() > 1 {
+        for (i, root) in state.roots.iter().enumerate() {
+            let size: u64 = events
+                .items
+                .iter()
+                .filter(|e| !events.is_tombstone(&e.path) && root_of(&state.roots, e) == Some(i))
+                .map(|e| e.size)
+                .sum();
+            footer.push_str(&format!(
+                "  |  {}: {}",
+                root.display(),
+                size::format_size(size)
+            ));
+        }
+    }
+    if state.group_by_filesystem {
+        for fs in &state.filesystems {
+            footer.push_str(&filesystem_summary(fs, &events.items));
+        }
+    } else if let Some(free) = free_summary(&state.filesystems, events) {
+        footer.push_str(&free);
+    }
+    if state.sort != Sort::Found {
+        footer.push_str(&tf(
+            "  |  Sorted by {}",
+            &[&t(state.sort.describe(state.sort_reversed))],
+        ));
+    }
+    // Marked entries the filter hides are left out of bulk actions
+    let marked: Vec<&Entry> = events
+        .items
+        .iter()
+        .filter(|e| events.marked.contains(&e.path) && !events.is_tombstone(&e.path))
+        .filter(|e| !state.pinned.contains(&e.path))
+        .collect();
+    if !marked.is_empty() {
+        footer.push_str(&tf(
+            "  |  Marked: {} ({})",
+            &[
+                &marked.len(),
+                &size::format_size(marked.iter().map(|e| e.size).sum()),
+            ],
+        ));
+    }
+    if let Some(checker) = state.checker.as_ref().filter(|c| c.pending > 0) {
+        footer.push_str(&tf("  |  Checking {} projects", &[&checker.pending]));
+    }
+    if !state.trashed.is_empty() {
+        let in_trash = state.trashed.iter().map(|e| e.size).sum();
+        footer.push_str(&state.keys.label(&tf(
+            "  |  In trash: {}, empty with (E)",
+            &[&size::format_size(in_trash)],
+        )));
+    }
+    Paragraph::new(footer)
+}
+
+// Keys of the mode, shown in the actions box
+fn actions(state: &State) -> String {
+    match &state.mode {
+        Mode::List => state.keys.label(t("Select (Up/Down) Page (PageUp/PageDown) First (Home) Last (End)  Mark (Space) Mark row (1-9) Mark all (v) Trash all (a) Trash selected/marked (Del) Delete permanently (D) Mark older than (O) Pin (P) Review later (r) Note (N) Exclude project (x) Excluded (X) Filter (/) Hide small (h) Sort by size (s) path (p) age (m) Group by filesystem (g) Names (n) Look inside (Enter) File manager (o) Editor (e) Details (Tab) Install binaries (i) Cargo clean (c) Light clean (l) Profiles (C) Shared dependencies (A) Undo trash (u) Trend (t) Tree (T) Rescan (R) Help (?) Quit (Esc)")),
+        Mode::Browse(_) => t("Select (Up/Down)  Open folder (Enter) Back (Backspace)").to_string(),
+        Mode::MarkOlder(input) => tf("Mark entries older than (days): {}_", &[input]),
+        Mode::Search(_) => t("Type to filter by path, crate or type, letters in order match too  Keep filter (Enter) Cancel (Esc)").to_string(),
+        Mode::Confirm(Confirm::SlowDelete(_)) => {
+            t("Trash (y) Delete permanently without trash (p) Cancel (n)").to_string()
+        }
+        Mode::Confirm(_) => t("Yes (y) No (n)").to_string(),
+        Mode::Trend | Mode::Help => t("Back (any key)").to_string(),
+        Mode::Note(input) => tf("Note: {}_  Save (Enter) Cancel (Esc)", &[input]),
+        Mode::Tree => t(
+            "Select (Up/Down)  Collapse/expand (Enter) Trash all in selected (Del) Back (Esc)",
+        )
+        .to_string(),
+        Mode::Excluded(_) => {
+            t("Select (Up/Down)  Include again (Del) Back (Esc)").to_string()
+        }
+        Mode::Profiles(_) => {
+            t("Select (Up/Down)  Clean or keep (Space) Clean the picked (Enter) Back (Esc)")
+                .to_string()
+        }
+        Mode::Duplicates(_) => t("Select (Up/Down)  Back (Esc)").to_string(),
+    }
+}
+
+// The latest message, or where the scan is so a long one doesn't look stuck
+fn status(state: &mut State) -> Option<Paragraph<'static>> {
+    if let Some(message) = state.messages.current() {
+        let style = if message.error {
+            state.theme.error
+        } else {
+            state.theme.success
+        };
+        Some(Paragraph::new(message.text.clone()).style(style))
+    } else if let Some((_, _, progress)) = &state.scan {
+        let current = progress.current().display().to_string();
+        Some(Paragraph::new(current).style(state.theme.dimmed))
+    } else {
+        None
+    }
+}
+
+// Dialogs and panes the mode shows over the list
+fn draw_over(f: &mut Frame<CrosstermBackend<Stdout>>, state: &State, rect: Rect) {
+    match &state.mode {
+        Mode::Confirm(confirm) => {
+            let mut text = question(state, confirm);
+            if let Some(preview) = state
+                .preview_of
+                .as_ref()
+                .filter(|_| !paths_of(confirm).is_empty())
+            {
+                text.push_str(&describe_preview(preview));
+            }
+            draw_dialog(f, &text, rect);
+        }
+        Mode::Trend => draw_trend(f, state, rect),
+        Mode::Help => draw_help(f, state, rect),
+        _ => (),
+    }
+}
+
+// What a confirm dialog asks
+fn question(state: &State, confirm: &Confirm) -> String {
+    let size_of = |paths: &[PathBuf]| {
+        size::format_size(
+            state
+                .results
+                .iter()
+                .filter(|e| paths.contains(&e.path))
+                .map(|e| e.size)
+                .sum(),
+        )
+    };
+
+    let question = match confirm {
+        Confirm::Trash(paths) if paths.len() == 1 => {
+            tf("Trash {} ({})?", &[&paths[0].display(), &size_of(paths)])
+        }
+        Confirm::Trash(paths) => tf("Trash {} folders ({})?", &[&paths.len(), &size_of(paths)]),
+        Confirm::SlowDelete(paths) => {
+            let files = files(state, paths);
+            tf(
+                "{} folders hold {} files, trashing takes {}. Deleting them without trash is faster.",
+                &[
+                    &paths.len(),
+                    &files,
+                    &trashcan::format_estimate(trashcan::estimate(files)),
+                ],
+            )
+        }
+        Confirm::CargoClean(paths) => tf(
+            "Run cargo clean {}in the projects of {} targets ({})?",
+            &[&cargo_args(state), &paths.len(), &size_of(paths)],
+        ),
+        Confirm::LightClean(paths) => tf(
+            "Remove incremental caches, docs and old artifacts of {} targets ({}), keeping binaries?",
+            &[&paths.len(), &size_of(paths)],
+        ),
+        Confirm::PermanentDelete(paths) => tf(
+            "Permanently delete {} folders ({}) without trash? This can't be undone.",
+            &[&paths.len(), &size_of(paths)],
+        ),
+        Confirm::EmptyTrash => tf(
+            "Permanently delete {} trashed folders ({}, {}) from trash?",
+            &[
+                &state.trashed.len(),
+                &size::format_size(state.trashed.iter().map(|e| e.size).sum()),
+                &trashcan::format_estimate(trashcan::estimate(
+                    state.trashed.iter().map(|e| e.files).sum(),
+                )),
+            ],
+        ),
+    };
+
+    // Cargo cleans Rust targets whatever the dialog says
+    let rust = paths_of(confirm).iter().any(|path| {
+        state
+            .results
+            .iter()
+            .any(|e| e.path == *path && e.kind == Kind::Rust)
+    });
+    let cleaning = matches!(confirm, Confirm::CargoClean(_) | Confirm::LightClean(_));
+    match &state.cleanup {
+        Cleanup::Cargo if rust && !cleaning => {
+            question
+                + &tf(
+                    " Rust targets are cleaned with cargo clean {}instead.",
+                    &[&cargo_args(state)],
+                )
+        }
+        Cleanup::Light if rust && !cleaning => {
+            question + t(" Rust targets are only cleaned lightly, keeping binaries.")
+        }
+        Cleanup::Profiles(profiles) if rust && !cleaning => {
+            question
+                + &tf(
+                    " Only {} of Rust targets are cleaned.",
+                    &[&profiles.join(", ")],
+                )
+        }
+        _ => question,
+    }
+}
+
+// Entries shown in a preview, the rest are counted
+const PREVIEW_ITEMS: usize = 6;
+
+// Lines listing what a preview found, to go below the question
+fn describe_preview(preview: &Preview) -> String {
+    let mut text = String::new();
+    if !preview.items.is_empty() {
+        text.push_str(&format!("\n\n{}", t("Inside:")));
+        for item in preview.items.iter().take(PREVIEW_ITEMS) {
+            let slash = if item.is_dir { "/" } else { "" };
+            text.push_str(&format!(
+                "\n- {}{slash}  {}",
+                item.name,
+                size::format_size(item.size)
+            ));
+        }
+        if preview.items.len() > PREVIEW_ITEMS {
+            text.push_str(&tf(
+                "\n- and {} more",
+                &[&(preview.items.len() - PREVIEW_ITEMS)],
+            ));
+        }
+    }
+    if !preview.biggest.is_empty() {
+        text.push_str(&format!("\n\n{}", t("Biggest files:")));
+        for (path, size) in &preview.biggest {
+            text.push_str(&format!(
+                "\n- {}  {}",
+                path.display(),
+                size::format_size(*size)
+            ));
+        }
+    }
+    text
+}
+
+// Box with `text` over the middle of `area`, sized to fit the wrapped text
+fn draw_dialog(f: &mut Frame<CrosstermBackend<Stdout>>, text: &str, area: Rect) {
+    let width = area.width.saturating_sub(4).min(60);
+    let height = (wrapped_lines(text, width.saturating_sub(2)) + 2).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let dialog = Paragraph::new(text.to_string())
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title(t("Confirm")).borders(Borders::ALL));
+    f.render_widget(Clear, rect);
+    f.render_widget(dialog, rect);
+}
+
+// Current settings over every key of the list in as many columns as it
+// takes, over `area`
+fn draw_help(f: &mut Frame<CrosstermBackend<Stdout>>, state: &State, area: Rect) {
+    let block = Block::default().title(t("Help")).borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let settings = settings(state);
+    let rows = settings.len() as u16 + 1;
+    let lines: Vec<Spans> = settings
+        .into_iter()
+        .map(|(name, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{name}: "), state.theme.dimmed),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+    let top = Rect::new(inner.x, inner.y, inner.width, rows.min(inner.height));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), top);
+
+    let mut keys: Vec<(String, &str)> = keys::ACTIONS
+        .iter()
+        .map(|(action, _, what)| (state.keys.bound(action).join(" "), *what))
+        .filter(|(keys, _)| !keys.is_empty())
+        .collect();
+    keys.insert(6, ("1-9".to_string(), "Mark row on screen"));
+    let area = Rect::new(
+        inner.x,
+        inner.y + top.height,
+        inner.width,
+        inner.height - top.height,
+    );
+    let title = Paragraph::new(Span::styled(
+        t("Keys"),
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+    f.render_widget(
+        title,
+        Rect::new(area.x, area.y, area.width, 1.min(area.height)),
+    );
+
+    // Columns as wide as their longest line, the last one cut off if the
+    // screen is too narrow
+    let per_column = usize::from(area.height.saturating_sub(1)).max(1);
+    let mut x = area.x;
+    for column in keys.chunks(per_column) {
+        if x >= area.right() {
+            break;
+        }
+        let key_width = column.iter().map(|(k, _)| k.chars().count()).max();
+        let key_width = key_width.unwrap_or(0);
+        let width = column
+            .iter()
+            .map(|(_, what)| key_width + 2 + t(what).chars().count() + 3)
+            .max()
+            .unwrap_or(1) as u16;
+        let lines: Vec<Spans> = column
+            .iter()
+            .map(|(key, what)| {
+                Spans::from(vec![
+                    Span::styled(format!("{key:>key_width$}  "), state.theme.info),
+                    Span::raw(t(what)),
+                ])
+            })
+            .collect();
+        let width = width.min(area.right() - x);
+        let rect = Rect::new(x, area.y + 1, width, area.height.saturating_sub(1));
+        f.render_widget(Paragraph::new(lines), rect);
+        x += width;
+    }
+}
+
+// Names and values of what the options set, for the help
+fn settings(state: &State) -> Vec<(&'static str, String)> {
+    let roots: Vec<String> = match remote::get() {
+        Some(remote) => vec![remote.to_string()],
+        None => state.roots.iter().map(|r| tilde(r)).collect(),
+    };
+    let mut deleting = match &state.cleanup {
+        Cleanup::Cargo => tf("with cargo clean {}", &[&cargo_args(state)]),
+        Cleanup::Light => t("lightly cleaning Rust targets").to_string(),
+        Cleanup::Profiles(profiles) => {
+            tf("only cleaning {} of Rust targets", &[&profiles.join(", ")])
+        }
+        Cleanup::Remove if state.permanent => t("permanently").to_string(),
+        Cleanup::Remove => t("to trash").to_string(),
+    };
+    if !state.permanent && state.roots.iter().any(|r| wsl::is_windows_drive(r)) {
+        deleting.push_str(&format!(", {}", t("Windows drives to the recycle bin")));
+    }
+
+    let mut settings = vec![
+        (t("Scanning"), roots.join(", ")),
+        (t("Deleting"), deleting),
+        (
+            t("Filter"),
+            if state.filter.is_empty() {
+                t("none").to_string()
+            } else {
+                state.filter.clone()
+            },
+        ),
+    ];
+    if let Some(age) = state.older_than {
+        let days = age.as_secs() / (24 * 60 * 60);
+        settings.push((t("Older than"), tf("{} days", &[&days])));
+    }
+    if let Some(min) = state.min_size {
+        let size = size::format_size(min);
+        let hidden = if state.hide_small {
+            tf("{} (hidden)", &[&size])
+        } else {
+            tf("{} (shown)", &[&size])
+        };
+        settings.push((t("Smaller than"), hidden));
+    }
+    settings.push((
+        t("Sorted by"),
+        t(state.sort.describe(state.sort_reversed)).to_string(),
+    ));
+    let open = match state.open_projects {
+        OpenProjects::Off => t("off"),
+        OpenProjects::Warn => t("flagged"),
+        OpenProjects::Skip => t("skipped by bulk actions"),
+    };
+    settings.push((t("Open projects"), open.to_string()));
+    if let Some(within) = state.active_within {
+        let days = within.as_secs() / (24 * 60 * 60);
+        settings.push((
+            t("Active projects"),
+            tf(
+                "changed or committed within {} days, skipped by bulk actions",
+                &[&days],
+            ),
+        ));
+    }
+    if let Some(checker) = &state.checker {
+        settings.push((t("Check builds with"), checker.command.join(" ")));
+    }
+    settings
+}
+
+// Sparkline of the total size of remembered scans over `area`
+fn draw_trend(f: &mut Frame<CrosstermBackend<Stdout>>, state: &State, area: Rect) {
+    let block = Block::default()
+        .title(t("Total size of targets over time"))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    // As many of the latest scans as there are columns
+    let shown = &state.trend[state.trend.len().saturating_sub(usize::from(inner.width))..];
+    let (Some((first, _)), Some((_, latest))) = (shown.first(), shown.last()) else {
+        let text = Paragraph::new(t("No scans of these folders remembered yet"));
+        f.render_widget(text, inner);
+        return;
+    };
+    let sizes: Vec<u64> = shown.iter().map(|(_, size)| *size).collect();
+    // Date without the time
+    let since = report::format_time(*first);
+    let summary = tf(
+        "{} scans since {}, now {}, smallest {}, biggest {}",
+        &[
+            &sizes.len(),
+            &&since[..10],
+            &size::format_size(*latest),
+            &size::format_size(sizes.iter().copied().min().unwrap_or(0)),
+            &size::format_size(sizes.iter().copied().max().unwrap_or(0)),
+        ],
+    );
+    f.render_widget(
+        Paragraph::new(summary),
+        Rect::new(inner.x, inner.y, inner.width, 1.min(inner.height)),
+    );
+
+    let bars = if state.caps.unicode {
+        symbols::bar::NINE_LEVELS
+    } else {
+        ASCII_BARS
+    };
+    let sparkline = Sparkline::default()
+        .data(&sizes)
+        .bar_set(bars)
+        .style(state.theme.info);
+    f.render_widget(
+        sparkline,
+        Rect::new(
+            inner.x,
+            inner.y + 1.min(inner.height),
+            inner.width,
+            inner.height.saturating_sub(1),
+        ),
+    );
+}
+
+// Bars of a sparkline for terminals that can only show ASCII
+const ASCII_BARS: symbols::bar::Set = symbols::bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: "=",
+    half: "=",
+    three_eighths: "-",
+    one_quarter: "-",
+    one_eighth: "_",
+    empty: " ",
+};
+
+// Lines `text` takes when wrapped at word boundaries to `width` columns
+fn wrapped_lines(text: &str, width: u16) -> u16 {
+    text.split('\n').map(|line| wrapped_line(line, width)).sum()
+}
+
+// Where each word of the key help `text` is when wrapped into `area`, with the
+// key of the action it belongs to, the one in parentheses after it
+fn hints(text: &str, area: Rect) -> Vec<(Rect, String)> {
+    let width = usize::from(area.width.max(1));
+    let mut hints = vec![];
+    let mut pending: Vec<Rect> = vec![];
+    let (mut line, mut column, mut gap) = (0, 0, 0);
+
+    for word in text.split(' ') {
+        if word.is_empty() {
+            gap += 1;
+            continue;
+        }
+        let len = word.chars().count();
+        let space = if column > 0 { gap.max(1) } else { 0 };
+        if column > 0 && column + space + len > width {
+            line += 1;
+            column = 0;
+        } else {
+            column += space;
+        }
+        gap = 0;
+        if line >= usize::from(area.height) {
+            break;
+        }
+        pending.push(Rect::new(
+            area.x + column as u16,
+            area.y + line as u16,
+            len.min(width) as u16,
+            1,
+        ));
+        column += len;
+
+        if let Some(key) = word.strip_prefix('(').and_then(|w| w.strip_suffix(')')) {
+            hints.extend(pending.drain(..).map(|rect| (rect, key.to_string())));
+        }
+    }
+    hints
+}
+
+// Lines a single line of text takes when wrapped
+fn wrapped_line(text: &str, width: u16) -> u16 {
+    let width = usize::from(width.max(1));
+    let mut lines = 1;
+    let mut column = 0;
+    for word in text.split_whitespace() {
+        let len = word.chars().count();
+        if column > 0 && column + 1 + len > width {
+            lines += 1;
+            column = 0;
+        }
+        column += if column > 0 { 1 + len } else { len };
+        // Words longer than a line get broken up
+        while column > width {
+            lines += 1;
+            column -= width;
+        }
+    }
+    lines
+}
+
+fn draw_details(
+    f: &mut Frame<CrosstermBackend<Stdout>>,
+    details: &mut Option<Details>,
+    note: Option<&String>,
+    theme: &Theme,
+    events: &Events,
+    rect: Rect,
+) {
+    let block = Block::default().title(t("Details")).borders(Borders::ALL);
+    let Some(entry) = events.selected() else {
+        f.render_widget(block, rect);
+        return;
+    };
+
+    // Compute once per selected entry
+    if details.as_ref().map(|d| &d.path) != Some(&entry.path) {
+        *details = Some(Details::new(entry));
+    }
+    let Some(details) = details else {
+        return;
+    };
+
+    let mut lines = vec![Spans::from(tf("Project: {}", &[&entry.project.display()]))];
+    if let Some(note) = note {
+        lines.push(Spans::from(Span::styled(
+            tf("Note: {}", &[note]),
+            theme.info,
+        )));
+    }
+    if let Some(branch) = &details.branch {
+        lines.push(Spans::from(tf("Git branch: {}", &[branch])));
+    }
+    lines.extend([
+        Spans::from(tf(
+            "Files: {}, deleting takes {}",
+            &[
+                &entry.files,
+                &trashcan::format_estimate(trashcan::estimate(entry.files)),
+            ],
+        )),
+        Spans::from(tf(
+            "Newest file: {}",
+            &[&details.newest.map_or("-".to_string(), report::format_time)],
+        )),
+    ]);
+    if entry.shared > 0 {
+        lines.push(Spans::from(tf(
+            "Hard linked elsewhere: {}",
+            &[&size::format_size(entry.shared)],
+        )));
+    }
+    for kept in &entry.kept {
+        lines.push(Spans::from(tf(
+            "Excluded, left in place: {}",
+            &[&kept.display()],
+        )));
+    }
+    if details.reflinked > 0 {
+        lines.push(Spans::from(tf(
+            "Reflinked or in snapshots: {}",
+            &[&size::format_size(details.reflinked)],
+        )));
+    }
+    if entry.shared > 0 || details.reflinked > 0 {
+        let frees = entry.reclaimable().saturating_sub(details.reflinked);
+        lines.push(Spans::from(tf(
+            "Deleting frees about {}",
+            &[&size::format_size(frees)],
+        )));
+    }
+    if !details.folders.is_empty() {
+        lines.push(Spans::from(""));
+        for (name, size) in &details.folders {
+            lines.push(Spans::from(format!(
+                "{:>9}  {name}/",
+                size::format_size(*size)
+            )));
+        }
+    }
+    match &details.members {
+        Some(members) => {
+            lines.push(Spans::from(""));
+            lines.push(Spans::from(tf("Workspace members ({})", &[&members.len()])));
+            for (name, size) in members {
+                lines.push(Spans::from(format!(
+                    "{:>9}  {name}",
+                    size::format_size(*size)
+                )));
+            }
+        }
+        None => lines.push(Spans::from(t("Not a workspace"))),
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), rect);
+}
+
+fn draw_browser(f: &mut Frame<CrosstermBackend<Stdout>>, browser: &mut Browser, rect: Rect) {
+    let block = Block::default()
+        .title(format!(
+            "{} ({})",
+            browser.dir.display(),
+            size::format_size(browser.size())
+        ))
+        .borders(Borders::ALL);
+
+    let items: Vec<ListItem> = browser
+        .items
+        .iter()
+        .map(|item| {
+            let name = if item.is_dir {
+                format!("{}/", item.name)
+            } else {
+                item.name.clone()
+            };
+            ListItem::new(format!("{:>9}  {name}", size::format_size(item.size)))
+        })
+        .collect();
+
+    let list = List::new(items).block(block).highlight_symbol(">>");
+
+    f.render_stateful_widget(list, rect, &mut browser.state);
+}
+
+fn draw_tree(f: &mut Frame<CrosstermBackend<Stdout>>, state: &State, tree: &mut Tree, rect: Rect) {
+    let entries: Vec<&Entry> = state.results.iter().filter(|e| listed(state, e)).collect();
+    let nodes = tree::nodes(&state.roots, &entries);
+    let rows = tree.rows(&nodes);
+    let theme = &state.theme;
+    let (open, closed) = if state.caps.unicode {
+        ("▾ ", "▸ ")
+    } else {
+        ("v ", "> ")
+    };
+
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|node| {
+            let size = Span::styled(
+                format!("{:>9}  ", size::format_size(node.size)),
+                size_style(node.size, state.size_colors, theme),
+            );
+            let indent = Span::raw("  ".repeat(node.depth));
+            let mut spans = if node.is_target {
+                vec![size, indent, Span::raw("  "), Span::raw(node.label.clone())]
+            } else {
+                let glyph = if tree.collapsed.contains(&node.path) {
+                    closed
+                } else {
+                    open
+                };
+                vec![
+                    size,
+                    indent,
+                    Span::raw(glyph),
+                    Span::styled(format!("{}/", node.label), theme.info),
+                    Span::styled(tf("  ({} targets)", &[&node.targets.len()]), theme.dimmed),
+                ]
+            };
+            if node.targets.iter().any(|p| state.deleter.is_queued(p)) {
+                spans.push(Span::styled(t("  (waiting to be deleted)"), theme.dimmed));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+    let block = Block::default()
+        .title(tf("Tree of {} target folders", &[&entries.len()]))
+        .borders(Borders::ALL);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selected)
+        .highlight_symbol(">>");
+
+    tree.select(tree.state.selected().unwrap_or(0), rows.len());
+    f.render_stateful_widget(list, rect, &mut tree.state);
+}
+
+fn draw_excluded(
+    f: &mut Frame<CrosstermBackend<Stdout>>,
+    excluded: &[PathBuf],
+    list: &mut ListState,
+    rect: Rect,
+) {
+    let block = Block::default()
+        .title(tf("Excluded projects ({})", &[&excluded.len()]))
+        .borders(Borders::ALL);
+    let items: Vec<ListItem> = if excluded.is_empty() {
+        vec![ListItem::new(t(
+            "Nothing excluded, x on a target excludes its project from scans",
+        ))]
+    } else {
+        excluded
+            .iter()
+            .map(|project| ListItem::new(tilde(project)))
+            .collect()
+    };
+    let list_widget = List::new(items).block(block).highlight_symbol(">>");
+
+    f.render_stateful_widget(list_widget, rect, list);
+}
+
+fn draw_profiles(f: &mut Frame<CrosstermBackend<Stdout>>, profiles: &mut Profiles, rect: Rect) {
+    let block = Block::default()
+        .title(tf("Profiles to clean in {}", &[&tilde(&profiles.path)]))
+        .borders(Borders::ALL);
+    let items: Vec<ListItem> = profiles
+        .folders
+        .iter()
+        .map(|(name, size, clean)| {
+            let mark = if *clean { "[x]" } else { "[ ]" };
+            ListItem::new(format!("{mark} {:>9}  {name}", size::format_size(*size)))
+        })
+        .collect();
+    let list_widget = List::new(items).block(block).highlight_symbol(">>");
+
+    f.render_stateful_widget(list_widget, rect, &mut profiles.state);
+}
+
+fn draw_duplicates(
+    f: &mut Frame<CrosstermBackend<Stdout>>,
+    duplicates: &mut Duplicates,
+    rect: Rect,
+) {
+    let block = Block::default()
+        .title(t("Dependencies built in several targets"))
+        .borders(Borders::ALL);
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+    let Some(report) = &duplicates.report else {
+        let text = Paragraph::new(t("Comparing the dependencies of the Rust targets..."));
+        f.render_widget(text, inner);
+        return;
+    };
+
+    let summary = if report.duplicates.is_empty() {
+        tf(
+            "No dependency is built the same in several of {} Rust targets",
+            &[&report.targets],
+        )
+    } else {
+        tf(
+            "{} dependencies are built the same in several of {} Rust targets. One target folder shared by these projects would save {} of the {} their deps folders take: set CARGO_TARGET_DIR, or build.target-dir in ~/.cargo/config.toml.",
+            &[
+                &report.duplicates.len(),
+                &report.targets,
+                &size::format_size(report.savings()),
+                &size::format_size(report.total),
+            ],
+        )
+    };
+    let lines = (wrapped_lines(&summary, inner.width) + 1).min(inner.height);
+    f.render_widget(
+        Paragraph::new(summary).wrap(Wrap { trim: true }),
+        Rect::new(inner.x, inner.y, inner.width, lines),
+    );
+
+    let items: Vec<ListItem> = report
+        .duplicates
+        .iter()
+        .map(|duplicate| {
+            ListItem::new(tf(
+                "{}  {} in {} targets, {} each",
+                &[
+                    &format!("{:>9}", size::format_size(duplicate.savings)),
+                    &duplicate.name,
+                    &duplicate.targets.len(),
+                    &size::format_size(duplicate.size),
+                ],
+            ))
+        })
+        .collect();
+    let list = List::new(items).highlight_symbol(">>");
+    let list_rect = Rect::new(inner.x, inner.y + lines, inner.width, inner.height - lines);
+    f.render_stateful_widget(list, list_rect, &mut duplicates.state);
+}
+
+// Deleted entry, greyed out and struck through, fading as it gets older
+fn tombstone(entry: &Entry, age: Duration) -> ListItem<'static> {
+    let color = if age < TOMBSTONE / 2 {
+        Color::Gray
+    } else {
+        Color::DarkGray
+    };
+    let text = format!(
+        "      {:>9}  {:>8}  {:<7}{}",
+        size::format_size(entry.size),
+        last_used(entry),
+        entry.kind.name(),
+        entry.path.display()
+    );
+    ListItem::new(text).style(
+        Style::default()
+            .fg(color)
+            .add_modifier(Modifier::CROSSED_OUT),
+    )
+}
+
+/// Index of the root `entry` was found in, the innermost one if they overlap
+pub fn root_of(roots: &[PathBuf], entry: &Entry) -> Option<usize> {
+    let path = entry.path.as_path();
+    (0..roots.len())
+        .filter(|&i| path.starts_with(&roots[i]))
+        .max_by_key(|&i| roots[i].components().count())
+}
+
+// `path` with the home folder as `~`
+fn tilde(path: &Path) -> String {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match home
+        .filter(|home| !home.is_empty())
+        .as_deref()
+        .and_then(|home| path.strip_prefix(home).ok())
+    {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~{}{}", std::path::MAIN_SEPARATOR, rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+// Filesystem `entry` is on, if known
+fn filesystem<'a>(state: &'a State, entry: &Entry) -> Option<&'a Filesystem> {
+    state
+        .filesystems
+        .iter()
+        .find(|fs| Some(fs.device) == entry.device)
+}
+
+// Size of listed targets on `fs` and how much space it has left, for the footer
+fn filesystem_summary(fs: &Filesystem, entries: &[Entry]) -> String {
+    let on_fs = || entries.iter().filter(|e| e.device == Some(fs.device));
+    let targets = on_fs().map(|e| e.size).sum();
+    let freeable: u64 = on_fs().map(|e| e.reclaimable()).sum();
+    let free = match (fs.free, fs.size) {
+        (Some(free), Some(size)) if size > 0 => tf(
+            "{} free ({}%), {} with them deleted",
+            &[
+                &size::format_size(free),
+                &(free * 100 / size),
+                &size::format_size(free + freeable),
+            ],
+        ),
+        _ => t("free space unknown").to_string(),
+    };
+    tf(
+        "  |  {}: {} in targets, {}",
+        &[&fs.mount.display(), &size::format_size(targets), &free],
+    )
+}
+
+// Space free on the filesystems of the listed targets, and how much would be
+// free with all of them deleted. Hard linked files elsewhere stay.
+fn free_summary(filesystems: &[Filesystem], events: &Events) -> Option<String> {
+    let known: Vec<&Filesystem> = filesystems.iter().filter(|fs| fs.free.is_some()).collect();
+    if known.is_empty() {
+        return None;
+    }
+    let free: u64 = known.iter().filter_map(|fs| fs.free).sum();
+    let freeable: u64 = events
+        .items
+        .iter()
+        .filter(|e| !events.is_tombstone(&e.path))
+        .filter(|e| known.iter().any(|fs| Some(fs.device) == e.device))
+        .map(|e| e.reclaimable())
+        .sum();
+    let (free, after) = (size::format_size(free), size::format_size(free + freeable));
+    Some(match known.len() {
+        1 => tf(
+            "  |  Free: {}, {} with all listed deleted",
+            &[&free, &after],
+        ),
+        n => tf(
+            "  |  Free on {} filesystems: {}, {} with all listed deleted",
+            &[&n, &free, &after],
+        ),
+    })
+}
+
+// How long ago anything in the entry was modified, in days
+fn last_used(entry: &Entry) -> String {
+    match entry.age().map(|a| a.as_secs() / (24 * 60 * 60)) {
+        None => "-".to_string(),
+        Some(0) => t("today").to_string(),
+        Some(days) => tf("{}d ago", &[&days]),
+    }
+}
+
+// Grand total of listed entries, with subtotals when there are several kinds
+fn totals<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> String {
+    let mut subtotals: BTreeMap<Kind, u64> = BTreeMap::new();
+    for e in entries {
+        *subtotals.entry(e.kind).or_default() += e.size;
+    }
+
+    let total = size::format_size(subtotals.values().sum());
+    let mut text = match size::metric() {
+        size::Metric::Disk => tf("Total on disk: {}", &[&total]),
+        size::Metric::Apparent => tf("Total apparent size: {}", &[&total]),
+    };
+
+    if subtotals.len() > 1 {
+        let parts: Vec<String> = subtotals
+            .iter()
+            .map(|(kind, size)| format!("{}: {}", kind.name(), size::format_size(*size)))
+            .collect();
+        text.push_str(&format!(" ({})", parts.join(", ")));
+    }
+    text
+}
+
+// Style of the size column
+fn size_style(size: u64, (yellow, red): (u64, u64), theme: &Theme) -> Style {
+    if size >= red {
+        theme.large
+    } else if size >= yellow {
+        theme.medium
+    } else {
+        theme.small
+    }
+}
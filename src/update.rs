@@ -0,0 +1,260 @@
+use std::{path::Path, process::Command};
+
+// Latest release of rclin, as JSON
+const RELEASES: &str = "https://api.github.com/repos/aalhitennf/rclin/releases/latest";
+
+// Exit codes like the other commands without the interface
+const UPDATED: i32 = 0;
+const LATEST: i32 = 1;
+const FAILED: i32 = 2;
+
+/// `rclin self-update`: replace this executable with the binary of the latest
+/// GitHub release for this platform, after checking it against the SHA-256
+/// checksum published with it. Returns exit code, 1 when this already is the
+/// latest version.
+pub fn self_update() -> i32 {
+    match update() {
+        Ok(Some(tag)) => {
+            println!("Updated rclin to {tag}");
+            UPDATED
+        }
+        Ok(None) => {
+            println!("rclin {} is the latest version", env!("CARGO_PKG_VERSION"));
+            LATEST
+        }
+        Err(e) => {
+            eprintln!("Updating failed: {e}");
+            FAILED
+        }
+    }
+}
+
+// Tag of the release installed, None if there is nothing newer
+fn update() -> Result<Option<String>, String> {
+    let release = download_text(RELEASES)?;
+    let tag = string_fields(&release, "tag_name")
+        .into_iter()
+        .next()
+        .ok_or("no release found")?;
+    if !newer(tag.trim_start_matches('v'), env!("CARGO_PKG_VERSION")) {
+        return Ok(None);
+    }
+
+    // Assets are named like `rclin-x86_64-linux` and `rclin-x86_64-windows.exe`
+    let name = format!(
+        "rclin-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    );
+    let urls = string_fields(&release, "browser_download_url");
+    let asset = |name: &str| {
+        urls.iter()
+            .find(|url| url.rsplit('/').next() == Some(name))
+            .cloned()
+    };
+    let binary = asset(&name).ok_or(format!("release {tag} has no {name}"))?;
+    let checksum = asset(&format!("{name}.sha256")).ok_or(format!(
+        "release {tag} has no checksum for {name}, not installing it unchecked"
+    ))?;
+    // Like `sha256sum` writes it, the hash before the file name
+    let expected = download_text(&checksum)?
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let exe = std::env::current_exe().map_err(|e| format!("cannot find this executable: {e}"))?;
+    // Next to the executable, so renaming it over that can't cross filesystems
+    let new = exe.with_extension("new");
+    download(&binary, &new)?;
+    let bytes = std::fs::read(&new).map_err(|e| format!("cannot read {}: {e}", new.display()))?;
+    if hex(&sha256(&bytes)) != expected {
+        let _ = std::fs::remove_file(&new);
+        return Err(format!(
+            "checksum of {name} doesn't match, not installing it"
+        ));
+    }
+    replace(&exe, &new).map_err(|e| format!("cannot replace {}: {e}", exe.display()))?;
+    Ok(Some(tag))
+}
+
+// Whether dotted version `a` is later than `b`. Pre-release suffixes are
+// ignored.
+fn newer(a: &str, b: &str) -> bool {
+    let parts = |v: &str| -> Vec<u64> {
+        v.split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|n| n.parse().unwrap_or(0))
+            .collect()
+    };
+    parts(a) > parts(b)
+}
+
+// Put the executable at `new` in place of the one at `exe`. Windows can't
+// overwrite a running executable but can rename it out of the way.
+#[cfg(unix)]
+fn replace(exe: &Path, new: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(new, std::fs::Permissions::from_mode(0o755))?;
+    std::fs::rename(new, exe)
+}
+
+#[cfg(not(unix))]
+fn replace(exe: &Path, new: &Path) -> std::io::Result<()> {
+    let old = exe.with_extension("old");
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(exe, &old)?;
+    std::fs::rename(new, exe)
+}
+
+// With curl, which comes with every desktop OS these days, HTTPS only
+fn curl(url: &str) -> Command {
+    let mut curl = Command::new("curl");
+    curl.args(["--fail", "--silent", "--show-error", "--location"])
+        .args([
+            "--proto",
+            "=https",
+            "--header",
+            "Accept: application/vnd.github+json",
+        ])
+        .arg(url);
+    curl
+}
+
+fn download_text(url: &str) -> Result<String, String> {
+    let output = curl(url)
+        .output()
+        .map_err(|e| format!("cannot run curl: {e}"))?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("downloading {url} failed: {}", error.trim()));
+    }
+    String::from_utf8(output.stdout).map_err(|_| format!("{url} is not text"))
+}
+
+fn download(url: &str, dest: &Path) -> Result<(), String> {
+    let status = curl(url)
+        .arg("--output")
+        .arg(dest)
+        .status()
+        .map_err(|e| format!("cannot run curl: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("downloading {url} failed"))
+    }
+}
+
+// String values of `key` anywhere in `json`, enough for the flat fields of
+// a release and its assets
+fn string_fields(json: &str, key: &str) -> Vec<String> {
+    let quoted = format!("\"{key}\"");
+    let mut values = vec![];
+    let mut rest = json;
+    while let Some(at) = rest.find(&quoted) {
+        rest = rest[at + quoted.len()..].trim_start();
+        let Some(value) = rest
+            .strip_prefix(':')
+            .map(str::trim_start)
+            .and_then(|v| v.strip_prefix('"'))
+        else {
+            continue;
+        };
+
+        let mut out = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                // URLs and tags don't need more than these
+                '\\' => out.extend(chars.next()),
+                c => out.push(c),
+            }
+        }
+        values.push(out);
+        rest = value;
+    }
+    values
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// SHA-256 from FIPS 180-4
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // Padded with a one bit, zeros and the length in bits
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    let mut out = [0; 32];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
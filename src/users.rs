@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+/// Account with a home folder
+pub struct User {
+    pub name: String,
+    pub home: PathBuf,
+}
+
+/// Users with existing home folders, read from /etc/passwd. System accounts
+/// (uid below 1000) are left out, except root.
+#[cfg(unix)]
+pub fn users() -> Vec<User> {
+    let Ok(passwd) = std::fs::read_to_string("/etc/passwd") else {
+        return vec![];
+    };
+
+    let mut users: Vec<User> = passwd
+        .lines()
+        .filter_map(|line| {
+            // name:password:uid:gid:gecos:home:shell
+            let fields: Vec<&str> = line.split(':').collect();
+            let uid: u32 = fields.get(2)?.parse().ok()?;
+            if uid != 0 && (uid < 1000 || uid == 65534) {
+                return None;
+            }
+            Some(User {
+                name: fields.first()?.to_string(),
+                home: PathBuf::from(fields.get(5)?),
+            })
+        })
+        .filter(|user| user.home.is_dir())
+        .collect();
+
+    // Several accounts can share a home folder, scan it once
+    users.sort_by(|a, b| a.home.cmp(&b.home));
+    users.dedup_by(|a, b| a.home == b.home);
+    users.sort_by(|a, b| a.name.cmp(&b.name));
+    users
+}
+
+#[cfg(not(unix))]
+pub fn users() -> Vec<User> {
+    vec![]
+}
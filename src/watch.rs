@@ -0,0 +1,284 @@
+use std::{
+    collections::HashMap,
+    io::Stdout,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use tui::{
+    backend::CrosstermBackend,
+    layout::Rect,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    error::Error,
+    i18n::{t, tf},
+    input::{Input, Message},
+    scan::{self, Entry, Found},
+    size,
+};
+
+use crate::{
+    caps::{Caps, Degrade},
+    diff::signed,
+    theme::Theme,
+};
+
+/// Sizes of a target over the scans it was found in
+struct Watched {
+    path: PathBuf,
+    // What it took when first found
+    first: u64,
+    size: u64,
+    // When it was last measured
+    at: Instant,
+    // Bytes a minute since the scan before, none until scanned twice
+    rate: Option<f64>,
+    // Not found by the last scan
+    gone: bool,
+}
+
+struct Watch {
+    targets: Vec<Watched>,
+    theme: Theme,
+    caps: Caps,
+    scans: usize,
+    last: Option<Instant>,
+    interval: Duration,
+    state: ListState,
+}
+
+impl Watch {
+    // Take in what a scan measured at `at`, fastest growing first
+    fn update(&mut self, at: Instant, found: Vec<Entry>) {
+        let mut found: HashMap<PathBuf, u64> =
+            found.into_iter().map(|e| (e.path, e.size)).collect();
+        for target in &mut self.targets {
+            match found.remove(&target.path) {
+                Some(size) => {
+                    let minutes = at.duration_since(target.at).as_secs_f64() / 60.0;
+                    target.rate = Some((size as f64 - target.size as f64) / minutes);
+                    target.size = size;
+                    target.at = at;
+                    target.gone = false;
+                }
+                None => {
+                    target.rate = None;
+                    target.gone = true;
+                }
+            }
+        }
+        self.targets
+            .extend(found.into_iter().map(|(path, size)| Watched {
+                path,
+                first: size,
+                size,
+                at,
+                rate: None,
+                gone: false,
+            }));
+        self.targets.sort_by(|a, b| {
+            let rate = |w: &Watched| w.rate.unwrap_or(0.0);
+            rate(b)
+                .total_cmp(&rate(a))
+                .then(b.size.cmp(&a.size))
+                .then(a.path.cmp(&b.path))
+        });
+        self.scans += 1;
+        self.last = Some(at);
+        if self.state.selected().is_none() && !self.targets.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+}
+
+/// `rclin watch`: scan `roots` every `interval` until quit, showing how fast
+/// each target grows
+pub fn watch(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    roots: Vec<PathBuf>,
+    options: scan::Options,
+    interval: Duration,
+    theme: Theme,
+    caps: Caps,
+) -> Result<(), Error> {
+    let scans = spawn(roots, options, interval);
+    let input = Input::spawn(Duration::from_millis(250));
+    let mut watch = Watch {
+        targets: vec![],
+        theme,
+        caps,
+        scans: 0,
+        last: None,
+        interval,
+        state: ListState::default(),
+    };
+
+    loop {
+        for (at, found) in scans.try_iter() {
+            watch.update(at, found);
+        }
+        terminal.draw(|f| draw(f, &mut watch))?;
+
+        let Message::Event(Event::Key(KeyEvent {
+            code, modifiers, ..
+        })) = input.next()
+        else {
+            continue;
+        };
+        let selected = watch.state.selected().unwrap_or(0);
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if modifiers == KeyModifiers::CONTROL => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => {
+                watch.state.select(Some(selected.saturating_sub(1)))
+            }
+            KeyCode::Down | KeyCode::Char('j') if selected + 1 < watch.targets.len() => {
+                watch.state.select(Some(selected + 1));
+            }
+            _ => (),
+        }
+    }
+}
+
+// Scan `roots` every `interval` in the background, sending what was found and
+// when until nobody listens
+fn spawn(
+    roots: Vec<PathBuf>,
+    options: scan::Options,
+    interval: Duration,
+) -> Receiver<(Instant, Vec<Entry>)> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || loop {
+        let (receiver, handle, _) = scan::spawn(roots.clone(), options.clone());
+        // Folders it can't read only leave their targets out
+        let found = receiver
+            .iter()
+            .filter_map(|found| match found {
+                Found::Entry(entry) => Some(*entry),
+                Found::Unreadable(..) => None,
+            })
+            .collect();
+        if let Ok(Err(e)) = handle.join() {
+            tracing::warn!("{e}");
+        }
+        if sender.send((Instant::now(), found)).is_err() {
+            return;
+        }
+        std::thread::sleep(interval);
+    });
+    receiver
+}
+
+fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, watch: &mut Watch) {
+    let size = f.size();
+    let shown: Vec<&Watched> = watch.targets.iter().filter(|w| !w.gone).collect();
+    let title = match watch.last {
+        None => t("Scanning...").to_string(),
+        Some(last) => tf(
+            "{} target folders taking {}, scanned {} times every {}s, last {}s ago",
+            &[
+                &shown.len(),
+                &size::format_size(shown.iter().map(|w| w.size).sum()),
+                &watch.scans,
+                &watch.interval.as_secs(),
+                &last.elapsed().as_secs(),
+            ],
+        ),
+    };
+    let area = Rect::new(0, 0, size.width, size.height.saturating_sub(1));
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let items: Vec<ListItem> = watch
+        .targets
+        .iter()
+        .map(|w| item(w, &watch.theme))
+        .collect();
+    let list = List::new(items)
+        .highlight_style(watch.theme.selected)
+        .highlight_symbol(">>");
+    f.render_stateful_widget(list, inner, &mut watch.state);
+
+    let help = Paragraph::new(t(
+        "Size, growth since first found and growth a minute  Quit (q)",
+    ));
+    let bottom = Rect::new(0, area.height, size.width, 1.min(size.height));
+    f.render_widget(help, bottom);
+    f.render_widget(Degrade(watch.caps), size);
+}
+
+// Growing targets stand out, gone ones are dimmed
+fn item<'a>(watched: &Watched, theme: &Theme) -> ListItem<'a> {
+    let rate = match watched.rate {
+        _ if watched.gone => t("gone").to_string(),
+        None => String::new(),
+        Some(rate) => tf("{}/min", &[&signed(rate as i64)]),
+    };
+    let text = format!(
+        "{:>9}  {:>10}  {:>14}  {}",
+        size::format_size(watched.size),
+        signed(watched.size as i64 - watched.first as i64),
+        rate,
+        watched.path.display()
+    );
+    let style = if watched.gone {
+        theme.dimmed
+    } else if watched.rate.is_some_and(|rate| rate >= 1.0) {
+        theme.warning
+    } else {
+        Default::default()
+    };
+    ListItem::new(text).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::Kind;
+    use crate::size::Stats;
+    use std::path::Path;
+
+    #[test]
+    fn tells_how_fast_targets_grow() {
+        let found = |sizes: &[(&str, u64)]| -> Vec<Entry> {
+            sizes
+                .iter()
+                .map(|(path, size)| {
+                    let stats = Stats {
+                        size: *size,
+                        modified: None,
+                        files: 0,
+                        shared: 0,
+                        skipped: vec![],
+                    };
+                    Entry::with_stats(path.into(), "/nowhere".into(), Kind::Rust, stats)
+                })
+                .collect()
+        };
+        let mut watch = Watch {
+            targets: vec![],
+            theme: Theme::default(),
+            caps: Caps::detect(),
+            scans: 0,
+            last: None,
+            interval: Duration::from_secs(30),
+            state: ListState::default(),
+        };
+        let start = Instant::now();
+        watch.update(start, found(&[("/a/target", 1000), ("/b/target", 500)]));
+        assert!(watch.targets.iter().all(|w| w.rate.is_none()));
+
+        let later = start + Duration::from_secs(30);
+        watch.update(later, found(&[("/b/target", 2500)]));
+        assert_eq!(watch.targets[0].path, Path::new("/b/target"));
+        assert_eq!(watch.targets[0].rate, Some(4000.0));
+        assert!(watch.targets[1].gone);
+        assert_eq!(watch.state.selected(), Some(0));
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    path::{Component, Path},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+static WSL: OnceLock<bool> = OnceLock::new();
+
+/// Whether this runs under the Windows Subsystem for Linux
+pub fn is_wsl() -> bool {
+    *WSL.get_or_init(|| {
+        cfg!(target_os = "linux")
+            && (std::env::var_os("WSL_DISTRO_NAME").is_some()
+                || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                    .is_ok_and(|release| release.to_lowercase().contains("microsoft")))
+    })
+}
+
+/// Whether `path` is on a Windows drive mounted into WSL, like `/mnt/c`.
+/// Linux trash there is a hidden folder Windows knows nothing about.
+pub fn is_windows_drive(path: &Path) -> bool {
+    if !is_wsl() {
+        return false;
+    }
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut components = path.components();
+    matches!(
+        (components.next(), components.next(), components.next()),
+        (Some(Component::RootDir), Some(Component::Normal(mnt)), Some(Component::Normal(drive)))
+            if mnt == "mnt"
+                && drive.len() == 1
+                && drive.to_str().is_some_and(|d| d.chars().all(|c| c.is_ascii_alphabetic()))
+    )
+}
+
+/// Whether folders can be sent to the Windows Recycle Bin, which takes
+/// PowerShell from the Windows side
+pub fn can_recycle() -> bool {
+    static POWERSHELL: OnceLock<bool> = OnceLock::new();
+    *POWERSHELL.get_or_init(|| {
+        Command::new("powershell.exe")
+            .args(["-NoProfile", "-NonInteractive", "-Command", "exit 0"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Send the file or folder at `path` on a Windows drive to the Recycle Bin
+#[tracing::instrument]
+pub fn recycle(path: &Path) -> std::io::Result<()> {
+    let output = Command::new("wslpath").arg("-w").arg(path).output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other("wslpath cannot convert the path"));
+    }
+    let windows = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let method = if path.is_dir() {
+        "DeleteDirectory"
+    } else {
+        "DeleteFile"
+    };
+    // Quotes are doubled in PowerShell's single quoted strings
+    let script = format!(
+        "Add-Type -AssemblyName Microsoft.VisualBasic; \
+         [Microsoft.VisualBasic.FileIO.FileSystem]::{method}('{}', 'OnlyErrorDialogs', 'SendToRecycleBin')",
+        windows.replace('\'', "''")
+    );
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = stderr.lines().find(|line| !line.trim().is_empty());
+    Err(std::io::Error::other(
+        error.unwrap_or("PowerShell failed").trim().to_string(),
+    ))
+}
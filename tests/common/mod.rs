@@ -0,0 +1,104 @@
+//! Project trees built in the temp folder for the tests to scan and delete
+
+// Every test file uses only some of these
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use rclin::scan::{self, Entry, Found};
+
+/// Folder of its own for a test, removed again when dropped
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new(name: &str) -> Fixture {
+        let root = std::env::temp_dir().join(format!("rclin-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        // Real path, the temp folder can be behind a symlink like on macOS
+        let root = root.canonicalize().unwrap();
+        Fixture { root }
+    }
+
+    pub fn path(&self, relative: &str) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Write `content` to `relative`, creating the folders above it
+    pub fn file(&self, relative: &str, content: &str) -> PathBuf {
+        let path = self.path(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn dir(&self, relative: &str) -> PathBuf {
+        let path = self.path(relative);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    /// Rust project in `relative` that has been built, with a lock file and
+    /// a binary in its target
+    pub fn built_crate(&self, relative: &str) -> PathBuf {
+        let name = Path::new(relative).file_name().unwrap().to_str().unwrap();
+        self.file(
+            &format!("{relative}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        );
+        self.file(&format!("{relative}/Cargo.lock"), "version = 3\n");
+        self.file(
+            &format!("{relative}/target/debug/{name}"),
+            &"\0".repeat(4096),
+        );
+        self.path(&format!("{relative}/target"))
+    }
+
+    /// Scan the whole fixture with `options` on a single thread. Returns the
+    /// targets found, sorted by path, and the folders that couldn't be read.
+    pub fn scan(&self, options: scan::Options) -> (Vec<Entry>, Vec<PathBuf>) {
+        let options = scan::Options {
+            threads: 1,
+            ..options
+        };
+        let (receiver, handle, _) = scan::spawn(vec![self.root.clone()], options);
+        let mut entries = vec![];
+        let mut unreadable = vec![];
+        for found in receiver {
+            match found {
+                Found::Entry(entry) => entries.push(*entry),
+                Found::Unreadable(path, _) => unreadable.push(path),
+            }
+        }
+        handle.join().unwrap().unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        (entries, unreadable)
+    }
+
+    /// Paths of the targets a scan with `options` finds, relative to the root
+    pub fn found(&self, options: scan::Options) -> Vec<String> {
+        let (entries, _) = self.scan(options);
+        entries
+            .iter()
+            .map(|e| {
+                let path = e.path.strip_prefix(&self.root).unwrap();
+                path.to_str().unwrap().replace('\\', "/")
+            })
+            .collect()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        // Permission tests lock `locked`, it couldn't be removed otherwise
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let locked = self.root.join("locked");
+            let _ = std::fs::set_permissions(locked, std::fs::Permissions::from_mode(0o755));
+        }
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use std::path::Path;
+
+use common::Fixture;
+use rclin::{
+    deleter::{Cleanup, Deleter, Finished, Job, Removal},
+    error::Error,
+    scan::{Entry, Options},
+};
+
+// Delete `entries` found by scanning `root` for good and wait until all are
+// done
+fn delete(root: &Path, entries: Vec<Entry>) -> Vec<Finished> {
+    clean(root, entries, Cleanup::Remove)
+}
+
+// Like `delete`, cleaning Rust targets up as `cleanup` says
+fn clean(root: &Path, entries: Vec<Entry>, cleanup: Cleanup) -> Vec<Finished> {
+    let mut deleter = Deleter::spawn(vec![root.to_path_buf()], vec![], None);
+    let jobs = entries
+        .into_iter()
+        .map(|entry| Job {
+            entry,
+            permanent: true,
+            cleanup: cleanup.clone(),
+            salvage: false,
+        })
+        .collect();
+    deleter.delete(jobs);
+    let mut finished = vec![];
+    while deleter.pending() > 0 {
+        finished.extend(deleter.finished(true));
+    }
+    finished
+}
+
+fn refused(finished: &Finished) -> bool {
+    matches!(finished.result, Err(Error::Refused { .. }))
+}
+
+#[test]
+fn deletes_targets_and_nothing_else() {
+    let fx = Fixture::new("delete");
+    let app = fx.built_crate("app");
+    let lib = fx.built_crate("lib");
+    let (entries, _) = fx.scan(Options::default());
+
+    let finished = delete(&fx.root, entries);
+    assert_eq!(finished.len(), 2);
+    for done in &finished {
+        assert_eq!(done.result.as_ref().ok(), Some(&Removal::Deleted));
+    }
+    assert!(!app.exists() && !lib.exists());
+    assert!(fx.path("app/Cargo.toml").is_file());
+    assert!(fx.path("lib/Cargo.lock").is_file());
+}
+
+#[test]
+fn cleans_only_the_picked_profiles() {
+    let fx = Fixture::new("profiles");
+    let target = fx.built_crate("app");
+    fx.dir("app/target/debug/deps");
+    fx.file("app/target/release/deps/app-0123456789abcdef", "binary");
+    fx.file(
+        "app/target/x86_64-unknown-linux-gnu/debug/deps/app",
+        "binary",
+    );
+    fx.file("app/target/doc/app/index.html", "docs");
+    let (entries, _) = fx.scan(Options::default());
+
+    let profiles = vec!["debug".to_string(), "doc".to_string()];
+    let finished = clean(&fx.root, entries, Cleanup::Profiles(profiles));
+    assert_eq!(finished[0].result.as_ref().ok(), Some(&Removal::Cleaned));
+    assert!(!target.join("debug").exists() && !target.join("doc").exists());
+    assert!(!target.join("x86_64-unknown-linux-gnu/debug").exists());
+    assert!(target.join("release/deps/app-0123456789abcdef").is_file());
+}
+
+#[test]
+fn keeps_excluded_folders_inside_targets() {
+    let fx = Fixture::new("kept");
+    fx.built_crate("app");
+    fx.file("app/target/mnt/data", "keep me");
+    let options = Options {
+        excludes: vec![fx.path("app/target/mnt")],
+        ..Options::default()
+    };
+    let (entries, _) = fx.scan(options);
+    assert_eq!(entries[0].kept, [fx.path("app/target/mnt")]);
+
+    let finished = delete(&fx.root, entries);
+    assert!(finished[0].result.is_ok());
+    assert!(fx.path("app/target/mnt/data").is_file());
+    assert!(!fx.path("app/target/debug").exists());
+}
+
+#[test]
+fn refuses_targets_changed_since_the_scan() {
+    let fx = Fixture::new("changed");
+    let gone = fx.built_crate("gone");
+    let orphan = fx.built_crate("orphan");
+    let (entries, _) = fx.scan(Options::default());
+    std::fs::remove_dir_all(&gone).unwrap();
+    std::fs::remove_file(fx.path("orphan/Cargo.toml")).unwrap();
+
+    let finished = delete(&fx.root, entries);
+    assert!(finished.iter().all(refused));
+    // Without a manifest it's not a target anymore, whatever it holds
+    assert!(orphan.join("debug/orphan").is_file());
+}
+
+#[cfg(unix)]
+#[test]
+fn refuses_targets_swapped_for_symlinks() {
+    let fx = Fixture::new("swapped");
+    let target = fx.built_crate("app");
+    let precious = fx.file("precious/data", "important");
+    let (entries, _) = fx.scan(Options::default());
+    std::fs::remove_dir_all(&target).unwrap();
+    std::os::unix::fs::symlink(fx.path("precious"), &target).unwrap();
+
+    let finished = delete(&fx.root, entries);
+    assert!(refused(&finished[0]));
+    assert!(precious.is_file());
+}
+
+#[cfg(unix)]
+#[test]
+fn deletes_targets_of_projects_named_in_no_encoding() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let fx = Fixture::new("encoding");
+    fx.built_crate("app");
+    let project = fx.root.join(OsStr::from_bytes(b"caf\xe9"));
+    std::fs::rename(fx.path("app"), &project).unwrap();
+    let (entries, unreadable) = fx.scan(Options::default());
+    assert!(unreadable.is_empty());
+    assert_eq!(entries[0].project, project);
+
+    let finished = delete(&fx.root, entries);
+    assert_eq!(finished[0].result.as_ref().ok(), Some(&Removal::Deleted));
+    assert!(!project.join("target").exists());
+    assert!(project.join("Cargo.toml").is_file());
+}
+
+#[test]
+fn refuses_targets_holding_a_scanned_folder() {
+    let fx = Fixture::new("protected");
+    // A crafted config points the target at the folder being scanned
+    fx.built_crate("app");
+    fx.file(
+        "app/.cargo/config.toml",
+        "[build]\ntarget-dir = \"inside\"\n",
+    );
+    fx.dir("app/inside/scanned");
+    let root = fx.path("app/inside/scanned");
+    let entry = Entry::new(
+        fx.path("app/inside"),
+        fx.path("app"),
+        rclin::scan::Kind::Rust,
+        &|_: &Path| false,
+    );
+
+    let finished = delete(&root, vec![entry]);
+    assert!(matches!(
+        finished[0].result,
+        Err(Error::Refused { reason, .. }) if reason.contains("scanned folder")
+    ));
+    assert!(root.is_dir());
+}
+
+#[test]
+fn leaves_untagged_target_dirs_outside_the_project_alone() {
+    let fx = Fixture::new("outside");
+    // A cloned repo pointing its target at a folder of the user
+    fx.built_crate("repo");
+    fx.file(
+        "repo/.cargo/config.toml",
+        "[build]\ntarget-dir = \"../documents\"\n",
+    );
+    let precious = fx.file("documents/thesis.txt", "years of work");
+    assert_eq!(fx.found(Options::default()), Vec::<String>::new());
+
+    let entry = Entry::new(
+        fx.path("documents"),
+        fx.path("repo"),
+        rclin::scan::Kind::Rust,
+        &|_: &Path| false,
+    );
+    let finished = delete(&fx.root, vec![entry]);
+    assert!(matches!(
+        finished[0].result,
+        Err(Error::Refused { reason, .. }) if reason.contains("not tagged")
+    ));
+    assert!(precious.is_file());
+}
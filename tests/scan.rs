@@ -0,0 +1,180 @@
+mod common;
+
+use common::Fixture;
+use rclin::scan::{self, Kind, Options};
+
+#[test]
+fn finds_built_projects() {
+    let fx = Fixture::new("built");
+    fx.built_crate("app");
+    fx.built_crate("deep/down/lib");
+    // Hidden folders are scanned like any other
+    fx.built_crate(".hidden/tool");
+
+    assert_eq!(
+        fx.found(Options::default()),
+        [".hidden/tool/target", "app/target", "deep/down/lib/target"]
+    );
+    let (entries, _) = fx.scan(Options::default());
+    let app = &entries[1];
+    assert_eq!(app.kind, Kind::Rust);
+    assert_eq!(app.project, fx.path("app"));
+    assert_eq!(app.names, ["app"]);
+    assert!(app.size >= 4096);
+    assert_eq!(app.files, 1);
+}
+
+#[test]
+fn needs_a_manifest() {
+    let fx = Fixture::new("manifest");
+    fx.dir("plain/target/debug");
+    // A symlinked manifest isn't followed
+    fx.file("real/Cargo.toml", "[package]\nname = \"real\"\n");
+    fx.dir("linked/target");
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(fx.path("real/Cargo.toml"), fx.path("linked/Cargo.toml")).unwrap();
+
+    assert!(fx.found(Options::default()).is_empty());
+}
+
+#[test]
+fn skips_unbuilt_projects_when_asked() {
+    let fx = Fixture::new("unbuilt");
+    fx.built_crate("built");
+    fx.file("fresh/Cargo.toml", "[package]\nname = \"fresh\"\n");
+    fx.dir("fresh/target");
+
+    assert_eq!(
+        fx.found(Options::default()),
+        ["built/target", "fresh/target"]
+    );
+    let options = Options {
+        skip_unbuilt: true,
+        ..Options::default()
+    };
+    assert_eq!(fx.found(options), ["built/target"]);
+}
+
+#[test]
+fn finds_other_kinds_when_asked() {
+    let fx = Fixture::new("kinds");
+    fx.built_crate("app");
+    fx.file("web/package.json", "{}");
+    fx.file("web/node_modules/left-pad/package.json", "{}");
+    fx.file("web/node_modules/left-pad/index.js", "");
+
+    // Packages in node_modules aren't projects of their own
+    let options = Options {
+        kinds: vec![Kind::Rust, Kind::Node],
+        ..Options::default()
+    };
+    assert_eq!(fx.found(options), ["app/target", "web/node_modules"]);
+    assert_eq!(fx.found(Options::default()), ["app/target"]);
+}
+
+#[test]
+fn leaves_out_excluded_folders() {
+    let fx = Fixture::new("excluded");
+    fx.built_crate("app");
+    fx.built_crate("vendor/dep");
+    fx.built_crate("backup.bak/old");
+    fx.built_crate("ignored/thing");
+    fx.file(".gitignore", "ignored/\n");
+
+    let options = Options {
+        excludes: vec![fx.path("vendor")],
+        globs: vec!["*.bak".to_string()],
+        ..Options::default()
+    };
+    assert_eq!(fx.found(options), ["app/target"]);
+
+    // Ignore files are only followed by default
+    let options = Options {
+        gitignore: false,
+        ..Options::default()
+    };
+    assert_eq!(
+        fx.found(options),
+        [
+            "app/target",
+            "backup.bak/old/target",
+            "ignored/thing/target",
+            "vendor/dep/target"
+        ]
+    );
+}
+
+#[test]
+fn stops_at_max_depth() {
+    let fx = Fixture::new("depth");
+    fx.built_crate("a");
+    fx.built_crate("x/y/b");
+
+    let options = Options {
+        max_depth: 2,
+        ..Options::default()
+    };
+    assert_eq!(fx.found(options), ["a/target"]);
+}
+
+#[test]
+fn stops_when_cancelled() {
+    let fx = Fixture::new("cancel");
+    for i in 0..200 {
+        fx.built_crate(&format!("deep/{i}/down/p{i}"));
+    }
+    let all = fx.scan(Options::default()).0.len();
+    assert_eq!(all, 200);
+
+    let (receiver, handle, progress) = scan::spawn(vec![fx.root.clone()], Options::default());
+    progress.cancel();
+    // Every thread stops, none is left waiting for more folders, and what
+    // was found before is still sent
+    assert!(handle.join().unwrap().is_ok());
+    assert!(receiver.iter().count() < all);
+}
+
+#[cfg(unix)]
+#[test]
+fn survives_symlink_traps() {
+    use std::os::unix::fs::symlink;
+
+    let fx = Fixture::new("symlinks");
+    fx.built_crate("app");
+    fx.built_crate("elsewhere/lib");
+    // Loops back to the root, and a second way into the same project
+    symlink(&fx.root, fx.path("app/loop")).unwrap();
+    symlink(fx.path("elsewhere"), fx.path("shortcut")).unwrap();
+    symlink(fx.path("missing"), fx.path("dangling")).unwrap();
+
+    assert_eq!(
+        fx.found(Options::default()),
+        ["app/target", "elsewhere/lib/target"]
+    );
+    // Followed, every folder is still scanned once
+    let options = Options {
+        follow_symlinks: true,
+        ..Options::default()
+    };
+    assert_eq!(fx.found(options), ["app/target", "elsewhere/lib/target"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn reports_folders_it_cannot_read() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fx = Fixture::new("denied");
+    fx.built_crate("app");
+    fx.built_crate("locked/secret");
+    let locked = fx.path("locked");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    // Root reads it anyway, nothing to test
+    if std::fs::read_dir(&locked).is_ok() {
+        return;
+    }
+
+    let (entries, unreadable) = fx.scan(Options::default());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(unreadable, [locked]);
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use std::path::Path;
+
+use common::Fixture;
+use rclin::size;
+
+#[cfg(unix)]
+#[test]
+fn counts_hard_links_once() {
+    let fx = Fixture::new("links");
+    let target = fx.built_crate("app");
+    let binary = fx.path("app/target/debug/app");
+    std::fs::hard_link(&binary, fx.path("app/target/debug/deps-app")).unwrap();
+    let usage = |path: &Path| size::usage(&std::fs::metadata(path).unwrap());
+    let alone = size::dir_stats(&target);
+    assert_eq!(alone.size, usage(&target.join("debug")) + usage(&binary));
+    assert_eq!(alone.files, 2);
+    assert_eq!(alone.shared, 0);
+
+    // Linked from outside the target too, deleting it won't free that
+    std::fs::hard_link(&binary, fx.path("app/app")).unwrap();
+    let linked = size::dir_stats(&target);
+    assert_eq!(linked.size, alone.size);
+    assert_eq!(linked.shared, usage(&binary));
+}